@@ -20,6 +20,22 @@ export interface paths {
     patch?: never
     trace?: never
   }
+  "/api/categories/suggestions": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["suggest_expense_categories_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
   "/api/friend-requests": {
     parameters: {
       query?: never
@@ -110,12 +126,108 @@ export interface paths {
     get: operations["get_member_handler"]
     put?: never
     post?: never
-    delete?: never
+    delete: operations["delete_member_handler"]
     options?: never
     head?: never
     patch: operations["update_member_handler"]
     trace?: never
   }
+  "/api/members/me/analytics/categories": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["get_member_category_analytics_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/members/me/export": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["export_member_data_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/notifications": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["list_notifications_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/notifications/preferences": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["get_notification_preferences_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch: operations["update_notification_preferences_handler"]
+    trace?: never
+  }
+  "/api/notifications/read-all": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post: operations["mark_all_notifications_read_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/notifications/{notification_id}/read": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post: operations["mark_notification_read_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
   "/api/pools": {
     parameters: {
       query?: never
@@ -148,6 +260,54 @@ export interface paths {
     patch?: never
     trace?: never
   }
+  "/api/pools/{pool_id}/activity": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["get_pool_activity_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/analytics/categories": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["get_pool_category_analytics_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/analytics/timeseries": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["get_pool_time_series_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
   "/api/pools/{pool_id}/balances": {
     parameters: {
       query?: never
@@ -164,6 +324,86 @@ export interface paths {
     patch?: never
     trace?: never
   }
+  "/api/pools/{pool_id}/budgets": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["list_pool_budgets_handler"]
+    put?: never
+    post: operations["create_pool_budget_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/budgets/status": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["get_pool_budget_status_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/budgets/{budget_id}": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post?: never
+    delete: operations["delete_pool_budget_handler"]
+    options?: never
+    head?: never
+    patch: operations["update_pool_budget_handler"]
+    trace?: never
+  }
+  "/api/pools/{pool_id}/categories": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["list_pool_categories_handler"]
+    put?: never
+    post: operations["create_pool_category_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/categories/{category_id}": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post?: never
+    delete: operations["delete_pool_category_handler"]
+    options?: never
+    head?: never
+    patch: operations["update_pool_category_handler"]
+    trace?: never
+  }
   "/api/pools/{pool_id}/default-splits": {
     parameters: {
       query?: never
@@ -180,6 +420,38 @@ export interface paths {
     patch: operations["modify_default_splits_handler"]
     trace?: never
   }
+  "/api/pools/{pool_id}/email-preferences": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["get_pool_email_preferences_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch: operations["update_pool_email_preferences_handler"]
+    trace?: never
+  }
+  "/api/pools/{pool_id}/events": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["pool_events_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
   "/api/pools/{pool_id}/expenses": {
     parameters: {
       query?: never
@@ -212,55 +484,55 @@ export interface paths {
     patch: operations["update_expense_handler"]
     trace?: never
   }
-  "/api/pools/{pool_id}/members": {
+  "/api/pools/{pool_id}/expenses/{expense_id}/attachments": {
     parameters: {
       query?: never
       header?: never
       path?: never
       cookie?: never
     }
-    get: operations["list_members_of_pool_handler"]
+    get: operations["list_expense_attachments_handler"]
     put?: never
-    post: operations["add_friend_to_pool_handler"]
+    post: operations["upload_expense_attachment_handler"]
     delete?: never
     options?: never
     head?: never
     patch?: never
     trace?: never
   }
-  "/api/pools/{pool_id}/members/{member_id}": {
+  "/api/pools/{pool_id}/expenses/{expense_id}/attachments/{attachment_id}": {
     parameters: {
       query?: never
       header?: never
       path?: never
       cookie?: never
     }
-    get?: never
+    get: operations["download_expense_attachment_handler"]
     put?: never
     post?: never
-    delete: operations["remove_friend_from_pool_handler"]
+    delete: operations["delete_expense_attachment_handler"]
     options?: never
     head?: never
     patch?: never
     trace?: never
   }
-  "/api/pools/{pool_id}/memberships": {
+  "/api/pools/{pool_id}/expenses/{expense_id}/comments": {
     parameters: {
       query?: never
       header?: never
       path?: never
       cookie?: never
     }
-    get?: never
+    get: operations["list_expense_comments_handler"]
     put?: never
-    post: operations["create_pool_membership_handler"]
+    post: operations["create_expense_comment_handler"]
     delete?: never
     options?: never
     head?: never
     patch?: never
     trace?: never
   }
-  "/api/pools/{pool_id}/settle-up": {
+  "/api/pools/{pool_id}/expenses/{expense_id}/comments/{comment_id}": {
     parameters: {
       query?: never
       header?: never
@@ -270,29 +542,29 @@ export interface paths {
     get?: never
     put?: never
     post?: never
-    delete?: never
+    delete: operations["delete_expense_comment_handler"]
     options?: never
     head?: never
-    patch: operations["settle_up_pool_handler"]
+    patch: operations["edit_expense_comment_handler"]
     trace?: never
   }
-  "/api/rules": {
+  "/api/pools/{pool_id}/expenses/{expense_id}/restore": {
     parameters: {
       query?: never
       header?: never
       path?: never
       cookie?: never
     }
-    get: operations["list_expense_category_rules_handler"]
+    get?: never
     put?: never
-    post: operations["create_expense_category_rule_handler"]
-    delete: operations["delete_expense_category_rule_handler"]
+    post: operations["restore_expense_handler"]
+    delete?: never
     options?: never
     head?: never
     patch?: never
     trace?: never
   }
-  "/api/signup": {
+  "/api/pools/{pool_id}/expenses/{expense_id}/tags": {
     parameters: {
       query?: never
       header?: never
@@ -300,18 +572,382 @@ export interface paths {
       cookie?: never
     }
     get?: never
-    put?: never
-    post: operations["signup_handler"]
+    put: operations["set_expense_tags_handler"]
+    post?: never
     delete?: never
     options?: never
     head?: never
     patch?: never
     trace?: never
   }
-}
+  "/api/pools/{pool_id}/export": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["export_pool_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/import/csv": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post: operations["import_pool_expenses_csv_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/import/splitwise": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post: operations["import_pool_expenses_splitwise_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/members": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["list_members_of_pool_handler"]
+    put?: never
+    post: operations["add_friend_to_pool_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/members/{member_id}": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post?: never
+    delete: operations["remove_friend_from_pool_handler"]
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/memberships": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post: operations["create_pool_membership_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/settle-up": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch: operations["settle_up_pool_handler"]
+    trace?: never
+  }
+  "/api/pools/{pool_id}/tags": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["list_pool_tags_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/tags/totals": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["get_pool_tag_totals_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/trash": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["list_pool_trash_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/webhooks": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["list_pool_webhooks_handler"]
+    put?: never
+    post: operations["create_pool_webhook_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/webhooks/{webhook_id}": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post?: never
+    delete: operations["delete_pool_webhook_handler"]
+    options?: never
+    head?: never
+    patch: operations["update_pool_webhook_handler"]
+    trace?: never
+  }
+  "/api/pools/{pool_id}/webhooks/{webhook_id}/deliveries": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["list_webhook_deliveries_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/pools/{pool_id}/webhooks/{webhook_id}/deliveries/{delivery_id}/retry": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post: operations["retry_webhook_delivery_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/rules": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["list_expense_category_rules_handler"]
+    put?: never
+    post: operations["create_expense_category_rule_handler"]
+    delete: operations["delete_expense_category_rule_handler"]
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/rules/apply": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post: operations["apply_expense_category_rules_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/rules/preview": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post: operations["preview_expense_category_rule_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/rules/suggestions": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["suggest_expense_category_rules_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/rules/{rule_id}": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post?: never
+    delete: operations["delete_expense_category_rule_by_id_handler"]
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/search": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get: operations["search_expenses_handler"]
+    put?: never
+    post?: never
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+  "/api/signup": {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    get?: never
+    put?: never
+    post: operations["signup_handler"]
+    delete?: never
+    options?: never
+    head?: never
+    patch?: never
+    trace?: never
+  }
+}
 export type webhooks = Record<string, never>
 export interface components {
   schemas: {
+    AddExpenseResult: components["schemas"]["Expense"] & {
+      applied_rule?: null | components["schemas"]["ExpenseCategoryRule"]
+      /** @description Whether the expense pushed any of the pool's budgets over for the
+       *     period it was incurred in. */
+      budget_exceeded: boolean
+      /** @description The budgets the expense pushed over for that period. Budgets that were
+       *     already over before it was added aren't included. */
+      exceeded_budgets: components["schemas"]["BudgetStatus"][]
+      tags: string[]
+    }
+    ApplyRulesInput: {
+      /**
+       * Format: uuid
+       * @description Only re-categorize expenses in this pool.
+       */
+      pool_id?: string | null
+      /** @description Only apply these rules. Defaults to all of the caller's rules. */
+      rule_ids?: string[] | null
+    }
+    ApplyRulesSummary: {
+      /** @description Number of expenses moved into each category. */
+      by_category: components["schemas"]["CategoryChangeCount"][]
+      changed_expenses: number
+    }
+    AttachmentUpload: {
+      /** Format: binary */
+      file: string
+    }
     AuthResult:
       | {
           /** Format: date-time */
@@ -321,264 +957,3019 @@ export interface components {
           is_authenticated: boolean
           token: string
         }
-      | {
-          /** Format: date-time */
-          expires_at?: string | null
-          /** Format: uuid */
-          id?: string | null
-          is_authenticated: boolean
-          token?: string | null
+      | {
+          /** Format: date-time */
+          expires_at?: string | null
+          /** Format: uuid */
+          id?: string | null
+          is_authenticated: boolean
+          token?: string | null
+        }
+    Balance: {
+      /** Format: double */
+      amount: number
+      direction: components["schemas"]["PaymentDirection"]
+      /** Format: uuid */
+      member_id: string
+    }
+    Budget: {
+      /** Format: double */
+      amount: number
+      category?: null | components["schemas"]["ExpenseCategory"]
+      /** Format: uuid */
+      created_by_member_id: string
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      period: components["schemas"]["BudgetPeriod"]
+      /** Format: uuid */
+      pool_id: string
+      /** Format: date-time */
+      updated_at: string
+    }
+    BudgetInput: {
+      /** Format: double */
+      amount: number
+      category?: null | components["schemas"]["ExpenseCategory"]
+      period?: components["schemas"]["BudgetPeriod"]
+    }
+    /** @enum {string} */
+    BudgetPeriod: "Weekly" | "Monthly" | "Yearly"
+    BudgetStatus: components["schemas"]["Budget"] & {
+      is_exceeded: boolean
+      is_projected_to_exceed: boolean
+      /**
+       * Format: date
+       * @description The last day of the period.
+       */
+      period_end: string
+      /** Format: date */
+      period_start: string
+      /**
+       * Format: double
+       * @description What will have been spent by the end of the period if spending keeps
+       *     its pace so far.
+       */
+      projected: number
+      /**
+       * Format: double
+       * @description Negative once the budget is exceeded.
+       */
+      remaining: number
+      /** Format: double */
+      spent: number
+    }
+    CategoryAnalytics: {
+      /** @description Largest share first. */
+      categories: components["schemas"]["CategoryTotal"][]
+      /** Format: date */
+      since?: string | null
+      /** Format: double */
+      total_paid: number
+      /** Format: double */
+      total_share: number
+      /** Format: date */
+      until?: string | null
+    }
+    CategoryChangeCount: {
+      category: components["schemas"]["ExpenseCategory"]
+      count: number
+    }
+    CategorySuggestion: {
+      category: components["schemas"]["ExpenseCategory"]
+      /**
+       * Format: double
+       * @description Share of the probability mass, between 0 and 1.
+       */
+      score: number
+    }
+    CategoryTotal: {
+      category: components["schemas"]["ExpenseCategory"]
+      /** Format: int64 */
+      expense_count: number
+      /**
+       * Format: double
+       * @description The full amount of the expenses the member paid for.
+       */
+      paid: number
+      /**
+       * Format: double
+       * @description The member's share of the expenses, whoever paid.
+       */
+      share: number
+    }
+    /** @description Which CSV header holds each expense field. Category and split columns
+     *     are optional; rows without a category are left to the payer's rules and
+     *     rows without a split use the pool's default split. */
+    ColumnMapping: {
+      amount: string
+      category?: string | null
+      date: string
+      /** @description A chrono format string for the date column. Defaults to `%Y-%m-%d`. */
+      date_format?: string | null
+      name: string
+      payer: string
+      /** @description Shares written as `member=60%; member=40%` or `member=30; member=54.20`,
+       *     where members are referenced like the payer. */
+      split?: string | null
+    }
+    CreatedWebhook: components["schemas"]["Webhook"] & {
+      /** @description Used to sign deliveries. It's only ever returned here, so keep it. */
+      secret: string
+    }
+    CsvImportInput: {
+      /** @description The CSV file's contents, including its header row. */
+      csv: string
+      /** @description Validate and preview the import without saving anything. */
+      dry_run?: boolean
+      mapping: components["schemas"]["ColumnMapping"]
+    }
+    CsvImportResult: {
+      dry_run: boolean
+      errors: components["schemas"]["ImportRowError"][]
+      imported: number
+      rows: components["schemas"]["CsvImportRow"][]
+    }
+    CsvImportRow: {
+      /** Format: double */
+      amount: number
+      /**
+       * Format: uuid
+       * @description The payer's rule that categorized a row without a category.
+       */
+      applied_rule_id?: string | null
+      category: components["schemas"]["ExpenseCategory"]
+      /** Format: uuid */
+      custom_category_id?: string | null
+      /**
+       * Format: uuid
+       * @description The created expense. Always empty on a dry run.
+       */
+      expense_id?: string | null
+      /** Format: date */
+      incurred_on: string
+      line_items: components["schemas"]["ImportLineItem"][]
+      name: string
+      /** Format: uuid */
+      paid_by_member_id: string
+      row: number
+      split_method: components["schemas"]["SplitMethod"]
+    }
+    DeleteMemberInput: {
+      /** @description The member's current password, to confirm the deletion. */
+      password: string
+    }
+    Expense: {
+      /** Format: double */
+      amount: number
+      /** @description Whether one of the payer's rules picked the category. */
+      categorized_by_rule: boolean
+      category: components["schemas"]["ExpenseCategory"]
+      /** Format: uuid */
+      custom_category_id?: string | null
+      /** Format: date-time */
+      deleted_at?: string | null
+      description?: string | null
+      /** Format: uuid */
+      id: string
+      /** Format: date */
+      incurred_on: string
+      /** Format: date-time */
+      inserted_at: string
+      is_settled: boolean
+      name: string
+      notes?: string | null
+      /** Format: uuid */
+      paid_by_member_id: string
+      /** Format: uuid */
+      pool_id: string
+      split_method: components["schemas"]["SplitMethod"]
+      /** Format: date-time */
+      updated_at: string
+    }
+    ExpenseAttachment: {
+      content_type: string
+      /** Format: uuid */
+      expense_id: string
+      file_name: string
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      /** Format: uuid */
+      pool_id: string
+      /** Format: int64 */
+      size_bytes: number
+      /** Format: date-time */
+      updated_at: string
+      /** Format: uuid */
+      uploaded_by_member_id: string
+    }
+    /** @enum {string} */
+    ExpenseCategory:
+      | "FoodDining"
+      | "Groceries"
+      | "Transportation"
+      | "HousingRent"
+      | "Utilities"
+      | "Healthcare"
+      | "Entertainment"
+      | "Shopping"
+      | "Education"
+      | "Travel"
+      | "PersonalCare"
+      | "Fitness"
+      | "Subscriptions"
+      | "BillsPayments"
+      | "BusinessExpenses"
+      | "Investments"
+      | "Insurance"
+      | "Gifts"
+      | "Charity"
+      | "Miscellaneous"
+      | "HomeHouseholdSupplies"
+      | "Pets"
+      | "Taxes"
+      | "Childcare"
+      | "ProfessionalServices"
+    ExpenseCategoryRule: {
+      category: components["schemas"]["ExpenseCategory"]
+      /** Format: uuid */
+      custom_category_id?: string | null
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      match_type: components["schemas"]["RuleMatchType"]
+      /** Format: double */
+      max_amount?: number | null
+      /** Format: uuid */
+      member_id: string
+      /** Format: double */
+      min_amount?: number | null
+      /** Format: uuid */
+      pool_id?: string | null
+      /** Format: int32 */
+      priority: number
+      rule: string
+      /** Format: date-time */
+      updated_at: string
+    }
+    ExpenseComment: {
+      /** Format: uuid */
+      author_member_id: string
+      body: string
+      /** Format: date-time */
+      deleted_at?: string | null
+      /** Format: date-time */
+      edited_at?: string | null
+      /** Format: uuid */
+      expense_id: string
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      is_deleted: boolean
+      is_edited: boolean
+      /** Format: uuid */
+      pool_id: string
+      /** Format: date-time */
+      updated_at: string
+    }
+    ExpenseCommentInput: {
+      body: string
+    }
+    ExpenseEvent: {
+      action: components["schemas"]["ExpenseEventAction"]
+      /** Format: uuid */
+      actor_member_id: string
+      after_snapshot?: null | components["schemas"]["ExpenseWithLineItems"]
+      before_snapshot?: null | components["schemas"]["ExpenseWithLineItems"]
+      /** Format: uuid */
+      expense_id: string
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      /** Format: uuid */
+      pool_id: string
+    }
+    /** @enum {string} */
+    ExpenseEventAction:
+      | "Created"
+      | "Updated"
+      | "Deleted"
+      | "Settled"
+      | "Restored"
+    ExpenseInput: {
+      /** Format: double */
+      amount: number
+      auto_categorize?: boolean
+      category?: null | components["schemas"]["ExpenseCategory"]
+      /**
+       * Format: uuid
+       * @description A custom category of the pool. Takes precedence over `category`,
+       *     which is then set to the custom category's parent.
+       */
+      custom_category_id?: string | null
+      description?: string | null
+      /** Format: date */
+      incurred_on?: string | null
+      line_items: components["schemas"]["ExpenseLineItem"][]
+      name: string
+      notes?: string | null
+      /** Format: uuid */
+      paid_by_member_id: string
+      /** Format: uuid */
+      pool_id: string
+      split_method: components["schemas"]["SplitMethod"]
+      /** @description Free-form tags. Tags the pool hasn't used before are created. */
+      tags?: string[]
+    }
+    ExpenseLineItem: {
+      /** Format: double */
+      amount: number
+      /** Format: uuid */
+      debtor_member_id: string
+      /** Format: uuid */
+      expense_id: string
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      is_settled: boolean
+      /** Format: date-time */
+      updated_at: string
+    }
+    ExpenseTagsInput: {
+      tags: string[]
+    }
+    ExpenseWithLineItems: {
+      /** Format: double */
+      amount: number
+      categorized_by_rule: boolean
+      category: components["schemas"]["ExpenseCategory"]
+      /** Format: uuid */
+      custom_category_id?: string | null
+      /** Format: date-time */
+      deleted_at?: string | null
+      description?: string | null
+      /** Format: uuid */
+      id: string
+      /** Format: date */
+      incurred_on: string
+      /** Format: date-time */
+      inserted_at: string
+      is_settled: boolean
+      line_items: components["schemas"]["ExpenseLineItem"][]
+      name: string
+      notes?: string | null
+      /** Format: uuid */
+      paid_by_member_id: string
+      /** Format: uuid */
+      pool_id: string
+      split_method: components["schemas"]["SplitMethod"]
+      /** Format: date-time */
+      updated_at: string
+    }
+    FriendRequestInput: {
+      friend_email: string
+    }
+    FriendRequestsList: {
+      direction: components["schemas"]["FriendshipDirection"]
+      member: components["schemas"]["Member"]
+    }
+    Friendship: {
+      /** Format: uuid */
+      friend_member_id: string
+      /** Format: date-time */
+      inserted_at: string
+      /** Format: uuid */
+      inviting_member_id: string
+      status: components["schemas"]["FriendshipStatus"]
+      /** Format: date-time */
+      updated_at: string
+    }
+    /** @enum {string} */
+    FriendshipDirection: "inbound" | "outbound"
+    /** @enum {string} */
+    FriendshipStatus: "Pending" | "Accepted"
+    ImportLineItem: {
+      /** Format: double */
+      amount: number
+      /** Format: uuid */
+      debtor_member_id: string
+    }
+    ImportRowError: {
+      column?: string | null
+      message: string
+      /** @description The row's line in the file, counting the header as line 1. */
+      row: number
+    }
+    LoginInput: {
+      email: string
+      password: string
+    }
+    Member: {
+      bio?: string | null
+      /**
+       * Format: date-time
+       * @description Set when the member deleted their account. Their personal details
+       *     have been cleared, but they stay in their pools' ledgers.
+       */
+      deleted_at?: string | null
+      email: string
+      first_name: string
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      is_placeholder: boolean
+      last_name: string
+      /** Format: date-time */
+      updated_at: string
+      venmo_handle?: string | null
+    }
+    MemberChangeset: {
+      bio?: string | null
+      email?: string | null
+      first_name?: string | null
+      last_name?: string | null
+      venmo_handle?: string | null
+    }
+    /** @description Everything stored about a member, for the personal data export. */
+    MemberDataExport: {
+      attachments: components["schemas"]["ExpenseAttachment"][]
+      budgets: components["schemas"]["Budget"][]
+      category_rules: components["schemas"]["ExpenseCategoryRule"][]
+      comments: components["schemas"]["ExpenseComment"][]
+      expense_events: components["schemas"]["ExpenseEvent"][]
+      /** @description Expenses the member paid or has a share of, including ones in a
+       *     pool's trash. */
+      expenses: components["schemas"]["ExpenseWithLineItems"][]
+      /** Format: date-time */
+      exported_at: string
+      friendships: components["schemas"]["Friendship"][]
+      member: components["schemas"]["Member"]
+      notification_preferences: components["schemas"]["NotificationPreferences"]
+      notifications: components["schemas"]["Notification"][]
+      /** @description Only pools the member changed their email preferences for. */
+      pool_email_preferences: components["schemas"]["PoolEmailPreferences"][]
+      pool_memberships: components["schemas"]["PoolMembership"][]
+      pools: components["schemas"]["Pool"][]
+      /** Format: int32 */
+      version: number
+    }
+    MemberIdSplitPercentage: {
+      /** Format: uuid */
+      member_id: string
+      /** Format: double */
+      split_percentage: number
+    }
+    MemberShare: {
+      /** Format: uuid */
+      member_id: string
+      /** Format: double */
+      share: number
+    }
+    ModifyDefaultSplitInput: {
+      default_split_percentages: components["schemas"]["MemberIdSplitPercentage"][]
+    }
+    NewExpenseCategoryRule: {
+      category: components["schemas"]["ExpenseCategory"]
+      /**
+       * Format: uuid
+       * @description A custom category of the rule's pool. `category` is then taken from
+       *     the custom category's parent.
+       */
+      custom_category_id?: string | null
+      match_type?: components["schemas"]["RuleMatchType"]
+      /** Format: double */
+      max_amount?: number | null
+      /** Format: double */
+      min_amount?: number | null
+      /**
+       * Format: uuid
+       * @description Limits the rule to expenses in this pool. Rules without a pool apply
+       *     everywhere.
+       */
+      pool_id?: string | null
+      /**
+       * Format: int32
+       * @description Higher priorities win when several rules match.
+       */
+      priority?: number
+      rule: string
+    }
+    Notification: {
+      /** Format: uuid */
+      actor_member_id?: string | null
+      /** @description Details for showing the notification, like the pool's name or the
+       *     member's share of an expense, as they were when it was sent. */
+      data: unknown
+      /** Format: uuid */
+      expense_id?: string | null
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      kind: components["schemas"]["NotificationKind"]
+      /** Format: uuid */
+      member_id: string
+      /** Format: uuid */
+      pool_id?: string | null
+      /** Format: date-time */
+      read_at?: string | null
+      /** Format: date-time */
+      updated_at: string
+    }
+    /** @enum {string} */
+    NotificationKind:
+      | "FriendRequest"
+      | "PoolInvitation"
+      | "ExpenseAdded"
+      | "Settlement"
+    NotificationList: {
+      notifications: components["schemas"]["Notification"][]
+      /**
+       * Format: int64
+       * @description Across all of the member's notifications, not just this page.
+       */
+      unread_count: number
+    }
+    /** @description Which kinds of notification a member gets. Members who've never changed
+     *     them get everything. */
+    NotificationPreferences: {
+      expenses: boolean
+      friend_requests: boolean
+      pool_invitations: boolean
+      settlements: boolean
+    }
+    NotificationPreferencesChangeset: {
+      expenses?: boolean | null
+      friend_requests?: boolean | null
+      pool_invitations?: boolean | null
+      settlements?: boolean | null
+    }
+    /** @description One page of a list. Pass `next_cursor` back as `cursor` to fetch the
+     *     next page. */
+    Page_RecentExpenseDetails: {
+      items: (components["schemas"]["Expense"] & {
+        /** Format: double */
+        line_amount: number
+        tags: string[]
+      })[]
+      /** @description Absent on the last page. */
+      next_cursor?: string | null
+    }
+    /** @enum {string} */
+    PaymentDirection: "inbound" | "outbound"
+    Pool: {
+      description?: string | null
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      name: string
+      /** Format: date-time */
+      updated_at: string
+    }
+    PoolCategory: {
+      color: string
+      icon?: string | null
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      name: string
+      parent_category?: null | components["schemas"]["ExpenseCategory"]
+      /** Format: uuid */
+      pool_id: string
+      /** Format: date-time */
+      updated_at: string
+    }
+    PoolCategoryInput: {
+      /** @description A hex color like `#1f8a70`. */
+      color: string
+      /** @description An emoji or icon name. */
+      icon?: string | null
+      name: string
+      parent_category?: null | components["schemas"]["ExpenseCategory"]
+    }
+    PoolDetails: components["schemas"]["Pool"] & {
+      role: components["schemas"]["PoolRole"]
+      /** Format: double */
+      total_debt: number
+    }
+    /** @description Which emails a member gets about a pool. Members who've never changed
+     *     them get both. */
+    PoolEmailPreferences: {
+      payment_reminders: boolean
+      /** Format: uuid */
+      pool_id: string
+      weekly_digest: boolean
+    }
+    PoolEmailPreferencesChangeset: {
+      payment_reminders?: boolean | null
+      weekly_digest?: boolean | null
+    }
+    PoolInput: {
+      description?: string | null
+      name: string
+    }
+    PoolMembership: {
+      /** Format: double */
+      default_split_percentage: number
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      /** Format: uuid */
+      member_id: string
+      /** Format: uuid */
+      pool_id: string
+      role: components["schemas"]["PoolRole"]
+      /** Format: date-time */
+      updated_at: string
+    }
+    PoolMembershipInput: {
+      /** Format: uuid */
+      member_id: string
+    }
+    PoolMembershipWithMemberDetails: {
+      member: components["schemas"]["Member"]
+      pool_membership: components["schemas"]["PoolMembership"]
+    }
+    /** @enum {string} */
+    PoolRole: "PARTICIPANT" | "ADMIN"
+    RecentExpenseDetails: components["schemas"]["Expense"] & {
+      /** Format: double */
+      line_amount: number
+      tags: string[]
+    }
+    /** @enum {string} */
+    RuleMatchType: "Contains" | "Exact" | "StartsWith" | "Regex"
+    RulePreview: {
+      matches: components["schemas"]["RulePreviewMatch"][]
+      would_change_count: number
+    }
+    RulePreviewMatch: {
+      expense: components["schemas"]["Expense"]
+      /** @description Whether applying the rule would change the expense's category. */
+      would_change: boolean
+    }
+    RuleProposal: {
+      /** @description How many past expenses with this name were given the category. */
+      occurrences: number
+      rule: components["schemas"]["NewExpenseCategoryRule"]
+    }
+    SearchResult: components["schemas"]["Expense"] & {
+      /**
+       * Format: float
+       * @description Relevance of the text match. Searches with only an amount rank every
+       *     result equally, newest first.
+       */
+      rank: number
+    }
+    SearchResults: {
+      /**
+       * Format: int64
+       * @description The offset of the next page, if there is one.
+       */
+      next_offset?: number | null
+      results: components["schemas"]["SearchResult"][]
+    }
+    SignupInput: {
+      email: string
+      first_name: string
+      last_name: string
+      password: string
+    }
+    /** @enum {string} */
+    SplitMethod: "Percentage" | "Amount" | "Default"
+    SplitwiseBalanceCheck: {
+      /**
+       * Format: double
+       * @description The member's balance according to the export's totals, leaving out
+       *     payments.
+       */
+      expected: number
+      /**
+       * Format: double
+       * @description The member's balance from the expenses that would be imported.
+       */
+      imported: number
+      matches: boolean
+      /** Format: uuid */
+      member_id: string
+      name: string
+    }
+    SplitwiseImportInput: {
+      /** @description Create placeholder members for names that match no pool member. */
+      create_placeholders?: boolean
+      /** @description The contents of a Splitwise group export. */
+      csv: string
+      /** @description Validate and preview the import without saving anything. */
+      dry_run?: boolean
+      /** @description Maps member names in the export to pool members. Names left out are
+       *     matched against pool members' names. */
+      members?: {
+        [key: string]: string
+      }
+    }
+    SplitwiseImportResult: {
+      balances: components["schemas"]["SplitwiseBalanceCheck"][]
+      dry_run: boolean
+      errors: components["schemas"]["ImportRowError"][]
+      imported: number
+      members: components["schemas"]["SplitwiseMemberMapping"][]
+      rows: components["schemas"]["CsvImportRow"][]
+      /** @description Payments between members, which aren't imported. They're left out of
+       *     the balance check too. */
+      skipped: components["schemas"]["ImportRowError"][]
+    }
+    SplitwiseMemberMapping: {
+      is_placeholder: boolean
+      /** Format: uuid */
+      member_id: string
+      name: string
+    }
+    Tag: {
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      name: string
+      /** Format: uuid */
+      pool_id: string
+      /** Format: date-time */
+      updated_at: string
+    }
+    TagTotal: {
+      /** Format: int64 */
+      expense_count: number
+      /**
+       * Format: double
+       * @description The member's share of the tagged expenses.
+       */
+      member_share: number
+      name: string
+      /** Format: uuid */
+      tag_id: string
+      /**
+       * Format: double
+       * @description The full amount of the tagged expenses.
+       */
+      total_amount: number
+    }
+    TagUsage: {
+      /**
+       * Format: int64
+       * @description How many live expenses carry the tag.
+       */
+      expense_count: number
+      /** Format: uuid */
+      id: string
+      name: string
+    }
+    TimeSeries: {
+      interval: components["schemas"]["TimeSeriesInterval"]
+      points: components["schemas"]["TimeSeriesPoint"][]
+      timezone: string
+    }
+    /** @enum {string} */
+    TimeSeriesInterval: "Week" | "Month"
+    TimeSeriesPoint: {
+      /**
+       * Format: date
+       * @description The first day of the week (Monday) or month.
+       */
+      bucket: string
+      category?: null | components["schemas"]["ExpenseCategory"]
+      /** @description Each member's share of `total`. */
+      shares: components["schemas"]["MemberShare"][]
+      /**
+       * Format: double
+       * @description Everything spent in the pool during the bucket.
+       */
+      total: number
+    }
+    UpdateBudgetInput: {
+      /** Format: double */
+      amount: number
+    }
+    UpdateExpenseInput: {
+      /** Format: double */
+      amount?: number | null
+      category?: null | components["schemas"]["ExpenseCategory"]
+      /**
+       * Format: uuid
+       * @description A custom category of the pool. Takes precedence over `category`.
+       */
+      custom_category_id?: string | null
+      description?: string | null
+      /** Format: date */
+      incurred_on?: string | null
+      is_settled?: boolean | null
+      line_items?: components["schemas"]["ExpenseLineItem"][] | null
+      name?: string | null
+      /** @description `null` or an empty string clears the notes. */
+      notes?: string | null
+      split_method?: null | components["schemas"]["SplitMethod"]
+      /** @description Replaces the expense's tags. */
+      tags?: string[] | null
+    }
+    UpdatePoolCategoryInput: {
+      /** @description Detaches the category from its built-in parent. */
+      clear_parent_category?: boolean
+      color?: string | null
+      /** @description An empty string removes the icon. */
+      icon?: string | null
+      name?: string | null
+      parent_category?: null | components["schemas"]["ExpenseCategory"]
+    }
+    UpdateWebhookInput: {
+      events?: components["schemas"]["WebhookEvent"][] | null
+      is_active?: boolean | null
+      url?: string | null
+    }
+    Webhook: {
+      /** Format: uuid */
+      created_by_member_id: string
+      events: components["schemas"]["WebhookEvent"][]
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      is_active: boolean
+      /** Format: uuid */
+      pool_id: string
+      /** Format: date-time */
+      updated_at: string
+      url: string
+    }
+    WebhookDelivery: {
+      /** Format: int32 */
+      attempts: number
+      /** Format: date-time */
+      delivered_at?: string | null
+      event: components["schemas"]["WebhookEvent"]
+      /** Format: uuid */
+      id: string
+      /** Format: date-time */
+      inserted_at: string
+      /** Format: date-time */
+      last_attempt_at?: string | null
+      last_error?: string | null
+      /** Format: int32 */
+      last_response_status?: number | null
+      /** Format: date-time */
+      next_attempt_at: string
+      /** @description The exact body that is sent, and that the signature covers. */
+      payload: unknown
+      /** Format: uuid */
+      pool_id: string
+      status: components["schemas"]["WebhookDeliveryStatus"]
+      /** Format: date-time */
+      updated_at: string
+      /** Format: uuid */
+      webhook_id: string
+    }
+    /** @enum {string} */
+    WebhookDeliveryStatus: "Pending" | "Delivered" | "Failed"
+    /** @enum {string} */
+    WebhookEvent:
+      | "expense.created"
+      | "expense.updated"
+      | "expense.deleted"
+      | "expense.restored"
+      | "settlement.recorded"
+      | "member.joined"
+    WebhookInput: {
+      events: components["schemas"]["WebhookEvent"][]
+      url: string
+    }
+  }
+  responses: never
+  parameters: never
+  requestBodies: never
+  headers: never
+  pathItems: never
+}
+export type $defs = Record<string, never>
+export interface operations {
+  authenticate_handler: {
+    parameters: {
+      query: {
+        /** @description Token to authenticate the member */
+        token: string
+      }
+      header?: never
+      path: {
+        /** @description ID of the member to authenticate */
+        member_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Authenticate a member successfully */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["AuthResult"]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  suggest_expense_categories_handler: {
+    parameters: {
+      query: {
+        /** @description Name of the expense to suggest a category for */
+        name: string
+        /** @description Also learn from this pool's expenses */
+        pool_id?: string
+        /** @description Maximum number of suggestions to return (default 3) */
+        limit?: number
+      }
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Suggested categories, best first */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["CategorySuggestion"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  list_inbound_friend_requests_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description List inbound friend requests of a member successfully */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["FriendRequestsList"][]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  create_friend_request_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["FriendRequestInput"]
+      }
+    }
+    responses: {
+      /** @description Create a friend request successfully */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": unknown
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  delete_friend_request: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the friend request to delete */
+        friend_member_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Delete a friend request successfully */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": unknown
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  accept_friend_request_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the friend to accept request from */
+        friend_member_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Accept a friend request successfully */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": unknown
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  list_friends_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description List friends of a member successfully */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Member"][]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  login_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["LoginInput"]
+      }
+    }
+    responses: {
+      /** @description Log in a member successfully */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["AuthResult"]
+        }
+      }
+      /** @description Incorrect credentials */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["AuthResult"]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  get_member_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Get a member successfully */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Member"]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  delete_member_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["DeleteMemberInput"]
+      }
+    }
+    responses: {
+      /** @description Deleted the member's account and cleared their personal details */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Member"]
+        }
+      }
+      /** @description Incorrect password */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  update_member_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["MemberChangeset"]
+      }
+    }
+    responses: {
+      /** @description Updated member */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Member"]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  get_member_category_analytics_handler: {
+    parameters: {
+      query?: {
+        /** @description Only count expenses incurred on or after this date */
+        since?: string
+        /** @description Only count expenses incurred on or before this date */
+        until?: string
+      }
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description What the caller paid and their share across all of their pools, per category */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["CategoryAnalytics"]
+        }
+      }
+      /** @description Invalid date range */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  export_member_data_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Everything stored about the member, as a JSON download */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["MemberDataExport"]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  list_notifications_handler: {
+    parameters: {
+      query?: {
+        /** @description Only return unread notifications */
+        unread?: boolean
+        /** @description Only return notifications sent before this time */
+        before?: string
+        /** @description Maximum number of notifications to return (default 50, max 200) */
+        limit?: number
+      }
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description The member's notifications, newest first */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["NotificationList"]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  get_notification_preferences_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Which kinds of notification the member gets */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["NotificationPreferences"]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  update_notification_preferences_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["NotificationPreferencesChangeset"]
+      }
+    }
+    responses: {
+      /** @description The updated preferences */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["NotificationPreferences"]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  mark_all_notifications_read_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description How many notifications were marked read */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": unknown
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  mark_notification_read_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the notification to mark read */
+        notification_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description The notification, marked read */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Notification"]
+        }
+      }
+      /** @description Notification not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  list_pools_for_member_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description List pools for member */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Pool"][]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  create_pool_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path?: never
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["PoolInput"]
+      }
+    }
+    responses: {
+      /** @description Create a pool successfully */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Pool"]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  get_pool_details_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to fetch details for */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Create expense */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["PoolDetails"]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  get_pool_activity_handler: {
+    parameters: {
+      query?: {
+        /** @description Only return events recorded before this time */
+        before?: string
+        /** @description Maximum number of events to return (default 50, max 200) */
+        limit?: number
+      }
+      header?: never
+      path: {
+        /** @description ID of the pool to fetch activity for */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Expense events in the pool, newest first */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["ExpenseEvent"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  get_pool_category_analytics_handler: {
+    parameters: {
+      query?: {
+        /** @description Only count expenses incurred on or after this date */
+        since?: string
+        /** @description Only count expenses incurred on or before this date */
+        until?: string
+      }
+      header?: never
+      path: {
+        /** @description ID of the pool to analyze */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description What the caller paid and their share of the pool's expenses, per category */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["CategoryAnalytics"]
+        }
+      }
+      /** @description Invalid date range */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  get_pool_time_series_handler: {
+    parameters: {
+      query?: {
+        /** @description Bucket size. Defaults to Month */
+        interval?: components["schemas"]["TimeSeriesInterval"]
+        /** @description Only count expenses incurred on or after this date. Defaults to the start of the 12th most recent bucket */
+        since?: string
+        /** @description Only count expenses incurred on or before this date. Defaults to today */
+        until?: string
+        /** @description IANA time zone used to decide what today is, like America/New_York. Defaults to UTC */
+        timezone?: string
+        /** @description Split each bucket by category */
+        by_category?: boolean
+      }
+      header?: never
+      path: {
+        /** @description ID of the pool to chart */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Pool spending and each member's share per bucket, oldest first, with empty buckets zero-filled */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["TimeSeries"]
+        }
+      }
+      /** @description Invalid date range or time zone */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  get_pool_balances_for_member: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to fetch balances for */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Got balances */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Balance"][]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  list_pool_budgets_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to list budgets for */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description The pool's budgets */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Budget"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  create_pool_budget_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to add the budget to */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["BudgetInput"]
+      }
+    }
+    responses: {
+      /** @description The created budget */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Budget"]
+        }
+      }
+      /** @description Invalid amount */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description The pool already has a budget for this category and period */
+      409: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  get_pool_budget_status_handler: {
+    parameters: {
+      query?: {
+        /** @description Report on the periods containing this date. Defaults to today */
+        on?: string
+      }
+      header?: never
+      path: {
+        /** @description ID of the pool to report on */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Spent, remaining and projected amounts for each of the pool's budgets */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["BudgetStatus"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  delete_pool_budget_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the budget belongs to */
+        pool_id: string
+        /** @description ID of the budget to delete */
+        budget_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description The deleted budget */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Budget"]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Budget not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  update_pool_budget_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the budget belongs to */
+        pool_id: string
+        /** @description ID of the budget to update */
+        budget_id: string
+      }
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["UpdateBudgetInput"]
+      }
+    }
+    responses: {
+      /** @description The updated budget */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Budget"]
+        }
+      }
+      /** @description Invalid amount */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Budget not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  list_pool_categories_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to list custom categories for */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description The pool's custom categories, by name */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["PoolCategory"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  create_pool_category_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to add the category to */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["PoolCategoryInput"]
+      }
+    }
+    responses: {
+      /** @description The created category */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["PoolCategory"]
+        }
+      }
+      /** @description Invalid category */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description A category with this name already exists */
+      409: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  delete_pool_category_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the category belongs to */
+        pool_id: string
+        /** @description ID of the category to delete */
+        category_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description The deleted category. Its expenses keep their built-in category and rules using it are removed */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["PoolCategory"]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Category not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  update_pool_category_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the category belongs to */
+        pool_id: string
+        /** @description ID of the category to update */
+        category_id: string
+      }
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["UpdatePoolCategoryInput"]
+      }
+    }
+    responses: {
+      /** @description The updated category */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["PoolCategory"]
+        }
+      }
+      /** @description Invalid category */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Category not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description A category with this name already exists */
+      409: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  modify_default_splits_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to modify default split percentages for */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["ModifyDefaultSplitInput"]
+      }
+    }
+    responses: {
+      /** @description Default splits modified */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["PoolMembershipWithMemberDetails"][]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  get_pool_email_preferences_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to get email preferences for */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Which emails the member gets about the pool */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["PoolEmailPreferences"]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  update_pool_email_preferences_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to update email preferences for */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["PoolEmailPreferencesChangeset"]
+      }
+    }
+    responses: {
+      /** @description The updated preferences */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["PoolEmailPreferences"]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
         }
-    Balance: {
-      /** Format: double */
-      amount: number
-      direction: components["schemas"]["PaymentDirection"]
-      /** Format: uuid */
-      member_id: string
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
     }
-    Expense: {
-      /** Format: double */
-      amount: number
-      category: components["schemas"]["ExpenseCategory"]
-      description?: string | null
-      /** Format: uuid */
-      id: string
-      /** Format: date-time */
-      inserted_at: string
-      is_settled: boolean
-      name: string
-      notes?: string | null
-      /** Format: uuid */
-      paid_by_member_id: string
-      /** Format: uuid */
-      pool_id: string
-      split_method: components["schemas"]["SplitMethod"]
-      /** Format: date-time */
-      updated_at: string
+  }
+  pool_events_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to stream changes for */
+        pool_id: string
+      }
+      cookie?: never
     }
-    /** @enum {string} */
-    ExpenseCategory:
-      | "FoodDining"
-      | "Groceries"
-      | "Transportation"
-      | "HousingRent"
-      | "Utilities"
-      | "Healthcare"
-      | "Entertainment"
-      | "Shopping"
-      | "Education"
-      | "Travel"
-      | "PersonalCare"
-      | "Fitness"
-      | "Subscriptions"
-      | "BillsPayments"
-      | "BusinessExpenses"
-      | "Investments"
-      | "Insurance"
-      | "Gifts"
-      | "Charity"
-      | "Miscellaneous"
-      | "HomeHouseholdSupplies"
-      | "Pets"
-      | "Taxes"
-      | "Childcare"
-      | "ProfessionalServices"
-    ExpenseCategoryRule: {
-      category: components["schemas"]["ExpenseCategory"]
-      /** Format: date-time */
-      inserted_at: string
-      /** Format: uuid */
-      member_id: string
-      rule: string
-      /** Format: date-time */
-      updated_at: string
+    requestBody?: never
+    responses: {
+      /** @description Server-sent events. `expense` events carry a PoolNotification for each change to the pool's expenses. `balances` events carry the member's balances in the pool, on connect and after changes. `resync` events mean changes may have been missed, so refetch. */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "text/event-stream": string
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
     }
-    ExpenseInput: {
-      /** Format: double */
-      amount: number
-      category: components["schemas"]["ExpenseCategory"]
-      description?: string | null
-      line_items: components["schemas"]["ExpenseLineItem"][]
-      name: string
-      /** Format: uuid */
-      paid_by_member_id: string
-      /** Format: uuid */
-      pool_id: string
-      split_method: components["schemas"]["SplitMethod"]
+  }
+  get_pool_recent_expenses_handler: {
+    parameters: {
+      query: {
+        /** @description Filter expenses by category */
+        category?: components["schemas"]["ExpenseCategory"]
+        /** @description Filter expenses by custom category */
+        custom_category_id?: string
+        /** @description Comma-separated tags. Only expenses with every listed tag are returned */
+        tags?: string
+        /** @description Maximum number of expenses per page (default 50, max 200) */
+        limit?: number
+        /** @description The `next_cursor` of the previous page */
+        cursor?: string
+        /** @description Filter expenses by settle status */
+        is_settled: boolean
+        /** @description Filter expenses by the member who paid */
+        paid_by_member_id?: string
+        /** @description Filter expenses incurred on or after a specific date */
+        since?: string
+        /** @description Filter expenses incurred on or before a specific date */
+        until?: string
+      }
+      header?: never
+      path: {
+        /** @description ID of the pool to fetch expenses for */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description The caller's expenses in the pool, newest first */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Page_RecentExpenseDetails"]
+        }
+      }
+      /** @description Invalid cursor */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  add_expense_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to add expense to */
+        pool_id: string
+      }
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["ExpenseInput"]
+      }
+    }
+    responses: {
+      /** @description Create expense */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["AddExpenseResult"]
+        }
+      }
+      /** @description Unknown custom category or invalid tags */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  get_expense_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to fetch expenses for */
+        pool_id: string
+        /** @description ID of the expense to fetch */
+        expense_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Get expenses */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["ExpenseWithLineItems"]
+        }
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  delete_expense_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to delete the expense for */
+        pool_id: string
+        /** @description ID of the expense to delete */
+        expense_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description The expense, moved to the pool's trash */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Expense"]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Expense not found in the pool */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
     }
-    ExpenseLineItem: {
-      /** Format: double */
-      amount: number
-      /** Format: uuid */
-      debtor_member_id: string
+  }
+  update_expense_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool to update expense for */
+        pool_id: string
+        /** @description ID of the expense to update */
+        expense_id: string
+      }
+      cookie?: never
     }
-    ExpenseWithLineItems: {
-      /** Format: double */
-      amount: number
-      category: components["schemas"]["ExpenseCategory"]
-      description?: string | null
-      /** Format: uuid */
-      id: string
-      /** Format: date-time */
-      inserted_at: string
-      is_settled: boolean
-      line_items: components["schemas"]["ExpenseLineItem"][]
-      name: string
-      notes?: string | null
-      /** Format: uuid */
-      paid_by_member_id: string
-      /** Format: uuid */
-      pool_id: string
-      split_method: components["schemas"]["SplitMethod"]
-      /** Format: date-time */
-      updated_at: string
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["UpdateExpenseInput"]
+      }
     }
-    FriendRequestInput: {
-      friend_email: string
+    responses: {
+      /** @description Create expense */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["Expense"]
+        }
+      }
+      /** @description Unknown custom category or invalid tags */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Expense not found in the pool */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
     }
-    FriendRequestsList: {
-      direction: components["schemas"]["FriendshipDirection"]
-      member: components["schemas"]["Member"]
+  }
+  list_expense_attachments_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the expense belongs to */
+        pool_id: string
+        /** @description ID of the expense to list attachments for */
+        expense_id: string
+      }
+      cookie?: never
     }
-    /** @enum {string} */
-    FriendshipDirection: "inbound" | "outbound"
-    LoginInput: {
-      email: string
-      password: string
+    requestBody?: never
+    responses: {
+      /** @description List attachments of an expense */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["ExpenseAttachment"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
     }
-    Member: {
-      bio?: string | null
-      email: string
-      first_name: string
-      /** Format: uuid */
-      id: string
-      /** Format: date-time */
-      inserted_at: string
-      last_name: string
-      /** Format: date-time */
-      updated_at: string
-      venmo_handle?: string | null
+  }
+  upload_expense_attachment_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the expense belongs to */
+        pool_id: string
+        /** @description ID of the expense to attach the file to */
+        expense_id: string
+      }
+      cookie?: never
     }
-    MemberChangeset: {
-      bio?: string | null
-      email?: string | null
-      first_name?: string | null
-      last_name?: string | null
-      venmo_handle?: string | null
+    requestBody: {
+      content: {
+        "multipart/form-data": components["schemas"]["AttachmentUpload"]
+      }
     }
-    MemberIdSplitPercentage: {
-      /** Format: uuid */
-      member_id: string
-      /** Format: double */
-      split_percentage: number
+    responses: {
+      /** @description Uploaded attachment */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["ExpenseAttachment"]
+        }
+      }
+      /** @description Missing or empty file */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Expense not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description File too large */
+      413: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Unsupported content type, or contents that aren't a supported image or PDF */
+      415: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
     }
-    ModifyDefaultSplitInput: {
-      default_split_percentages: components["schemas"]["MemberIdSplitPercentage"][]
+  }
+  download_expense_attachment_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the expense belongs to */
+        pool_id: string
+        /** @description ID of the expense the attachment belongs to */
+        expense_id: string
+        /** @description ID of the attachment to download */
+        attachment_id: string
+      }
+      cookie?: never
     }
-    NewExpenseCategoryRule: {
-      category: components["schemas"]["ExpenseCategory"]
-      rule: string
+    requestBody?: never
+    responses: {
+      /** @description The attachment contents */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/octet-stream": unknown
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Attachment not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
     }
-    /** @enum {string} */
-    PaymentDirection: "inbound" | "outbound"
-    Pool: {
-      description?: string | null
-      /** Format: uuid */
-      id: string
-      /** Format: date-time */
-      inserted_at: string
-      name: string
-      /** Format: date-time */
-      updated_at: string
+  }
+  delete_expense_attachment_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the expense belongs to */
+        pool_id: string
+        /** @description ID of the expense the attachment belongs to */
+        expense_id: string
+        /** @description ID of the attachment to delete */
+        attachment_id: string
+      }
+      cookie?: never
     }
-    PoolDetails: components["schemas"]["Pool"] & {
-      role: components["schemas"]["PoolRole"]
-      /** Format: double */
-      total_debt: number
+    requestBody?: never
+    responses: {
+      /** @description The deleted attachment */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["ExpenseAttachment"]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Attachment not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
     }
-    PoolInput: {
-      description?: string | null
-      name: string
+  }
+  list_expense_comments_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the expense belongs to */
+        pool_id: string
+        /** @description ID of the expense to list comments for */
+        expense_id: string
+      }
+      cookie?: never
     }
-    PoolMembership: {
-      /** Format: double */
-      default_split_percentage: number
-      /** Format: uuid */
-      id: string
-      /** Format: date-time */
-      inserted_at: string
-      /** Format: uuid */
-      member_id: string
-      /** Format: uuid */
-      pool_id: string
-      role: components["schemas"]["PoolRole"]
-      /** Format: date-time */
-      updated_at: string
+    requestBody?: never
+    responses: {
+      /** @description List comments on an expense, oldest first */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["ExpenseComment"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
     }
-    PoolMembershipInput: {
-      /** Format: uuid */
-      member_id: string
+  }
+  create_expense_comment_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the expense belongs to */
+        pool_id: string
+        /** @description ID of the expense to comment on */
+        expense_id: string
+      }
+      cookie?: never
     }
-    PoolMembershipWithMemberDetails: {
-      member: components["schemas"]["Member"]
-      pool_membership: components["schemas"]["PoolMembership"]
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["ExpenseCommentInput"]
+      }
     }
-    /** @enum {string} */
-    PoolRole: "PARTICIPANT" | "ADMIN"
-    RecentExpenseDetails: components["schemas"]["Expense"] & {
-      /** Format: double */
-      line_amount: number
+    responses: {
+      /** @description The created comment */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["ExpenseComment"]
+        }
+      }
+      /** @description Invalid comment body */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Expense not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
     }
-    SignupInput: {
-      email: string
-      first_name: string
-      last_name: string
-      password: string
+  }
+  delete_expense_comment_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the expense belongs to */
+        pool_id: string
+        /** @description ID of the expense the comment belongs to */
+        expense_id: string
+        /** @description ID of the comment to delete */
+        comment_id: string
+      }
+      cookie?: never
     }
-    /** @enum {string} */
-    SplitMethod: "Percentage" | "Amount" | "Default"
-    UpdateExpenseInput: {
-      /** Format: double */
-      amount?: number | null
-      category?: null | components["schemas"]["ExpenseCategory"]
-      description?: string | null
-      is_settled?: boolean | null
-      line_items?: components["schemas"]["ExpenseLineItem"][] | null
-      name?: string | null
-      split_method?: null | components["schemas"]["SplitMethod"]
+    requestBody?: never
+    responses: {
+      /** @description The deleted comment */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["ExpenseComment"]
+        }
+      }
+      /** @description Not a member of the pool or not the author */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Comment not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
     }
   }
-  responses: never
-  parameters: never
-  requestBodies: never
-  headers: never
-  pathItems: never
-}
-export type $defs = Record<string, never>
-export interface operations {
-  authenticate_handler: {
+  edit_expense_comment_handler: {
     parameters: {
-      query: {
-        /** @description Token to authenticate the member */
-        token: string
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool the expense belongs to */
+        pool_id: string
+        /** @description ID of the expense the comment belongs to */
+        expense_id: string
+        /** @description ID of the comment to edit */
+        comment_id: string
+      }
+      cookie?: never
+    }
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["ExpenseCommentInput"]
+      }
+    }
+    responses: {
+      /** @description The edited comment */
+      200: {
+        headers: {
+          [name: string]: unknown
+        }
+        content: {
+          "application/json": components["schemas"]["ExpenseComment"]
+        }
+      }
+      /** @description Invalid comment body */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool or not the author */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Comment not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
       }
+    }
+  }
+  restore_expense_handler: {
+    parameters: {
+      query?: never
       header?: never
       path: {
-        /** @description ID of the member to authenticate */
-        member_id: string
+        /** @description ID of the pool the expense belongs to */
+        pool_id: string
+        /** @description ID of the deleted expense to restore */
+        expense_id: string
       }
       cookie?: never
     }
     requestBody?: never
     responses: {
-      /** @description Authenticate a member successfully */
+      /** @description The restored expense */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["AuthResult"]
+          "application/json": components["schemas"]["Expense"]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Expense not found in the pool's trash */
+      404: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -589,23 +3980,53 @@ export interface operations {
       }
     }
   }
-  list_inbound_friend_requests_handler: {
+  set_expense_tags_handler: {
     parameters: {
       query?: never
       header?: never
-      path?: never
+      path: {
+        /** @description ID of the pool the expense belongs to */
+        pool_id: string
+        /** @description ID of the expense to tag */
+        expense_id: string
+      }
       cookie?: never
     }
-    requestBody?: never
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["ExpenseTagsInput"]
+      }
+    }
     responses: {
-      /** @description List inbound friend requests of a member successfully */
+      /** @description The expense's tags, which replace any it had */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["FriendRequestsList"][]
+          "application/json": components["schemas"]["Tag"][]
+        }
+      }
+      /** @description Invalid tags */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Expense not found */
+      404: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -616,27 +4037,51 @@ export interface operations {
       }
     }
   }
-  create_friend_request_handler: {
+  export_pool_handler: {
     parameters: {
-      query?: never
+      query?: {
+        /** @description Export format (default Csv) */
+        format?: components["schemas"]["ExportFormat"]
+        /** @description Only include expenses incurred on or after this date */
+        since?: string
+        /** @description Only include expenses incurred on or before this date */
+        until?: string
+        /** @description Commodity for the ledger formats (default USD) */
+        currency?: string
+      }
       header?: never
-      path?: never
-      cookie?: never
-    }
-    requestBody: {
-      content: {
-        "application/json": components["schemas"]["FriendRequestInput"]
+      path: {
+        /** @description ID of the pool to export */
+        pool_id: string
       }
+      cookie?: never
     }
+    requestBody?: never
     responses: {
-      /** @description Create a friend request successfully */
+      /** @description The pool's history, streamed as CSV, JSON or a ledger journal */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
+          "text/csv": unknown
           "application/json": unknown
+          "text/plain": unknown
+        }
+      }
+      /** @description Invalid currency or date range */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -647,26 +4092,51 @@ export interface operations {
       }
     }
   }
-  delete_friend_request: {
+  import_pool_expenses_csv_handler: {
     parameters: {
       query?: never
       header?: never
       path: {
-        /** @description ID of the friend request to delete */
-        friend_member_id: string
+        /** @description ID of the pool to import expenses into */
+        pool_id: string
       }
       cookie?: never
     }
-    requestBody?: never
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["CsvImportInput"]
+      }
+    }
     responses: {
-      /** @description Delete a friend request successfully */
+      /** @description The imported rows, or a preview of them on a dry run */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": unknown
+          "application/json": components["schemas"]["CsvImportResult"]
+        }
+      }
+      /** @description Unreadable CSV or a mapped column is missing */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Some rows are invalid, so nothing was imported */
+      422: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -677,26 +4147,51 @@ export interface operations {
       }
     }
   }
-  accept_friend_request_handler: {
+  import_pool_expenses_splitwise_handler: {
     parameters: {
       query?: never
       header?: never
       path: {
-        /** @description ID of the friend to accept request from */
-        friend_member_id: string
+        /** @description ID of the pool to import expenses into */
+        pool_id: string
       }
       cookie?: never
     }
-    requestBody?: never
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["SplitwiseImportInput"]
+      }
+    }
     responses: {
-      /** @description Accept a friend request successfully */
+      /** @description The imported rows, or a preview of them on a dry run */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": unknown
+          "application/json": components["schemas"]["SplitwiseImportResult"]
+        }
+      }
+      /** @description Not a Splitwise export, or a mapped member isn't in the pool or has several names mapped to it */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Some rows are invalid or balances don't match the export, so nothing was imported */
+      422: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -707,22 +4202,25 @@ export interface operations {
       }
     }
   }
-  list_friends_handler: {
+  list_members_of_pool_handler: {
     parameters: {
       query?: never
       header?: never
-      path?: never
+      path: {
+        /** @description ID of the pool to fetch members for */
+        pool_id: string
+      }
       cookie?: never
     }
     requestBody?: never
     responses: {
-      /** @description List friends of a member successfully */
+      /** @description List all members of a pool successfully */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["Member"][]
+          "application/json": components["schemas"]["PoolMembershipWithMemberDetails"][]
         }
       }
       /** @description Internal server error */
@@ -734,35 +4232,61 @@ export interface operations {
       }
     }
   }
-  login_handler: {
+  add_friend_to_pool_handler: {
     parameters: {
       query?: never
       header?: never
-      path?: never
+      path: {
+        /** @description ID of the pool to add a member to */
+        pool_id: string
+      }
       cookie?: never
     }
     requestBody: {
       content: {
-        "application/json": components["schemas"]["LoginInput"]
+        "application/json": components["schemas"]["PoolMembershipInput"]
       }
     }
     responses: {
-      /** @description Log in a member successfully */
+      /** @description Add a friend to a pool successfully */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["AuthResult"]
+          "application/json": components["schemas"]["PoolMembership"]
         }
       }
-      /** @description Incorrect credentials */
-      400: {
+      /** @description Internal server error */
+      500: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+    }
+  }
+  remove_friend_from_pool_handler: {
+    parameters: {
+      query?: never
+      header?: never
+      path: {
+        /** @description ID of the pool */
+        pool_id: string
+        /** @description ID of the member to remove */
+        member_id: string
+      }
+      cookie?: never
+    }
+    requestBody?: never
+    responses: {
+      /** @description Remove a friend from a pool successfully */
+      200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["AuthResult"]
+          "application/json": unknown
         }
       }
       /** @description Internal server error */
@@ -774,22 +4298,28 @@ export interface operations {
       }
     }
   }
-  get_member_handler: {
+  create_pool_membership_handler: {
     parameters: {
-      query?: never
+      query: {
+        /** @description ID of the member to create membership for */
+        member_id: string
+      }
       header?: never
-      path?: never
+      path: {
+        /** @description ID of the pool to create membership for */
+        pool_id: string
+      }
       cookie?: never
     }
     requestBody?: never
     responses: {
-      /** @description Get a member successfully */
+      /** @description Create pool membership */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["Member"]
+          "application/json": components["schemas"]["PoolMembership"]
         }
       }
       /** @description Internal server error */
@@ -801,26 +4331,25 @@ export interface operations {
       }
     }
   }
-  update_member_handler: {
+  settle_up_pool_handler: {
     parameters: {
       query?: never
       header?: never
-      path?: never
-      cookie?: never
-    }
-    requestBody: {
-      content: {
-        "application/json": components["schemas"]["MemberChangeset"]
+      path: {
+        /** @description ID of the pool to settle up */
+        pool_id: string
       }
+      cookie?: never
     }
+    requestBody?: never
     responses: {
-      /** @description Updated member */
+      /** @description Pool settled up */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["Member"]
+          "application/json": components["schemas"]["PoolDetails"]
         }
       }
       /** @description Internal server error */
@@ -832,23 +4361,38 @@ export interface operations {
       }
     }
   }
-  list_pools_for_member_handler: {
+  list_pool_tags_handler: {
     parameters: {
-      query?: never
+      query?: {
+        /** @description Only return tags starting with this text, for autocomplete */
+        prefix?: string
+        /** @description Maximum number of tags to return (default 20, max 100) */
+        limit?: number
+      }
       header?: never
-      path?: never
+      path: {
+        /** @description ID of the pool to list tags for */
+        pool_id: string
+      }
       cookie?: never
     }
     requestBody?: never
     responses: {
-      /** @description List pools for member */
+      /** @description The pool's tags, most used first */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["Pool"][]
+          "application/json": components["schemas"]["TagUsage"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -859,27 +4403,38 @@ export interface operations {
       }
     }
   }
-  create_pool_handler: {
+  get_pool_tag_totals_handler: {
     parameters: {
-      query?: never
+      query?: {
+        /** @description Only count expenses incurred on or after this date */
+        since?: string
+        /** @description Only count expenses incurred on or before this date */
+        until?: string
+      }
       header?: never
-      path?: never
-      cookie?: never
-    }
-    requestBody: {
-      content: {
-        "application/json": components["schemas"]["PoolInput"]
+      path: {
+        /** @description ID of the pool to total tags for */
+        pool_id: string
       }
+      cookie?: never
     }
+    requestBody?: never
     responses: {
-      /** @description Create a pool successfully */
+      /** @description Spending per tag, largest first, with the caller's share */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["Pool"]
+          "application/json": components["schemas"]["TagTotal"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -890,26 +4445,33 @@ export interface operations {
       }
     }
   }
-  get_pool_details_handler: {
+  list_pool_trash_handler: {
     parameters: {
       query?: never
       header?: never
       path: {
-        /** @description ID of the pool to fetch details for */
+        /** @description ID of the pool to list deleted expenses for */
         pool_id: string
       }
       cookie?: never
     }
     requestBody?: never
     responses: {
-      /** @description Create expense */
+      /** @description Deleted expenses awaiting purge, most recently deleted first */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["PoolDetails"]
+          "application/json": components["schemas"]["Expense"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -920,26 +4482,33 @@ export interface operations {
       }
     }
   }
-  get_pool_balances_for_member: {
+  list_pool_webhooks_handler: {
     parameters: {
       query?: never
       header?: never
       path: {
-        /** @description ID of the pool to fetch balances for */
+        /** @description ID of the pool to list webhooks for */
         pool_id: string
       }
       cookie?: never
     }
     requestBody?: never
     responses: {
-      /** @description Got balances */
+      /** @description The pool's webhooks */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["Balance"][]
+          "application/json": components["schemas"]["Webhook"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -950,30 +4519,44 @@ export interface operations {
       }
     }
   }
-  modify_default_splits_handler: {
+  create_pool_webhook_handler: {
     parameters: {
       query?: never
       header?: never
       path: {
-        /** @description ID of the pool to modify default split percentages for */
+        /** @description ID of the pool to add the webhook to */
         pool_id: string
       }
       cookie?: never
     }
     requestBody: {
       content: {
-        "application/json": components["schemas"]["ModifyDefaultSplitInput"]
+        "application/json": components["schemas"]["WebhookInput"]
       }
     }
     responses: {
-      /** @description Default splits modified */
+      /** @description The created webhook, including its signing secret */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["PoolMembershipWithMemberDetails"][]
+          "application/json": components["schemas"]["CreatedWebhook"]
+        }
+      }
+      /** @description Invalid or private URL, or no events */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -984,39 +4567,42 @@ export interface operations {
       }
     }
   }
-  get_pool_recent_expenses_handler: {
+  delete_pool_webhook_handler: {
     parameters: {
-      query: {
-        /** @description Filter expenses by category */
-        category?: components["schemas"]["ExpenseCategory"]
-        /** @description Limit the number of expenses returned */
-        limit?: number
-        /** @description Filter expenses by settle status */
-        is_settled: boolean
-        /** @description Filter expenses by the member who paid */
-        paid_by_member_id?: string
-        /** @description Filter expenses since a specific date */
-        since?: string
-        /** @description Filter expenses until a specific date */
-        until?: string
-      }
+      query?: never
       header?: never
       path: {
-        /** @description ID of the pool to fetch expenses for */
+        /** @description ID of the pool the webhook belongs to */
         pool_id: string
+        /** @description ID of the webhook to delete */
+        webhook_id: string
       }
       cookie?: never
     }
     requestBody?: never
     responses: {
-      /** @description Create expense */
+      /** @description The deleted webhook */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["RecentExpenseDetails"][]
+          "application/json": components["schemas"]["Webhook"]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Webhook not found */
+      404: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -1027,62 +4613,53 @@ export interface operations {
       }
     }
   }
-  add_expense_handler: {
+  update_pool_webhook_handler: {
     parameters: {
       query?: never
       header?: never
       path: {
-        /** @description ID of the pool to add expense to */
+        /** @description ID of the pool the webhook belongs to */
         pool_id: string
+        /** @description ID of the webhook to update */
+        webhook_id: string
       }
       cookie?: never
     }
     requestBody: {
       content: {
-        "application/json": components["schemas"]["ExpenseInput"]
+        "application/json": components["schemas"]["UpdateWebhookInput"]
       }
     }
     responses: {
-      /** @description Create expense */
+      /** @description The updated webhook */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["Expense"]
+          "application/json": components["schemas"]["Webhook"]
         }
       }
-      /** @description Internal server error */
-      500: {
+      /** @description Invalid or private URL, or no events */
+      400: {
         headers: {
           [name: string]: unknown
         }
         content?: never
       }
-    }
-  }
-  get_expense_handler: {
-    parameters: {
-      query?: never
-      header?: never
-      path: {
-        /** @description ID of the pool to fetch expenses for */
-        pool_id: string
-        /** @description ID of the expense to fetch */
-        expense_id: string
-      }
-      cookie?: never
-    }
-    requestBody?: never
-    responses: {
-      /** @description Get expenses */
-      200: {
+      /** @description Not a member of the pool */
+      403: {
         headers: {
           [name: string]: unknown
         }
-        content: {
-          "application/json": components["schemas"]["ExpenseWithLineItems"]
+        content?: never
+      }
+      /** @description Webhook not found */
+      404: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -1093,28 +4670,47 @@ export interface operations {
       }
     }
   }
-  delete_expense_handler: {
+  list_webhook_deliveries_handler: {
     parameters: {
-      query?: never
+      query?: {
+        /** @description Only return deliveries queued before this time */
+        before?: string
+        /** @description Maximum number of deliveries to return (default 50, max 200) */
+        limit?: number
+      }
       header?: never
       path: {
-        /** @description ID of the pool to delete the expense for */
+        /** @description ID of the pool the webhook belongs to */
         pool_id: string
-        /** @description ID of the expense to delete */
-        expense_id: string
+        /** @description ID of the webhook to list deliveries for */
+        webhook_id: string
       }
       cookie?: never
     }
     requestBody?: never
     responses: {
-      /** @description The deleted expense */
+      /** @description The webhook's deliveries, newest first */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["Expense"]
+          "application/json": components["schemas"]["WebhookDelivery"][]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Webhook not found */
+      404: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -1125,32 +4721,44 @@ export interface operations {
       }
     }
   }
-  update_expense_handler: {
+  retry_webhook_delivery_handler: {
     parameters: {
       query?: never
       header?: never
       path: {
-        /** @description ID of the pool to update expense for */
+        /** @description ID of the pool the webhook belongs to */
         pool_id: string
-        /** @description ID of the expense to update */
-        expense_id: string
+        /** @description ID of the webhook the delivery was sent to */
+        webhook_id: string
+        /** @description ID of the delivery to send again */
+        delivery_id: string
       }
       cookie?: never
     }
-    requestBody: {
-      content: {
-        "application/json": components["schemas"]["UpdateExpenseInput"]
-      }
-    }
+    requestBody?: never
     responses: {
-      /** @description Create expense */
+      /** @description The delivery, queued to be sent again */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["Expense"]
+          "application/json": components["schemas"]["WebhookDelivery"]
+        }
+      }
+      /** @description Not a member of the pool */
+      403: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Webhook or delivery not found */
+      404: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -1161,25 +4769,22 @@ export interface operations {
       }
     }
   }
-  list_members_of_pool_handler: {
+  list_expense_category_rules_handler: {
     parameters: {
       query?: never
       header?: never
-      path: {
-        /** @description ID of the pool to fetch members for */
-        pool_id: string
-      }
+      path?: never
       cookie?: never
     }
     requestBody?: never
     responses: {
-      /** @description List all members of a pool successfully */
+      /** @description Got rules */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["PoolMembershipWithMemberDetails"][]
+          "application/json": components["schemas"]["ExpenseCategoryRule"][]
         }
       }
       /** @description Internal server error */
@@ -1191,30 +4796,41 @@ export interface operations {
       }
     }
   }
-  add_friend_to_pool_handler: {
+  create_expense_category_rule_handler: {
     parameters: {
       query?: never
       header?: never
-      path: {
-        /** @description ID of the pool to add a member to */
-        pool_id: string
-      }
+      path?: never
       cookie?: never
     }
     requestBody: {
       content: {
-        "application/json": components["schemas"]["PoolMembershipInput"]
+        "application/json": components["schemas"]["NewExpenseCategoryRule"]
       }
     }
     responses: {
-      /** @description Add a friend to a pool successfully */
+      /** @description Successfully created rule */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["PoolMembership"]
+          "application/json": components["schemas"]["ExpenseCategoryRule"]
+        }
+      }
+      /** @description Invalid rule */
+      400: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
+      /** @description Not a member of the rule's pool */
+      403: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -1225,27 +4841,27 @@ export interface operations {
       }
     }
   }
-  remove_friend_from_pool_handler: {
+  delete_expense_category_rule_handler: {
     parameters: {
-      query?: never
-      header?: never
-      path: {
-        /** @description ID of the pool */
-        pool_id: string
-        /** @description ID of the member to remove */
-        member_id: string
+      query: {
+        /** @description The rule to delete */
+        rule: string
+        /** @description The category of the rule to delete */
+        category: components["schemas"]["ExpenseCategory"]
       }
+      header?: never
+      path?: never
       cookie?: never
     }
     requestBody?: never
     responses: {
-      /** @description Remove a friend from a pool successfully */
+      /** @description Successfully deleted rule */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": unknown
+          "application/json": components["schemas"]["ExpenseCategoryRule"]
         }
       }
       /** @description Internal server error */
@@ -1257,28 +4873,26 @@ export interface operations {
       }
     }
   }
-  create_pool_membership_handler: {
+  apply_expense_category_rules_handler: {
     parameters: {
-      query: {
-        /** @description ID of the member to create membership for */
-        member_id: string
-      }
+      query?: never
       header?: never
-      path: {
-        /** @description ID of the pool to create membership for */
-        pool_id: string
-      }
+      path?: never
       cookie?: never
     }
-    requestBody?: never
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["ApplyRulesInput"]
+      }
+    }
     responses: {
-      /** @description Create pool membership */
+      /** @description Re-categorized the caller's past expenses */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["PoolMembership"]
+          "application/json": components["schemas"]["ApplyRulesSummary"]
         }
       }
       /** @description Internal server error */
@@ -1290,26 +4904,34 @@ export interface operations {
       }
     }
   }
-  settle_up_pool_handler: {
+  preview_expense_category_rule_handler: {
     parameters: {
       query?: never
       header?: never
-      path: {
-        /** @description ID of the pool to settle up */
-        pool_id: string
-      }
+      path?: never
       cookie?: never
     }
-    requestBody?: never
+    requestBody: {
+      content: {
+        "application/json": components["schemas"]["NewExpenseCategoryRule"]
+      }
+    }
     responses: {
-      /** @description Pool settled up */
+      /** @description The caller's expenses the candidate rule would match */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["PoolDetails"]
+          "application/json": components["schemas"]["RulePreview"]
+        }
+      }
+      /** @description Invalid rule */
+      400: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
@@ -1320,7 +4942,7 @@ export interface operations {
       }
     }
   }
-  list_expense_category_rules_handler: {
+  suggest_expense_category_rules_handler: {
     parameters: {
       query?: never
       header?: never
@@ -1329,13 +4951,13 @@ export interface operations {
     }
     requestBody?: never
     responses: {
-      /** @description Got rules */
+      /** @description Rules proposed from expenses the caller categorized consistently */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["ExpenseCategoryRule"][]
+          "application/json": components["schemas"]["RuleProposal"][]
         }
       }
       /** @description Internal server error */
@@ -1347,20 +4969,19 @@ export interface operations {
       }
     }
   }
-  create_expense_category_rule_handler: {
+  delete_expense_category_rule_by_id_handler: {
     parameters: {
       query?: never
       header?: never
-      path?: never
-      cookie?: never
-    }
-    requestBody: {
-      content: {
-        "application/json": components["schemas"]["NewExpenseCategoryRule"]
+      path: {
+        /** @description ID of the rule to delete */
+        rule_id: string
       }
+      cookie?: never
     }
+    requestBody?: never
     responses: {
-      /** @description Successfully created rule */
+      /** @description The deleted rule */
       200: {
         headers: {
           [name: string]: unknown
@@ -1369,6 +4990,13 @@ export interface operations {
           "application/json": components["schemas"]["ExpenseCategoryRule"]
         }
       }
+      /** @description Rule not found */
+      404: {
+        headers: {
+          [name: string]: unknown
+        }
+        content?: never
+      }
       /** @description Internal server error */
       500: {
         headers: {
@@ -1378,13 +5006,17 @@ export interface operations {
       }
     }
   }
-  delete_expense_category_rule_handler: {
+  search_expenses_handler: {
     parameters: {
       query: {
-        /** @description The rule to delete */
-        rule: string
-        /** @description The category of the rule to delete */
-        category: components["schemas"]["ExpenseCategory"]
+        /** @description Words to find in expense names, descriptions and notes, and amounts like $84 or 12.50 */
+        q: string
+        /** @description Only search this pool */
+        pool_id?: string
+        /** @description Maximum number of results to return (default 20, max 100) */
+        limit?: number
+        /** @description Number of results to skip */
+        offset?: number
       }
       header?: never
       path?: never
@@ -1392,14 +5024,21 @@ export interface operations {
     }
     requestBody?: never
     responses: {
-      /** @description Successfully deleted rule */
+      /** @description Matching expenses across the caller's pools, best matches first */
       200: {
         headers: {
           [name: string]: unknown
         }
         content: {
-          "application/json": components["schemas"]["ExpenseCategoryRule"]
+          "application/json": components["schemas"]["SearchResults"]
+        }
+      }
+      /** @description Empty search */
+      400: {
+        headers: {
+          [name: string]: unknown
         }
+        content?: never
       }
       /** @description Internal server error */
       500: {
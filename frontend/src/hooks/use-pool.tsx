@@ -59,7 +59,7 @@ export const usePool = ({
   )
 
   const {
-    data: expenses,
+    data: expensesPage,
     isLoading: isExpensesLoading,
     isRefetching: isExpensesRefetching,
   } = apiClient.useQuery(
@@ -201,6 +201,8 @@ export const usePool = ({
     f => !members?.find(m => m.member.id === f.id)
   )
 
+  const expenses = expensesPage?.items
+
   const totalExpenses =
     expenses?.reduce((sum, expense) => sum + (expense.amount || 0), 0) || 0
 
@@ -72,6 +72,7 @@ export const useRules = ({
         body: {
           rule: rule,
           category: category,
+          match_type: "Regex",
         },
         headers: createAuthHeader(),
       })
@@ -0,0 +1,3 @@
+DROP INDEX ix_expense_pool_id_incurred_on;
+
+ALTER TABLE expense DROP COLUMN incurred_on;
@@ -0,0 +1,6 @@
+ALTER TABLE expense ADD COLUMN incurred_on DATE NOT NULL DEFAULT CURRENT_DATE;
+
+UPDATE expense
+SET incurred_on = (inserted_at AT TIME ZONE 'UTC')::DATE;
+
+CREATE INDEX ix_expense_pool_id_incurred_on ON expense (pool_id, incurred_on);
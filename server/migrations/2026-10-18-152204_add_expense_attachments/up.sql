@@ -0,0 +1,19 @@
+CREATE TABLE expense_attachment (
+    id UUID NOT NULL DEFAULT GEN_RANDOM_UUID(),
+    expense_id UUID NOT NULL,
+    pool_id UUID NOT NULL REFERENCES pool(id) ON DELETE CASCADE,
+    uploaded_by_member_id UUID NOT NULL REFERENCES member(id) ON DELETE CASCADE,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes >= 0),
+    storage_key TEXT NOT NULL,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX ix_expense_attachment_expense_id ON expense_attachment (expense_id);
+
+SELECT add_updated_at_trigger('expense_attachment');
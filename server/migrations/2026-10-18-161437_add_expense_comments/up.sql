@@ -0,0 +1,20 @@
+CREATE TABLE expense_comment (
+    id UUID NOT NULL DEFAULT GEN_RANDOM_UUID(),
+    expense_id UUID NOT NULL,
+    pool_id UUID NOT NULL REFERENCES pool(id) ON DELETE CASCADE,
+    author_member_id UUID NOT NULL REFERENCES member(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    is_edited BOOLEAN NOT NULL DEFAULT FALSE,
+    is_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+    edited_at TIMESTAMPTZ NULL,
+    deleted_at TIMESTAMPTZ NULL,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX ix_expense_comment_expense_id_inserted_at ON expense_comment (expense_id, inserted_at);
+
+SELECT add_updated_at_trigger('expense_comment');
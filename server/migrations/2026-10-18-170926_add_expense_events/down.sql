@@ -0,0 +1,3 @@
+DROP TABLE expense_event;
+DROP FUNCTION trigger_reject_expense_event_update;
+DROP TYPE expense_event_action;
@@ -0,0 +1,33 @@
+CREATE TYPE expense_event_action AS ENUM (
+    'created',
+    'updated',
+    'deleted',
+    'settled'
+);
+
+-- Append-only: rows are written alongside every expense mutation and never updated.
+CREATE TABLE expense_event (
+    id UUID NOT NULL DEFAULT GEN_RANDOM_UUID(),
+    expense_id UUID NOT NULL,
+    pool_id UUID NOT NULL REFERENCES pool(id) ON DELETE CASCADE,
+    actor_member_id UUID NOT NULL REFERENCES member(id) ON DELETE CASCADE,
+    action expense_event_action NOT NULL,
+    before_snapshot JSONB NULL,
+    after_snapshot JSONB NULL,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX ix_expense_event_pool_id_inserted_at ON expense_event (pool_id, inserted_at DESC);
+CREATE INDEX ix_expense_event_expense_id ON expense_event (expense_id);
+
+CREATE OR REPLACE FUNCTION trigger_reject_expense_event_update() RETURNS TRIGGER AS $$ BEGIN
+RAISE EXCEPTION 'expense_event is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER reject_expense_event_update
+BEFORE UPDATE ON expense_event
+FOR EACH ROW EXECUTE PROCEDURE trigger_reject_expense_event_update();
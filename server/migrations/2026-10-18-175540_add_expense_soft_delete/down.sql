@@ -0,0 +1,15 @@
+DELETE FROM expense_event WHERE action = 'restored';
+
+ALTER TYPE expense_event_action RENAME TO expense_event_action_old;
+CREATE TYPE expense_event_action AS ENUM (
+    'created',
+    'updated',
+    'deleted',
+    'settled'
+);
+ALTER TABLE expense_event
+    ALTER COLUMN action TYPE expense_event_action USING action::TEXT::expense_event_action;
+DROP TYPE expense_event_action_old;
+
+DELETE FROM expense WHERE deleted_at IS NOT NULL;
+ALTER TABLE expense DROP COLUMN deleted_at;
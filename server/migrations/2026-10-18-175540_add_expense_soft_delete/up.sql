@@ -0,0 +1,5 @@
+ALTER TABLE expense ADD COLUMN deleted_at TIMESTAMPTZ NULL;
+
+CREATE INDEX ix_expense_pool_id_deleted_at ON expense (pool_id, deleted_at) WHERE deleted_at IS NOT NULL;
+
+ALTER TYPE expense_event_action ADD VALUE 'restored';
@@ -0,0 +1,19 @@
+DROP INDEX ix_expense_category_rule_member_id;
+
+DELETE FROM expense_category_rule r
+USING expense_category_rule dup
+WHERE
+    (r.member_id, r.rule, r.category) = (dup.member_id, dup.rule, dup.category)
+    AND r.inserted_at > dup.inserted_at;
+
+ALTER TABLE expense_category_rule
+    DROP CONSTRAINT expense_category_rule_pkey,
+    DROP COLUMN id,
+    DROP COLUMN match_type,
+    DROP COLUMN min_amount,
+    DROP COLUMN max_amount,
+    DROP COLUMN pool_id,
+    DROP COLUMN priority,
+    ADD PRIMARY KEY (member_id, rule, category);
+
+DROP TYPE rule_match_type;
@@ -0,0 +1,27 @@
+CREATE TYPE rule_match_type AS ENUM (
+    'contains',
+    'exact',
+    'starts_with',
+    'regex'
+);
+
+ALTER TABLE expense_category_rule DROP CONSTRAINT expense_category_rule_pkey;
+
+ALTER TABLE expense_category_rule
+    ADD COLUMN id UUID NOT NULL DEFAULT GEN_RANDOM_UUID(),
+    ADD COLUMN match_type rule_match_type NOT NULL DEFAULT 'contains',
+    ADD COLUMN min_amount DOUBLE PRECISION NULL,
+    ADD COLUMN max_amount DOUBLE PRECISION NULL,
+    ADD COLUMN pool_id UUID NULL REFERENCES pool(id) ON DELETE CASCADE,
+    ADD COLUMN priority INTEGER NOT NULL DEFAULT 0,
+    ADD PRIMARY KEY (id),
+    ADD CONSTRAINT expense_category_rule_amount_range_check CHECK (min_amount IS NULL OR max_amount IS NULL OR min_amount <= max_amount);
+
+-- Rules used to always be case-insensitive regexes. Ones without any regex
+-- syntax match the same names as "contains" rules, so only the rest are kept
+-- as regexes.
+UPDATE expense_category_rule
+SET match_type = 'regex'
+WHERE rule ~ '[.^$*+?()\[\]{}|\\]';
+
+CREATE INDEX ix_expense_category_rule_member_id ON expense_category_rule (member_id);
@@ -0,0 +1,3 @@
+ALTER TABLE expense_category_rule DROP COLUMN custom_category_id;
+ALTER TABLE expense DROP COLUMN custom_category_id;
+DROP TABLE pool_category;
@@ -0,0 +1,22 @@
+CREATE TABLE pool_category (
+    id UUID NOT NULL DEFAULT GEN_RANDOM_UUID(),
+    pool_id UUID NOT NULL REFERENCES pool(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    color TEXT NOT NULL,
+    icon TEXT NULL,
+    parent_category expense_category NULL,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (id)
+);
+
+CREATE UNIQUE INDEX ix_pool_category_pool_id_name ON pool_category (pool_id, LOWER(name));
+
+SELECT add_updated_at_trigger('pool_category');
+
+-- Expenses and rules with a custom category keep its parent (or
+-- 'miscellaneous') in `category`, so built-in analytics still cover them.
+ALTER TABLE expense ADD COLUMN custom_category_id UUID NULL REFERENCES pool_category(id) ON DELETE SET NULL;
+ALTER TABLE expense_category_rule ADD COLUMN custom_category_id UUID NULL REFERENCES pool_category(id) ON DELETE CASCADE;
@@ -0,0 +1,2 @@
+DROP TABLE expense_tag;
+DROP TABLE tag;
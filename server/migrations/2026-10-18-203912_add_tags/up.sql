@@ -0,0 +1,28 @@
+CREATE TABLE tag (
+    id UUID NOT NULL DEFAULT GEN_RANDOM_UUID(),
+    pool_id UUID NOT NULL REFERENCES pool(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (id)
+);
+
+CREATE UNIQUE INDEX ix_tag_pool_id_name ON tag (pool_id, LOWER(name));
+
+SELECT add_updated_at_trigger('tag');
+
+-- `expense` is partitioned on `is_settled`, so `expense_id` can't reference
+-- it directly. Links are removed alongside purged expenses.
+CREATE TABLE expense_tag (
+    expense_id UUID NOT NULL,
+    tag_id UUID NOT NULL REFERENCES tag(id) ON DELETE CASCADE,
+    pool_id UUID NOT NULL REFERENCES pool(id) ON DELETE CASCADE,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (expense_id, tag_id)
+);
+
+CREATE INDEX ix_expense_tag_tag_id ON expense_tag (tag_id);
@@ -0,0 +1,2 @@
+DROP INDEX ix_expense_search;
+DROP FUNCTION expense_search_vector;
@@ -0,0 +1,15 @@
+-- Names weigh more than descriptions, which weigh more than notes. Searches
+-- must call this function so the index below is used.
+CREATE FUNCTION expense_search_vector(name TEXT, description TEXT, notes TEXT)
+RETURNS TSVECTOR
+LANGUAGE SQL
+IMMUTABLE
+PARALLEL SAFE
+AS $$
+    SELECT
+        SETWEIGHT(TO_TSVECTOR('english', COALESCE(name, '')), 'A')
+        || SETWEIGHT(TO_TSVECTOR('english', COALESCE(description, '')), 'B')
+        || SETWEIGHT(TO_TSVECTOR('english', COALESCE(notes, '')), 'C')
+$$;
+
+CREATE INDEX ix_expense_search ON expense USING GIN (expense_search_vector(name, description, notes));
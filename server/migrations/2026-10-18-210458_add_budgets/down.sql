@@ -0,0 +1,2 @@
+DROP TABLE budget;
+DROP TYPE budget_period;
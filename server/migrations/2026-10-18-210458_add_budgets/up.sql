@@ -0,0 +1,23 @@
+CREATE TYPE budget_period AS ENUM (
+    'weekly',
+    'monthly',
+    'yearly'
+);
+
+CREATE TABLE budget (
+    id UUID NOT NULL DEFAULT GEN_RANDOM_UUID(),
+    pool_id UUID NOT NULL REFERENCES pool(id) ON DELETE CASCADE,
+    -- NULL caps all of the pool's spending.
+    category expense_category NULL,
+    amount DOUBLE PRECISION NOT NULL CHECK (amount > 0),
+    period budget_period NOT NULL DEFAULT 'monthly',
+    created_by_member_id UUID NOT NULL REFERENCES member(id),
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (id),
+    UNIQUE NULLS NOT DISTINCT (pool_id, category, period)
+);
+
+SELECT add_updated_at_trigger('budget');
@@ -0,0 +1,3 @@
+-- Placeholder members stand in for people brought over by an import who
+-- don't have an account yet. They have no password, so they can't log in.
+ALTER TABLE member ADD COLUMN is_placeholder BOOLEAN NOT NULL DEFAULT FALSE;
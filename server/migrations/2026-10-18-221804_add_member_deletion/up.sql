@@ -0,0 +1,3 @@
+-- Deleted members keep their row, anonymized, so the expenses and line
+-- items other members' balances depend on stay intact.
+ALTER TABLE member ADD COLUMN deleted_at TIMESTAMPTZ NULL;
@@ -0,0 +1,4 @@
+DROP TABLE webhook_delivery;
+DROP TABLE webhook;
+DROP TYPE webhook_delivery_status;
+DROP TYPE webhook_event;
@@ -0,0 +1,63 @@
+CREATE TYPE webhook_event AS ENUM (
+    'expense.created',
+    'expense.updated',
+    'expense.deleted',
+    'expense.restored',
+    'settlement.recorded',
+    'member.joined'
+);
+
+CREATE TYPE webhook_delivery_status AS ENUM (
+    'pending',
+    'delivered',
+    'failed'
+);
+
+CREATE TABLE webhook (
+    id UUID NOT NULL DEFAULT GEN_RANDOM_UUID(),
+    pool_id UUID NOT NULL REFERENCES pool(id) ON DELETE CASCADE,
+    created_by_member_id UUID NOT NULL REFERENCES member(id),
+    url TEXT NOT NULL,
+    -- Deliveries are signed with this, so receivers can check they came
+    -- from us.
+    secret TEXT NOT NULL,
+    events webhook_event[] NOT NULL CHECK (CARDINALITY(events) > 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX webhook_pool_id_idx ON webhook (pool_id);
+
+SELECT add_updated_at_trigger('webhook');
+
+-- The outbox. A delivery is written in the same transaction as the change
+-- it describes, then sent by a background task until it succeeds or runs
+-- out of attempts.
+CREATE TABLE webhook_delivery (
+    id UUID NOT NULL DEFAULT GEN_RANDOM_UUID(),
+    webhook_id UUID NOT NULL REFERENCES webhook(id) ON DELETE CASCADE,
+    pool_id UUID NOT NULL REFERENCES pool(id) ON DELETE CASCADE,
+    event webhook_event NOT NULL,
+    payload JSONB NOT NULL,
+    status webhook_delivery_status NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_attempt_at TIMESTAMPTZ NULL,
+    last_response_status INTEGER NULL,
+    last_error TEXT NULL,
+    delivered_at TIMESTAMPTZ NULL,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX webhook_delivery_due_idx ON webhook_delivery (next_attempt_at) WHERE status = 'pending';
+CREATE INDEX webhook_delivery_webhook_id_idx ON webhook_delivery (webhook_id, inserted_at DESC);
+
+SELECT add_updated_at_trigger('webhook_delivery');
@@ -0,0 +1,3 @@
+DROP TABLE notification_preference;
+DROP TABLE notification;
+DROP TYPE notification_kind;
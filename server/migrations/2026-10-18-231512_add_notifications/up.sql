@@ -0,0 +1,45 @@
+CREATE TYPE notification_kind AS ENUM (
+    'friend_request',
+    'pool_invitation',
+    'expense_added',
+    'settlement'
+);
+
+CREATE TABLE notification (
+    id UUID NOT NULL DEFAULT GEN_RANDOM_UUID(),
+    member_id UUID NOT NULL REFERENCES member(id) ON DELETE CASCADE,
+    kind notification_kind NOT NULL,
+    actor_member_id UUID NULL REFERENCES member(id) ON DELETE CASCADE,
+    pool_id UUID NULL REFERENCES pool(id) ON DELETE CASCADE,
+    expense_id UUID NULL,
+    -- What the inbox shows, captured when the notification was made so it
+    -- reads the same after the pool or expense changes.
+    data JSONB NOT NULL DEFAULT '{}',
+    read_at TIMESTAMPTZ NULL,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX notification_member_id_idx ON notification (member_id, inserted_at DESC);
+CREATE INDEX notification_unread_idx ON notification (member_id) WHERE read_at IS NULL;
+
+SELECT add_updated_at_trigger('notification');
+
+-- Members without a row get every kind of notification.
+CREATE TABLE notification_preference (
+    member_id UUID NOT NULL REFERENCES member(id) ON DELETE CASCADE,
+    friend_requests BOOLEAN NOT NULL DEFAULT TRUE,
+    pool_invitations BOOLEAN NOT NULL DEFAULT TRUE,
+    expenses BOOLEAN NOT NULL DEFAULT TRUE,
+    settlements BOOLEAN NOT NULL DEFAULT TRUE,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (member_id)
+);
+
+SELECT add_updated_at_trigger('notification_preference');
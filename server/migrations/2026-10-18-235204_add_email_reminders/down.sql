@@ -0,0 +1,3 @@
+DROP TABLE email_digest;
+DROP TABLE payment_reminder;
+DROP TABLE pool_email_preference;
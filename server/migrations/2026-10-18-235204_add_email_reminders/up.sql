@@ -0,0 +1,42 @@
+-- Members without a row get both kinds of email for the pool.
+CREATE TABLE pool_email_preference (
+    pool_id UUID NOT NULL REFERENCES pool(id) ON DELETE CASCADE,
+    member_id UUID NOT NULL REFERENCES member(id) ON DELETE CASCADE,
+    payment_reminders BOOLEAN NOT NULL DEFAULT TRUE,
+    weekly_digest BOOLEAN NOT NULL DEFAULT TRUE,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (pool_id, member_id)
+);
+
+SELECT add_updated_at_trigger('pool_email_preference');
+
+-- Members who currently owe more than the reminder threshold in a pool, and
+-- since when. Rows are removed once the member drops back under it.
+CREATE TABLE payment_reminder (
+    pool_id UUID NOT NULL REFERENCES pool(id) ON DELETE CASCADE,
+    member_id UUID NOT NULL REFERENCES member(id) ON DELETE CASCADE,
+    owing_since TIMESTAMPTZ NOT NULL,
+    last_sent_at TIMESTAMPTZ NULL,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (pool_id, member_id)
+);
+
+SELECT add_updated_at_trigger('payment_reminder');
+
+CREATE TABLE email_digest (
+    member_id UUID NOT NULL REFERENCES member(id) ON DELETE CASCADE,
+    last_sent_at TIMESTAMPTZ NOT NULL,
+
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (member_id)
+);
+
+SELECT add_updated_at_trigger('email_digest');
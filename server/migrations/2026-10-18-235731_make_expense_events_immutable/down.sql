@@ -0,0 +1,14 @@
+DROP TRIGGER reject_expense_event_update ON expense_event;
+
+CREATE OR REPLACE FUNCTION trigger_reject_expense_event_update() RETURNS TRIGGER AS $$ BEGIN
+RAISE EXCEPTION 'expense_event is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER reject_expense_event_update
+BEFORE UPDATE ON expense_event
+FOR EACH ROW EXECUTE PROCEDURE trigger_reject_expense_event_update();
+
+ALTER TABLE expense_event
+DROP CONSTRAINT expense_event_actor_member_id_fkey,
+ADD CONSTRAINT expense_event_actor_member_id_fkey FOREIGN KEY (actor_member_id) REFERENCES member(id) ON DELETE CASCADE;
@@ -0,0 +1,21 @@
+-- Events outlive the members who caused them. Members are anonymized rather
+-- than deleted, so deleting one with events is a mistake.
+ALTER TABLE expense_event
+DROP CONSTRAINT expense_event_actor_member_id_fkey,
+ADD CONSTRAINT expense_event_actor_member_id_fkey FOREIGN KEY (actor_member_id) REFERENCES member(id) ON DELETE RESTRICT;
+
+-- Deletes are rejected too, except when cascading from a deleted pool.
+CREATE OR REPLACE FUNCTION trigger_reject_expense_event_update() RETURNS TRIGGER AS $$ BEGIN
+IF TG_OP = 'DELETE' AND NOT EXISTS (SELECT 1 FROM pool WHERE id = OLD.pool_id) THEN
+    RETURN OLD;
+END IF;
+
+RAISE EXCEPTION 'expense_event is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER reject_expense_event_update ON expense_event;
+
+CREATE TRIGGER reject_expense_event_update
+BEFORE UPDATE OR DELETE ON expense_event
+FOR EACH ROW EXECUTE PROCEDURE trigger_reject_expense_event_update();
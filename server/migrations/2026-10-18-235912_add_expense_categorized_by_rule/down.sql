@@ -0,0 +1 @@
+ALTER TABLE expense DROP COLUMN categorized_by_rule;
@@ -0,0 +1,3 @@
+-- Whether the expense's category was picked by one of the payer's rules
+-- rather than by a person. Category suggestions only learn from the latter.
+ALTER TABLE expense ADD COLUMN categorized_by_rule BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,98 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::{ExpenseCategory, TimeSeriesRow};
+
+/// The most buckets a single time-series request may span.
+pub const MAX_TIME_SERIES_BUCKETS: i64 = 260;
+
+/// How many buckets a time series covers when no start date is given.
+pub const DEFAULT_TIME_SERIES_BUCKETS: i64 = 12;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub enum TimeSeriesInterval {
+    Week,
+    #[default]
+    Month,
+}
+
+impl TimeSeriesInterval {
+    /// The `date_trunc` field for this interval.
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            TimeSeriesInterval::Week => "week",
+            TimeSeriesInterval::Month => "month",
+        }
+    }
+
+    /// The number of buckets from the one containing `since` through the one
+    /// containing `until`.
+    pub fn bucket_count(&self, since: NaiveDate, until: NaiveDate) -> i64 {
+        if since > until {
+            return 0;
+        }
+
+        match self {
+            TimeSeriesInterval::Week => {
+                let monday = |date: NaiveDate| {
+                    date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+                };
+                (monday(until) - monday(since)).num_days() / 7 + 1
+            }
+            TimeSeriesInterval::Month => {
+                let months = |date: NaiveDate| date.year() as i64 * 12 + date.month0() as i64;
+                months(until) - months(since) + 1
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct MemberShare {
+    pub member_id: uuid::Uuid,
+    pub share: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct TimeSeriesPoint {
+    /// The first day of the week (Monday) or month.
+    pub bucket: NaiveDate,
+    /// Set when the series is split by category.
+    pub category: Option<ExpenseCategory>,
+    /// Everything spent in the pool during the bucket.
+    pub total: f64,
+    /// Each member's share of `total`.
+    pub shares: Vec<MemberShare>,
+}
+
+/// Folds rows ordered by bucket and category, where the row without a
+/// member holds the total, into one point per bucket and category.
+pub fn group_time_series(rows: Vec<TimeSeriesRow>) -> Vec<TimeSeriesPoint> {
+    let mut points: Vec<TimeSeriesPoint> = Vec::new();
+
+    for row in rows {
+        let point = match points.last_mut() {
+            Some(point) if point.bucket == row.bucket && point.category == row.category => point,
+            _ => {
+                points.push(TimeSeriesPoint {
+                    bucket: row.bucket,
+                    category: row.category.clone(),
+                    total: 0.0,
+                    shares: Vec::new(),
+                });
+                points.last_mut().expect("A point was just pushed")
+            }
+        };
+
+        match row.member_id {
+            Some(member_id) => point.shares.push(MemberShare {
+                member_id,
+                share: row.amount,
+            }),
+            None => point.total = row.amount,
+        }
+    }
+
+    points
+}
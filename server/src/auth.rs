@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use diesel::pg::PgConnection;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Member;
+
+/// How long a token stays valid after it's issued.
+pub const TOKEN_LIFETIME: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// How long a member found to still have an account is trusted before the
+/// database is asked again.
+pub const ACTIVE_MEMBER_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum AuthError {
+    #[error("Invalid token")]
+    InvalidToken,
+    #[error("Account no longer exists")]
+    Deleted,
+    #[error("Failed to check account: {0}")]
+    Database(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub exp: usize,
+    pub iat: usize,
+}
+
+pub fn issue_token(
+    member_id: uuid::Uuid,
+    secret: &[u8],
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as usize;
+
+    let claims = Claims {
+        sub: member_id.to_string(),
+        exp: now + TOKEN_LIFETIME.as_secs() as usize,
+        iat: now,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret),
+    )
+}
+
+/// The member a token was issued to. This only checks the token itself, so
+/// it still holds for accounts deleted since; see [`ensure_active`].
+pub fn verify_token(token: &str, secret: &[u8]) -> Result<uuid::Uuid, AuthError> {
+    let token_data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret),
+        &Validation::new(Algorithm::HS256),
+    )
+    .map_err(|_| AuthError::InvalidToken)?;
+
+    uuid::Uuid::parse_str(&token_data.claims.sub).map_err(|_| AuthError::InvalidToken)
+}
+
+/// Tokens outlive account deletion, so a token's member has to be checked
+/// against the database too.
+pub fn ensure_active(conn: &mut PgConnection, member_id: uuid::Uuid) -> Result<(), AuthError> {
+    match Member::is_deleted(conn, member_id) {
+        Ok(false) => Ok(()),
+        Ok(true) | Err(diesel::result::Error::NotFound) => Err(AuthError::Deleted),
+        Err(e) => Err(AuthError::Database(e.to_string())),
+    }
+}
+
+/// Members recently found to still have an account, so requests don't each
+/// need a database round-trip to check. Deleting an account forgets the
+/// member right away in this process; other processes catch up within the
+/// TTL.
+pub struct ActiveMembers {
+    ttl: Duration,
+    checked_at: Mutex<HashMap<uuid::Uuid, Instant>>,
+}
+
+impl ActiveMembers {
+    pub fn new(ttl: Duration) -> Self {
+        ActiveMembers {
+            ttl,
+            checked_at: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn contains(&self, member_id: uuid::Uuid, now: Instant) -> bool {
+        self.checked_at
+            .lock()
+            .expect("Active member cache poisoned")
+            .get(&member_id)
+            .is_some_and(|checked_at| now.duration_since(*checked_at) < self.ttl)
+    }
+
+    /// Remembers that the member was found active at `now`, dropping
+    /// entries that have expired along the way.
+    pub fn insert(&self, member_id: uuid::Uuid, now: Instant) {
+        let mut checked_at = self
+            .checked_at
+            .lock()
+            .expect("Active member cache poisoned");
+
+        checked_at.retain(|_, checked_at| now.duration_since(*checked_at) < self.ttl);
+        checked_at.insert(member_id, now);
+    }
+
+    pub fn forget(&self, member_id: uuid::Uuid) {
+        self.checked_at
+            .lock()
+            .expect("Active member cache poisoned")
+            .remove(&member_id);
+    }
+}
@@ -0,0 +1,501 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{BufRead, Write};
+
+use chrono::{DateTime, Utc};
+use diesel::migration::MigrationSource;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::sql_types::{Bool, Json, Text};
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
+use serde::{Deserialize, Serialize};
+
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+/// Identifies a file as a Medici backup.
+pub const BACKUP_FORMAT: &str = "medici-backup";
+
+/// The version of the backup archive. Bump it when the archive's layout
+/// changes; the tables inside are versioned by `schema_version` instead.
+pub const BACKUP_VERSION: u32 = 1;
+
+const MIGRATIONS_TABLE: &str = "__diesel_schema_migrations";
+const FETCH_SIZE: usize = 1000;
+const INSERT_BATCH_SIZE: usize = 500;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("database error: {0}")]
+    Database(#[from] diesel::result::Error),
+    #[error("migration failed: {0}")]
+    Migration(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("not a valid backup: {0}")]
+    Malformed(String),
+    #[error("backup version {0} is newer than this server supports ({BACKUP_VERSION})")]
+    UnsupportedVersion(u32),
+    #[error("backup was taken at schema version {0}, which this server doesn't know about")]
+    UnknownSchemaVersion(String),
+    #[error("database has migrations newer than the backup's schema version {0}")]
+    DatabaseAhead(String),
+    #[error("table {0} is not empty; restore into an empty database")]
+    NotEmpty(String),
+    #[error("backup has a table {0} that doesn't exist in this database")]
+    UnknownTable(String),
+    #[error("expected {expected} rows for table {table} but the backup has {found}")]
+    RowCountMismatch {
+        table: String,
+        expected: u64,
+        found: u64,
+    },
+    #[error("foreign keys form a cycle between {0}")]
+    ForeignKeyCycle(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupTable {
+    pub name: String,
+    pub rows: u64,
+}
+
+/// The first line of an archive. Every line after it is one row:
+/// `{"table": ..., "row": {...}}`, grouped by table in `tables` order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupHeader {
+    pub format: String,
+    pub version: u32,
+    /// The newest migration applied to the database the backup came from.
+    pub schema_version: String,
+    pub created_at: DateTime<Utc>,
+    /// Tables in the order they must be loaded to satisfy foreign keys.
+    pub tables: Vec<BackupTable>,
+}
+
+impl BackupHeader {
+    pub fn parse(line: &str) -> Result<Self, BackupError> {
+        let header: BackupHeader =
+            serde_json::from_str(line).map_err(|e| BackupError::Malformed(e.to_string()))?;
+
+        if header.format != BACKUP_FORMAT {
+            return Err(BackupError::Malformed(format!(
+                "expected format \"{BACKUP_FORMAT}\", found \"{}\"",
+                header.format
+            )));
+        }
+
+        Ok(header)
+    }
+
+    /// Checks that this server can load the backup: it understands the
+    /// archive's layout and has the migration the backup was taken at.
+    pub fn validate(&self, known_versions: &[String]) -> Result<(), BackupError> {
+        if self.version > BACKUP_VERSION {
+            return Err(BackupError::UnsupportedVersion(self.version));
+        }
+
+        if !known_versions.contains(&self.schema_version) {
+            return Err(BackupError::UnknownSchemaVersion(
+                self.schema_version.clone(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct BackupRow {
+    table: String,
+    row: serde_json::Value,
+}
+
+#[derive(QueryableByName)]
+struct TableName {
+    #[diesel(sql_type = Text)]
+    name: String,
+}
+
+#[derive(QueryableByName)]
+struct ForeignKey {
+    #[diesel(sql_type = Text)]
+    table_name: String,
+    #[diesel(sql_type = Text)]
+    references_table: String,
+}
+
+#[derive(QueryableByName)]
+struct JsonRow {
+    #[diesel(sql_type = Text)]
+    row: String,
+}
+
+#[derive(QueryableByName)]
+struct Exists {
+    #[diesel(sql_type = Bool)]
+    exists: bool,
+}
+
+#[derive(QueryableByName)]
+struct Count {
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    count: i64,
+}
+
+pub fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Orders tables so that every table comes after the tables it references.
+/// Tables that don't depend on each other are ordered by name, so the same
+/// schema always gives the same order.
+pub fn restore_order(
+    tables: &[String],
+    foreign_keys: &[(String, String)],
+) -> Result<Vec<String>, BackupError> {
+    let mut dependencies: HashMap<&str, HashSet<&str>> = tables
+        .iter()
+        .map(|table| (table.as_str(), HashSet::new()))
+        .collect();
+
+    for (table, references) in foreign_keys {
+        if table != references
+            && dependencies.contains_key(references.as_str())
+            && let Some(table_dependencies) = dependencies.get_mut(table.as_str())
+        {
+            table_dependencies.insert(references.as_str());
+        }
+    }
+
+    let mut remaining: BTreeSet<&str> = tables.iter().map(String::as_str).collect();
+    let mut order = Vec::with_capacity(tables.len());
+
+    while !remaining.is_empty() {
+        let ready: Vec<&str> = remaining
+            .iter()
+            .copied()
+            .filter(|table| dependencies[table].iter().all(|d| !remaining.contains(d)))
+            .collect();
+
+        if ready.is_empty() {
+            return Err(BackupError::ForeignKeyCycle(
+                remaining.into_iter().collect::<Vec<_>>().join(", "),
+            ));
+        }
+
+        for table in ready {
+            remaining.remove(table);
+            order.push(table.to_string());
+        }
+    }
+
+    Ok(order)
+}
+
+/// Every migration this server knows about, oldest first.
+pub fn known_schema_versions() -> Result<Vec<String>, BackupError> {
+    let migrations = MigrationSource::<Pg>::migrations(&MIGRATIONS)
+        .map_err(|e| BackupError::Migration(e.to_string()))?;
+
+    let mut versions: Vec<String> = migrations
+        .iter()
+        .map(|migration| migration.name().version().to_string())
+        .collect();
+    versions.sort();
+
+    Ok(versions)
+}
+
+/// The tables to back up, in load order. Partitions are left out, since
+/// their rows are read and written through the partitioned table.
+fn ordered_tables(conn: &mut PgConnection) -> Result<Vec<String>, BackupError> {
+    let tables: Vec<String> = diesel::sql_query(
+        "SELECT c.relname::text AS name
+         FROM pg_class c
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE n.nspname = 'public'
+           AND c.relkind IN ('r', 'p')
+           AND NOT c.relispartition
+           AND c.relname <> $1",
+    )
+    .bind::<Text, _>(MIGRATIONS_TABLE)
+    .load::<TableName>(conn)?
+    .into_iter()
+    .map(|table| table.name)
+    .collect();
+
+    let foreign_keys: Vec<(String, String)> = diesel::sql_query(
+        "SELECT c.relname::text AS table_name, r.relname::text AS references_table
+         FROM pg_constraint k
+         JOIN pg_class c ON c.oid = k.conrelid
+         JOIN pg_class r ON r.oid = k.confrelid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE k.contype = 'f'
+           AND k.conparentid = 0
+           AND n.nspname = 'public'",
+    )
+    .load::<ForeignKey>(conn)?
+    .into_iter()
+    .map(|key| (key.table_name, key.references_table))
+    .collect();
+
+    restore_order(&tables, &foreign_keys)
+}
+
+fn insertable_columns(conn: &mut PgConnection, table: &str) -> Result<Vec<String>, BackupError> {
+    Ok(diesel::sql_query(
+        "SELECT column_name::text AS name
+         FROM information_schema.columns
+         WHERE table_schema = 'public'
+           AND table_name = $1
+           AND is_generated = 'NEVER'
+         ORDER BY ordinal_position",
+    )
+    .bind::<Text, _>(table)
+    .load::<TableName>(conn)?
+    .into_iter()
+    .map(|column| quote_ident(&column.name))
+    .collect())
+}
+
+/// Writes every table to `output` from a single snapshot, so the archive is
+/// consistent even while the server is running. Rows are read through a
+/// cursor and written as they arrive.
+pub fn write_backup<W: Write>(
+    conn: &mut PgConnection,
+    output: &mut W,
+) -> Result<BackupHeader, BackupError> {
+    conn.build_transaction()
+        .repeatable_read()
+        .read_only()
+        .run(|conn| {
+            let schema_version = diesel::sql_query(format!(
+                "SELECT version::text AS name FROM {} ORDER BY version DESC LIMIT 1",
+                quote_ident(MIGRATIONS_TABLE)
+            ))
+            .get_result::<TableName>(conn)
+            .optional()?
+            .map(|version| version.name)
+            .ok_or_else(|| BackupError::Migration("no migrations have been run".to_string()))?;
+
+            let tables = ordered_tables(conn)?
+                .into_iter()
+                .map(|name| {
+                    let count = diesel::sql_query(format!(
+                        "SELECT COUNT(*) AS count FROM {}",
+                        quote_ident(&name)
+                    ))
+                    .get_result::<Count>(conn)?
+                    .count;
+
+                    Ok(BackupTable {
+                        name,
+                        rows: count as u64,
+                    })
+                })
+                .collect::<Result<Vec<_>, BackupError>>()?;
+
+            let header = BackupHeader {
+                format: BACKUP_FORMAT.to_string(),
+                version: BACKUP_VERSION,
+                schema_version,
+                created_at: Utc::now(),
+                tables,
+            };
+
+            serde_json::to_writer(&mut *output, &header)
+                .map_err(|e| BackupError::Io(e.into()))?;
+            output.write_all(b"\n")?;
+
+            for table in &header.tables {
+                let table_name = serde_json::to_string(&table.name)
+                    .map_err(|e| BackupError::Io(e.into()))?;
+
+                diesel::sql_query(format!(
+                    "DECLARE backup_rows NO SCROLL CURSOR FOR SELECT row_to_json(t)::text AS row FROM {} t",
+                    quote_ident(&table.name)
+                ))
+                .execute(conn)?;
+
+                loop {
+                    let rows = diesel::sql_query(format!("FETCH {FETCH_SIZE} FROM backup_rows"))
+                        .load::<JsonRow>(conn)?;
+
+                    for row in &rows {
+                        writeln!(output, "{{\"table\":{table_name},\"row\":{}}}", row.row)?;
+                    }
+
+                    if rows.len() < FETCH_SIZE {
+                        break;
+                    }
+                }
+
+                diesel::sql_query("CLOSE backup_rows").execute(conn)?;
+            }
+
+            output.flush()?;
+
+            Ok(header)
+        })
+}
+
+fn insert_rows(
+    conn: &mut PgConnection,
+    table: &str,
+    columns: &[String],
+    rows: &mut Vec<serde_json::Value>,
+) -> Result<(), BackupError> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let columns = columns.join(", ");
+
+    diesel::sql_query(format!(
+        "INSERT INTO {table} ({columns}) SELECT {columns} FROM json_populate_recordset(NULL::{table}, $1)",
+        table = quote_ident(table),
+    ))
+    .bind::<Json, _>(serde_json::Value::Array(std::mem::take(rows)))
+    .execute(conn)?;
+
+    Ok(())
+}
+
+/// Runs the migrations up to and including `schema_version`, so the tables
+/// have the shape they had when the backup was taken.
+fn migrate_to(conn: &mut PgConnection, schema_version: &str) -> Result<(), BackupError> {
+    let applied = conn
+        .applied_migrations()
+        .map_err(|e| BackupError::Migration(e.to_string()))?;
+
+    if applied
+        .iter()
+        .any(|version| version.to_string().as_str() > schema_version)
+    {
+        return Err(BackupError::DatabaseAhead(schema_version.to_string()));
+    }
+
+    let pending = conn
+        .pending_migrations(MIGRATIONS)
+        .map_err(|e| BackupError::Migration(e.to_string()))?;
+
+    for migration in pending
+        .iter()
+        .take_while(|migration| migration.name().version().to_string().as_str() <= schema_version)
+    {
+        conn.run_migration(migration.as_ref())
+            .map_err(|e| BackupError::Migration(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+/// Loads an archive into an empty database. The database is first migrated
+/// to the backup's schema version, the rows are loaded in one transaction,
+/// and then any newer migrations run over the restored data.
+pub fn restore_backup<R: BufRead>(
+    conn: &mut PgConnection,
+    input: R,
+) -> Result<BackupHeader, BackupError> {
+    let mut lines = input.lines();
+
+    let header = match lines.next() {
+        Some(line) => BackupHeader::parse(&line?)?,
+        None => return Err(BackupError::Malformed("the file is empty".to_string())),
+    };
+    header.validate(&known_schema_versions()?)?;
+
+    migrate_to(conn, &header.schema_version)?;
+
+    let existing: HashSet<String> = ordered_tables(conn)?.into_iter().collect();
+
+    for table in &existing {
+        let has_rows = diesel::sql_query(format!(
+            "SELECT EXISTS (SELECT 1 FROM {}) AS exists",
+            quote_ident(table)
+        ))
+        .get_result::<Exists>(conn)?
+        .exists;
+
+        if has_rows {
+            return Err(BackupError::NotEmpty(table.clone()));
+        }
+    }
+
+    if let Some(table) = header
+        .tables
+        .iter()
+        .find(|table| !existing.contains(&table.name))
+    {
+        return Err(BackupError::UnknownTable(table.name.clone()));
+    }
+
+    conn.transaction(|conn| {
+        let mut tables = header.tables.iter();
+        let mut current: Option<(&BackupTable, Vec<String>, u64)> = None;
+        let mut batch = Vec::with_capacity(INSERT_BATCH_SIZE);
+
+        for line in lines.by_ref() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let BackupRow { table, row } =
+                serde_json::from_str(&line).map_err(|e| BackupError::Malformed(e.to_string()))?;
+
+            // Rows arrive grouped by table in header order, so a new table
+            // name means the previous table is finished.
+            while current
+                .as_ref()
+                .is_none_or(|(current, _, _)| current.name != table)
+            {
+                if let Some((finished, columns, found)) = current.take() {
+                    insert_rows(conn, &finished.name, &columns, &mut batch)?;
+                    check_row_count(finished, found)?;
+                }
+
+                let next = tables.next().ok_or_else(|| {
+                    BackupError::Malformed(format!("rows for table {table} are out of order"))
+                })?;
+                current = Some((next, insertable_columns(conn, &next.name)?, 0));
+            }
+
+            if let Some((current, columns, found)) = current.as_mut() {
+                batch.push(row);
+                *found += 1;
+
+                if batch.len() >= INSERT_BATCH_SIZE {
+                    insert_rows(conn, &current.name, columns, &mut batch)?;
+                }
+            }
+        }
+
+        if let Some((finished, columns, found)) = current.take() {
+            insert_rows(conn, &finished.name, &columns, &mut batch)?;
+            check_row_count(finished, found)?;
+        }
+
+        for table in tables {
+            check_row_count(table, 0)?;
+        }
+
+        Ok::<_, BackupError>(())
+    })?;
+
+    conn.run_pending_migrations(MIGRATIONS)
+        .map_err(|e| BackupError::Migration(e.to_string()))?;
+
+    Ok(header)
+}
+
+fn check_row_count(table: &BackupTable, found: u64) -> Result<(), BackupError> {
+    if table.rows == found {
+        Ok(())
+    } else {
+        Err(BackupError::RowCountMismatch {
+            table: table.name.clone(),
+            expected: table.rows,
+            found,
+        })
+    }
+}
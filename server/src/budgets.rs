@@ -0,0 +1,81 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::models::{Budget, BudgetPeriod, ExpenseCategory};
+
+/// The period containing `date`, as `[start, end)`. Weeks start on Monday.
+pub fn period_bounds(period: &BudgetPeriod, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    match period {
+        BudgetPeriod::Weekly => {
+            let start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+            (start, start + Duration::days(7))
+        }
+        BudgetPeriod::Monthly => {
+            let start = date.with_day(1).expect("Every month has a first day");
+            let end = if start.month() == 12 {
+                NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+            } else {
+                NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)
+            }
+            .expect("The first of a month is a valid date");
+            (start, end)
+        }
+        BudgetPeriod::Yearly => (
+            NaiveDate::from_ymd_opt(date.year(), 1, 1).expect("January 1st is a valid date"),
+            NaiveDate::from_ymd_opt(date.year() + 1, 1, 1).expect("January 1st is a valid date"),
+        ),
+    }
+}
+
+/// Whether an expense in `category` counts against the budget.
+pub fn budget_covers(budget: &Budget, category: &ExpenseCategory) -> bool {
+    budget
+        .category
+        .as_ref()
+        .is_none_or(|budget_category| budget_category == category)
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BudgetStatus {
+    #[serde(flatten)]
+    pub budget: Budget,
+    pub period_start: NaiveDate,
+    /// The last day of the period.
+    pub period_end: NaiveDate,
+    pub spent: f64,
+    /// Negative once the budget is exceeded.
+    pub remaining: f64,
+    /// What will have been spent by the end of the period if spending keeps
+    /// its pace so far.
+    pub projected: f64,
+    pub is_exceeded: bool,
+    pub is_projected_to_exceed: bool,
+}
+
+/// Reports on a budget for the period containing `today`, given what has
+/// been spent in that period.
+pub fn budget_status(budget: Budget, spent: f64, today: NaiveDate) -> BudgetStatus {
+    let (start, end) = period_bounds(&budget.period, today);
+
+    let period_days = (end - start).num_days() as f64;
+    let elapsed_days = ((today - start).num_days() + 1).clamp(1, period_days as i64) as f64;
+    let projected = spent / elapsed_days * period_days;
+
+    BudgetStatus {
+        period_start: start,
+        period_end: end - Duration::days(1),
+        spent,
+        remaining: budget.amount - spent,
+        projected,
+        is_exceeded: spent > budget.amount,
+        is_projected_to_exceed: projected > budget.amount,
+        budget,
+    }
+}
+
+/// Whether an expense of `amount`, already counted in the status, is what
+/// took the budget over, rather than landing on one that was already over.
+pub fn was_pushed_over(status: &BudgetStatus, amount: f64) -> bool {
+    status.is_exceeded && status.spent - amount <= status.budget.amount
+}
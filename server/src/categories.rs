@@ -0,0 +1,86 @@
+use crate::models::ExpenseCategory;
+
+pub const CATEGORY_NAME_MAX_CHARS: usize = 50;
+pub const CATEGORY_ICON_MAX_CHARS: usize = 32;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum CategoryError {
+    #[error("category name cannot be empty")]
+    EmptyName,
+    #[error("category name cannot exceed {CATEGORY_NAME_MAX_CHARS} characters")]
+    NameTooLong,
+    #[error("category color must be a hex color like #1f8a70")]
+    InvalidColor,
+    #[error("category icon cannot exceed {CATEGORY_ICON_MAX_CHARS} characters")]
+    IconTooLong,
+}
+
+/// Trims a category name and checks its length.
+pub fn validate_category_name(name: &str) -> Result<String, CategoryError> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(CategoryError::EmptyName);
+    }
+
+    if name.chars().count() > CATEGORY_NAME_MAX_CHARS {
+        return Err(CategoryError::NameTooLong);
+    }
+
+    Ok(name.to_string())
+}
+
+/// Accepts `#RRGGBB` colors and returns them lowercased.
+pub fn validate_category_color(color: &str) -> Result<String, CategoryError> {
+    let color = color.trim();
+
+    match color.strip_prefix('#') {
+        Some(hex) if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Ok(color.to_lowercase())
+        }
+        _ => Err(CategoryError::InvalidColor),
+    }
+}
+
+/// Icons are free-form identifiers (an emoji or an icon name). Blank icons
+/// are treated as no icon.
+pub fn validate_category_icon(icon: &str) -> Result<Option<String>, CategoryError> {
+    let icon = icon.trim();
+
+    if icon.chars().count() > CATEGORY_ICON_MAX_CHARS {
+        return Err(CategoryError::IconTooLong);
+    }
+
+    Ok((!icon.is_empty()).then(|| icon.to_string()))
+}
+
+/// Parses a built-in category from the ways people write it in spreadsheets
+/// and other apps: `food_dining`, `FoodDining` or `Food & Dining`.
+pub fn parse_builtin_category(value: &str) -> Option<ExpenseCategory> {
+    let words: Vec<&str> = value
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let pascal_case = match words.as_slice() {
+        [word]
+            if word.chars().any(|c| c.is_ascii_uppercase())
+                && word.chars().any(|c| c.is_ascii_lowercase()) =>
+        {
+            word.to_string()
+        }
+        _ => words
+            .iter()
+            .map(|word| {
+                let word = word.to_lowercase();
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect(),
+    };
+
+    serde_json::from_value(serde_json::Value::String(pascal_case)).ok()
+}
@@ -0,0 +1,84 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+use anyhow::{Context, anyhow};
+use server::backup::{restore_backup, write_backup};
+use server::establish_connection;
+
+const USAGE: &str = "Usage:
+  server                  Start the API server
+  server backup [FILE]    Write a backup of every table to FILE, or to stdout
+  server restore FILE     Restore a backup into an empty database (- reads stdin)";
+
+/// Runs a command other than the API server. Progress and summaries go to
+/// stderr, so `server backup > medici.backup` leaves a clean archive.
+pub fn run(args: &[String]) -> Result<(), anyhow::Error> {
+    match args {
+        [command] if command == "backup" => backup(None),
+        [command, path] if command == "backup" => backup(Some(path)),
+        [command, path] if command == "restore" => restore(path),
+        [command] if command == "help" || command == "--help" || command == "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(anyhow!(USAGE)),
+    }
+}
+
+fn backup(path: Option<&str>) -> Result<(), anyhow::Error> {
+    let mut conn = establish_connection();
+
+    let header = match path {
+        None => write_backup(&mut conn, &mut BufWriter::new(std::io::stdout().lock()))?,
+        Some(path) => {
+            // Write next to the destination and move it into place once
+            // it's complete, so a failed backup never replaces a good one.
+            let partial = format!("{path}.partial");
+            let file =
+                File::create(&partial).with_context(|| format!("Failed to create {partial}"))?;
+
+            let mut writer = BufWriter::new(file);
+            let header = write_backup(&mut conn, &mut writer)?;
+            writer
+                .into_inner()
+                .map_err(|e| e.into_error())?
+                .sync_all()?;
+
+            std::fs::rename(&partial, path)
+                .with_context(|| format!("Failed to move {partial} to {path}"))?;
+            header
+        }
+    };
+
+    let rows: u64 = header.tables.iter().map(|table| table.rows).sum();
+    eprintln!(
+        "Backed up {} rows from {} tables at schema version {}",
+        rows,
+        header.tables.len(),
+        header.schema_version
+    );
+
+    Ok(())
+}
+
+fn restore(path: &str) -> Result<(), anyhow::Error> {
+    let mut conn = establish_connection();
+
+    let header = if path == "-" {
+        restore_backup(&mut conn, std::io::stdin().lock())?
+    } else {
+        let file = File::open(path).with_context(|| format!("Failed to open {path}"))?;
+        restore_backup(&mut conn, BufReader::new(file))?
+    };
+
+    let rows: u64 = header.tables.iter().map(|table| table.rows).sum();
+    eprintln!(
+        "Restored {} rows into {} tables from a backup taken {} at schema version {}",
+        rows,
+        header.tables.len(),
+        header.created_at.format("%Y-%m-%d %H:%M UTC"),
+        header.schema_version
+    );
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::models::ExpenseComment;
+
+pub const EXPENSE_COMMENT_MAX_CHARS: usize = 2000;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum CommentError {
+    #[error("Comment body cannot be empty")]
+    EmptyBody,
+    #[error("Comment body cannot exceed {EXPENSE_COMMENT_MAX_CHARS} characters")]
+    BodyTooLong,
+    #[error("Comment not found")]
+    NotFound,
+    #[error("Only the author can modify this comment")]
+    NotAuthor,
+}
+
+/// Trims a comment body and checks its length.
+pub fn validate_comment_body(body: &str) -> Result<String, CommentError> {
+    let body = body.trim();
+
+    if body.is_empty() {
+        return Err(CommentError::EmptyBody);
+    }
+
+    if body.chars().count() > EXPENSE_COMMENT_MAX_CHARS {
+        return Err(CommentError::BodyTooLong);
+    }
+
+    Ok(body.to_string())
+}
+
+/// Only the author may edit or delete a comment, and deleted comments can't
+/// be changed at all.
+pub fn ensure_author(comment: &ExpenseComment, member_id: uuid::Uuid) -> Result<(), CommentError> {
+    if comment.is_deleted {
+        return Err(CommentError::NotFound);
+    }
+
+    if comment.author_member_id != member_id {
+        return Err(CommentError::NotAuthor);
+    }
+
+    Ok(())
+}
+
+/// Trims expense notes. Blank notes are treated as no notes.
+pub fn normalize_notes(notes: Option<&str>) -> Option<String> {
+    notes
+        .map(str::trim)
+        .filter(|notes| !notes.is_empty())
+        .map(str::to_string)
+}
@@ -0,0 +1,453 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::{ExpenseCategory, ExpenseWithLineItems, Member, Pool, PoolCategory};
+
+/// The version of the JSON export document. Bump it when the document's
+/// shape changes in a way readers would notice.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// The version of the personal data export from `/api/members/me/export`.
+pub const MEMBER_EXPORT_VERSION: u32 = 1;
+
+pub const DEFAULT_EXPORT_CURRENCY: &str = "USD";
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ExportError {
+    #[error("currency must be 1 to 24 uppercase letters or digits, starting with a letter")]
+    InvalidCurrency,
+    #[error("since cannot be after until")]
+    InvalidDateRange,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub enum ExportFormat {
+    /// One row per line item, for spreadsheets.
+    #[default]
+    Csv,
+    /// A versioned document with the pool, its members, categories,
+    /// expenses and settlements.
+    Json,
+    /// A plain-text journal for hledger and ledger.
+    Hledger,
+    /// A plain-text journal for beancount.
+    Beancount,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Hledger | ExportFormat::Beancount => "text/plain; charset=utf-8",
+        }
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Hledger => "journal",
+            ExportFormat::Beancount => "beancount",
+        }
+    }
+}
+
+/// A settle-up of an expense, taken from the expense event log. Settle-ups
+/// from before the log existed weren't recorded, so expenses settled back
+/// then have `is_settled` set but no settlement.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ExportSettlement {
+    pub expense_id: uuid::Uuid,
+    pub settled_by_member_id: uuid::Uuid,
+    pub settled_at: DateTime<Utc>,
+}
+
+/// Checks a commodity for the ledger formats. Beancount is the stricter of
+/// the two, so its rules apply to both.
+pub fn validate_currency(currency: &str) -> Result<String, ExportError> {
+    let currency = currency.trim();
+
+    let is_valid = (1..=24).contains(&currency.len())
+        && currency.starts_with(|c: char| c.is_ascii_uppercase())
+        && currency
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+
+    if is_valid {
+        Ok(currency.to_string())
+    } else {
+        Err(ExportError::InvalidCurrency)
+    }
+}
+
+/// Splits a name into alphanumeric words, so "Mary-Jane O'Neil" becomes
+/// ["Mary", "Jane", "O", "Neil"].
+fn words(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// The category's name as it's stored, e.g. `food_dining`.
+fn category_key(category: &ExpenseCategory) -> String {
+    let pascal_case = serde_json::to_value(category)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default();
+
+    pascal_case
+        .chars()
+        .enumerate()
+        .flat_map(|(index, c)| {
+            let separator = (index > 0 && c.is_ascii_uppercase()).then_some('_');
+            separator.into_iter().chain(c.to_lowercase())
+        })
+        .collect()
+}
+
+/// Account name components for each member, unique within the pool. Members
+/// whose names collide get the start of their id appended.
+pub fn member_accounts(format: ExportFormat, members: &[Member]) -> HashMap<uuid::Uuid, String> {
+    let component = |member: &Member| {
+        let words = words(&format!("{} {}", member.first_name, member.last_name));
+
+        match format {
+            ExportFormat::Beancount => words.iter().map(|word| capitalize(word)).collect(),
+            _ => words
+                .iter()
+                .map(|word| word.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("-"),
+        }
+    };
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for member in members {
+        *counts.entry(component(member)).or_default() += 1;
+    }
+
+    members
+        .iter()
+        .map(|member| {
+            let name = component(member);
+            // Ids are random, so their first eight digits are almost always
+            // enough to tell members apart; the whole id is used otherwise.
+            let id = member.id.simple().to_string();
+            let short_id = match members
+                .iter()
+                .filter(|other| other.id.simple().to_string()[..8] == id[..8])
+                .count()
+            {
+                1 => id[..8].to_string(),
+                _ => id,
+            };
+
+            let account = match (format, name.is_empty(), counts[&name] > 1) {
+                (ExportFormat::Beancount, true, _) => format!("Member{}", short_id.to_uppercase()),
+                (ExportFormat::Beancount, false, true) => {
+                    format!("{name}{}", short_id.to_uppercase())
+                }
+                (_, true, _) => format!("member-{short_id}"),
+                (_, false, true) => format!("{name}-{short_id}"),
+                (_, false, false) => name,
+            };
+
+            (member.id, account)
+        })
+        .collect()
+}
+
+fn beancount_string(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace(['\n', '\r'], " ")
+    )
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "null".to_string())
+}
+
+fn single_line(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Serialize)]
+struct CsvLine<'a> {
+    expense_id: uuid::Uuid,
+    date: NaiveDate,
+    name: &'a str,
+    description: Option<&'a str>,
+    notes: Option<&'a str>,
+    category: String,
+    custom_category: Option<&'a str>,
+    amount: f64,
+    paid_by: &'a str,
+    is_settled: bool,
+    member: Option<&'a str>,
+    share: Option<f64>,
+}
+
+/// Writes an export a piece at a time, so expenses can be streamed to the
+/// client as they're read. Call `begin` once, `expense` for each expense in
+/// date order, then `finish`.
+pub struct ExportWriter {
+    format: ExportFormat,
+    currency: String,
+    member_names: HashMap<uuid::Uuid, String>,
+    accounts: HashMap<uuid::Uuid, String>,
+    custom_categories: HashMap<uuid::Uuid, String>,
+    opened_accounts: HashSet<String>,
+    wrote_expense: bool,
+}
+
+impl ExportWriter {
+    pub fn new(
+        format: ExportFormat,
+        currency: &str,
+        members: &[Member],
+        custom_categories: &[PoolCategory],
+    ) -> Self {
+        Self {
+            format,
+            currency: currency.to_string(),
+            member_names: members
+                .iter()
+                .map(|member| {
+                    let name = format!("{} {}", member.first_name, member.last_name);
+                    (member.id, name.trim().to_string())
+                })
+                .collect(),
+            accounts: member_accounts(format, members),
+            custom_categories: custom_categories
+                .iter()
+                .map(|category| (category.id, category.name.clone()))
+                .collect(),
+            opened_accounts: HashSet::new(),
+            wrote_expense: false,
+        }
+    }
+
+    pub fn begin(
+        &mut self,
+        pool: &Pool,
+        members: &[Member],
+        custom_categories: &[PoolCategory],
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+        exported_at: DateTime<Utc>,
+    ) -> String {
+        match self.format {
+            ExportFormat::Csv => self.csv_record(&[
+                "expense_id",
+                "date",
+                "name",
+                "description",
+                "notes",
+                "category",
+                "custom_category",
+                "amount",
+                "paid_by",
+                "is_settled",
+                "member",
+                "share",
+            ]),
+            ExportFormat::Json => format!(
+                "{{\"version\":{EXPORT_VERSION},\"exported_at\":{},\"since\":{},\"until\":{},\"pool\":{},\"members\":{},\"categories\":{},\"expenses\":[",
+                to_json(&exported_at),
+                to_json(&since),
+                to_json(&until),
+                to_json(pool),
+                to_json(members),
+                to_json(custom_categories),
+            ),
+            ExportFormat::Hledger => format!(
+                "; {} exported {}\n; Shares are booked to expenses:<category>:<member> and\n; payments to assets:pool:<member>. Unsettled expenses are marked !.\n\n",
+                single_line(&pool.name),
+                exported_at.format("%Y-%m-%d %H:%M UTC"),
+            ),
+            ExportFormat::Beancount => format!(
+                "; {} exported {}\n; Shares are booked to Expenses:<Category>:<Member> and\n; payments to Assets:Pool:<Member>. Unsettled expenses are flagged !.\n\noption \"operating_currency\" {}\n\n",
+                single_line(&pool.name),
+                exported_at.format("%Y-%m-%d %H:%M UTC"),
+                beancount_string(&self.currency),
+            ),
+        }
+    }
+
+    pub fn expense(&mut self, expense: &ExpenseWithLineItems) -> String {
+        let chunk = match self.format {
+            ExportFormat::Csv => self.csv_expense(expense),
+            ExportFormat::Json => format!(
+                "{}{}",
+                if self.wrote_expense { "," } else { "" },
+                to_json(expense)
+            ),
+            ExportFormat::Hledger | ExportFormat::Beancount => self.ledger_expense(expense),
+        };
+
+        self.wrote_expense = true;
+
+        chunk
+    }
+
+    pub fn finish(&mut self, settlements: &[ExportSettlement]) -> String {
+        match self.format {
+            ExportFormat::Json => format!("],\"settlements\":{}}}", to_json(settlements)),
+            _ => String::new(),
+        }
+    }
+
+    fn csv_record<T: AsRef<[u8]>>(&self, record: &[T]) -> String {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        let _ = writer.write_record(record);
+        String::from_utf8(writer.into_inner().unwrap_or_default()).unwrap_or_default()
+    }
+
+    fn csv_expense(&self, expense: &ExpenseWithLineItems) -> String {
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(Vec::new());
+
+        let member_name =
+            |id: &uuid::Uuid| self.member_names.get(id).map(String::as_str).unwrap_or("");
+
+        let line = |member: Option<&uuid::Uuid>, share: Option<f64>| CsvLine {
+            expense_id: expense.id,
+            date: expense.incurred_on,
+            name: &expense.name,
+            description: expense.description.as_deref(),
+            notes: expense.notes.as_deref(),
+            category: category_key(&expense.category),
+            custom_category: expense
+                .custom_category_id
+                .and_then(|id| self.custom_categories.get(&id))
+                .map(String::as_str),
+            amount: expense.amount,
+            paid_by: member_name(&expense.paid_by_member_id),
+            is_settled: expense.is_settled,
+            member: member.map(member_name),
+            share,
+        };
+
+        if expense.line_items.is_empty() {
+            let _ = writer.serialize(line(None, None));
+        }
+
+        for item in &expense.line_items {
+            let _ = writer.serialize(line(Some(&item.debtor_member_id), Some(item.amount)));
+        }
+
+        String::from_utf8(writer.into_inner().unwrap_or_default()).unwrap_or_default()
+    }
+
+    fn account(&self, root: &str, middle: &str, member_id: &uuid::Uuid) -> String {
+        let member = self
+            .accounts
+            .get(member_id)
+            .cloned()
+            .unwrap_or_else(|| member_id.simple().to_string()[..8].to_string());
+
+        match self.format {
+            ExportFormat::Beancount => format!("{root}:{middle}:{member}"),
+            _ => format!("{}:{}:{member}", root.to_lowercase(), middle.to_lowercase()),
+        }
+    }
+
+    fn amount(&self, amount: f64) -> String {
+        format!("{amount:.2} {}", self.currency)
+    }
+
+    /// Each share is a posting to the debtor's expense account. The payer's
+    /// posting is left for the journal to balance, so expenses whose line
+    /// items don't add up still load.
+    fn ledger_expense(&mut self, expense: &ExpenseWithLineItems) -> String {
+        let category = match self.format {
+            ExportFormat::Beancount => words(&category_key(&expense.category).replace('_', " "))
+                .iter()
+                .map(|word| capitalize(word))
+                .collect::<String>(),
+            _ => category_key(&expense.category),
+        };
+
+        let shares: Vec<(String, f64)> = if expense.line_items.is_empty() {
+            vec![(
+                self.account("Expenses", &category, &expense.paid_by_member_id),
+                expense.amount,
+            )]
+        } else {
+            expense
+                .line_items
+                .iter()
+                .map(|item| {
+                    (
+                        self.account("Expenses", &category, &item.debtor_member_id),
+                        item.amount,
+                    )
+                })
+                .collect()
+        };
+
+        let payer_account = self.account("Assets", "Pool", &expense.paid_by_member_id);
+        let flag = if expense.is_settled { "*" } else { "!" };
+        let date = expense.incurred_on.format("%Y-%m-%d");
+
+        let mut chunk = String::new();
+
+        match self.format {
+            ExportFormat::Beancount => {
+                for account in shares
+                    .iter()
+                    .map(|(account, _)| account)
+                    .chain([&payer_account])
+                {
+                    if self.opened_accounts.insert(account.clone()) {
+                        chunk += &format!("{date} open {account} {}\n", self.currency);
+                    }
+                }
+
+                chunk += &format!("{date} {flag} {}\n", beancount_string(&expense.name));
+                chunk += &format!(
+                    "    expense_id: {}\n",
+                    beancount_string(&expense.id.to_string())
+                );
+                if let Some(description) = &expense.description {
+                    chunk += &format!("    description: {}\n", beancount_string(description));
+                }
+            }
+            _ => {
+                chunk += &format!(
+                    "{date} {flag} {}  ; expense_id:{}\n",
+                    single_line(&expense.name),
+                    expense.id
+                );
+                if let Some(description) = &expense.description {
+                    chunk += &format!("    ; {}\n", single_line(description));
+                }
+            }
+        }
+
+        for (account, amount) in &shares {
+            chunk += &format!("    {account}  {}\n", self.amount(*amount));
+        }
+        chunk += &format!("    {payer_account}\n\n");
+
+        chunk
+    }
+}
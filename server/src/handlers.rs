@@ -1,9 +1,13 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
 use std::sync::OnceLock;
-use std::time::{Duration as BuiltInDuration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration as BuiltInDuration, Instant};
 
-use axum::extract::{MatchedPath, Query, FromRequestParts};
-use axum::http::{StatusCode, request::Parts};
+use axum::extract::multipart::MultipartError;
+use axum::extract::{DefaultBodyLimit, FromRequestParts, MatchedPath, Multipart, Query};
+use axum::http::{StatusCode, header, request::Parts};
 use axum::middleware;
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::{Json, extract::Path};
 use axum::{
     extract::Request,
@@ -14,10 +18,10 @@ use axum_extra::TypedHeader;
 use axum_extra::headers::Authorization;
 use axum_extra::headers::authorization::Bearer;
 use bcrypt::{DEFAULT_COST, hash_with_salt};
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use diesel::Connection;
 use diesel::pg::PgConnection;
 use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
-use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use once_cell::sync::Lazy;
 use opentelemetry::KeyValue;
 use opentelemetry::global::{self, BoxedTracer};
@@ -27,13 +31,58 @@ use opentelemetry_otlp::tonic_types::metadata::MetadataMap;
 use opentelemetry_otlp::{Protocol, WithExportConfig, WithTonicConfig};
 use opentelemetry_sdk::Resource;
 use opentelemetry_sdk::trace::{BatchConfigBuilder, BatchSpanProcessor, SdkTracerProvider};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use server::analytics::{
+    DEFAULT_TIME_SERIES_BUCKETS, MAX_TIME_SERIES_BUCKETS, TimeSeriesInterval, TimeSeriesPoint,
+    group_time_series,
+};
+use server::auth::{
+    ACTIVE_MEMBER_TTL, ActiveMembers, AuthError, ensure_active, issue_token, verify_token,
+};
+use server::budgets::{BudgetStatus, budget_covers, was_pushed_over};
+use server::categories::{
+    CategoryError, validate_category_color, validate_category_icon, validate_category_name,
+};
+use server::comments::{CommentError, ensure_author, normalize_notes, validate_comment_body};
 use server::compute_balances_for_member;
+use server::export::{
+    DEFAULT_EXPORT_CURRENCY, ExportError, ExportFormat, ExportSettlement, ExportWriter,
+    validate_currency,
+};
+use server::import::{
+    ColumnMapping, ImportError, ImportLineItem, ImportMember, ImportRow, ImportRowError,
+    plan_import, resolve_member,
+};
+use server::mail::{Mailer, mailer_from_env};
 use server::models::{
-    self, Expense, ExpenseCategory, ExpenseCategoryRule, Friendship, Member, MemberChangeset,
-    MemberPassword, NewExpenseCategoryRule, NewExpenseLineItem, NewPool, PoolMembership,
-    SplitMethod,
+    self, Budget, BudgetChangeset, BudgetPeriod, CategoryTotal, Expense, ExpenseAttachment,
+    ExpenseCategory, ExpenseCategoryRule, ExpenseComment, ExpenseEvent, ExpenseEventAction,
+    Friendship, Member, MemberChangeset, MemberDataExport, MemberPassword, NewBudget,
+    NewExpenseAttachment, NewExpenseCategoryRule, NewExpenseEvent, NewExpenseLineItem, NewPool,
+    NewPoolCategory, NewWebhook, Notification, NotificationPreferences,
+    NotificationPreferencesChangeset, PoolCategory, PoolCategoryChangeset, PoolEmailPreferences,
+    PoolEmailPreferencesChangeset, PoolMembership, SplitMethod, Tag, TagTotal, TagUsage, Webhook,
+    WebhookChangeset, WebhookDelivery, WebhookEvent,
+};
+use server::pagination::{ExpenseCursor, Page, decode_cursor, encode_cursor, page_limit};
+use server::realtime::PoolFeedMessage;
+use server::rules::{
+    RuleSubject, changes_category, count_by_category, find_matching_rule, plan_recategorization,
+    rule_matches, validate_rule,
+};
+use server::search::parse_search_query;
+use server::splitwise::{
+    SplitwiseBalanceCheck, check_balances, find_duplicate_mapping, parse_splitwise_export,
+    splitwise_expense_rows,
+};
+use server::storage::{BlobStore, StorageError, blob_store_from_env, sniff_content_type};
+use server::suggestions::{CategorySuggester, CategorySuggestion, RuleProposal, propose_rules};
+use server::tags::{TagError, normalize_tags, parse_tag_filter};
+use server::webhooks::{
+    AllowedHosts, WebhookError, generate_secret, validate_events, validate_webhook_url,
 };
+use tokio::sync::broadcast::{self, error::RecvError, error::TryRecvError};
+use tokio_stream::wrappers::ReceiverStream;
 use utoipa::ToSchema;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
@@ -124,15 +173,23 @@ fn hash_password(password: &str) -> String {
         .to_string()
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Claims {
-    pub sub: String,
-    pub exp: usize,
-    pub iat: usize,
-}
-
 pub struct AuthenticatedUser(pub uuid::Uuid);
 
+/// Members whose accounts were recently confirmed to still exist.
+static ACTIVE_MEMBERS: Lazy<ActiveMembers> = Lazy::new(|| ActiveMembers::new(ACTIVE_MEMBER_TTL));
+
+fn auth_error(error: AuthError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = match error {
+        AuthError::InvalidToken | AuthError::Deleted => StatusCode::UNAUTHORIZED,
+        AuthError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    (
+        status,
+        Json(serde_json::json!({"error": error.to_string()})),
+    )
+}
+
 impl<S> FromRequestParts<S> for AuthenticatedUser
 where
     S: Send + Sync,
@@ -151,56 +208,40 @@ where
                 )
             })?;
 
-        let user_id = verify_jwt(auth.token()).map_err(|_| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({
-                    "error": "Invalid token"
-                })),
-            )
-        })?;
-
-        Ok(AuthenticatedUser(user_id))
-    }
-}
-
-fn generate_jwt(member_id: uuid::Uuid) -> Result<String, jsonwebtoken::errors::Error> {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
-        .as_secs() as usize;
+        let user_id = verify_jwt(auth.token()).map_err(auth_error)?;
 
-    let expiration = now + 7 * 24 * 60 * 60;
+        if !ACTIVE_MEMBERS.contains(user_id, Instant::now()) {
+            let mut conn = get_db_connection().await.map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({"error": "Failed to get database connection"})),
+                )
+            })?;
 
-    let claims = Claims {
-        sub: member_id.to_string(),
-        exp: expiration,
-        iat: now,
-    };
+            tokio::task::spawn_blocking(move || ensure_active(&mut conn, user_id))
+                .await
+                .expect("Task panicked")
+                .map_err(auth_error)?;
 
-    let secret = std::env::var("AUTH_SECRET_KEY").expect("AUTH_SECRET_KEY must be set");
+            ACTIVE_MEMBERS.insert(user_id, Instant::now());
+        }
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
+        Ok(AuthenticatedUser(user_id))
+    }
 }
 
-fn verify_jwt(token: &str) -> Result<uuid::Uuid, jsonwebtoken::errors::Error> {
-    let secret = std::env::var("AUTH_SECRET_KEY").expect("AUTH_SECRET_KEY must be set");
-
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret.as_bytes()),
-        &Validation::new(Algorithm::HS256),
-    )?;
+fn auth_secret() -> Vec<u8> {
+    std::env::var("AUTH_SECRET_KEY")
+        .expect("AUTH_SECRET_KEY must be set")
+        .into_bytes()
+}
 
-    let user_id = uuid::Uuid::parse_str(&token_data.claims.sub).map_err(|_| {
-        jsonwebtoken::errors::Error::from(jsonwebtoken::errors::ErrorKind::InvalidToken)
-    })?;
+fn generate_jwt(member_id: uuid::Uuid) -> Result<String, jsonwebtoken::errors::Error> {
+    issue_token(member_id, &auth_secret())
+}
 
-    Ok(user_id)
+fn verify_jwt(token: &str) -> Result<uuid::Uuid, AuthError> {
+    verify_token(token, &auth_secret())
 }
 
 #[derive(Serialize, ToSchema)]
@@ -295,9 +336,37 @@ pub struct ExpenseInput {
     name: String,
     amount: f64,
     line_items: Vec<ExpenseLineItem>,
-    category: ExpenseCategory,
+    /// Left out, or overridden by `auto_categorize`, to pick the category
+    /// from the payer's rules.
+    category: Option<ExpenseCategory>,
+    /// A custom category of the pool. Takes precedence over `category`,
+    /// which is then set to the custom category's parent.
+    custom_category_id: Option<uuid::Uuid>,
+    #[serde(default)]
+    auto_categorize: bool,
     description: Option<String>,
     split_method: SplitMethod,
+    incurred_on: Option<NaiveDate>,
+    notes: Option<String>,
+    /// Free-form tags. Tags the pool hasn't used before are created.
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AddExpenseResult {
+    #[serde(flatten)]
+    expense: Expense,
+    /// The payer's rule that chose the category, if the category was
+    /// auto-categorized.
+    applied_rule: Option<ExpenseCategoryRule>,
+    tags: Vec<String>,
+    /// Whether the expense pushed any of the pool's budgets over for the
+    /// period it was incurred in.
+    budget_exceeded: bool,
+    /// The budgets the expense pushed over for that period. Budgets that were
+    /// already over before it was added aren't included.
+    exceeded_budgets: Vec<BudgetStatus>,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -359,8 +428,8 @@ pub async fn create_pool_handler(
     let pool_id = pool.id;
 
     let new_membership = models::NewPoolMembership {
-        pool_id: pool_id,
-        member_id: member_id,
+        pool_id,
+        member_id,
         role: models::PoolRole::ADMIN,
         default_split_percentage: 100.0,
     };
@@ -394,6 +463,7 @@ pub async fn create_pool_handler(
     )
 )]
 pub async fn add_friend_to_pool_handler(
+    AuthenticatedUser(actor_member_id): AuthenticatedUser,
     Path(pool_id): Path<uuid::Uuid>,
     Json(input): Json<PoolMembershipInput>,
 ) -> Json<PoolMembership> {
@@ -406,14 +476,23 @@ pub async fn add_friend_to_pool_handler(
 
     span.set_attribute(KeyValue::new("pool_id", pool_id.to_string()));
     span.set_attribute(KeyValue::new("member_id", input.member_id.to_string()));
+    span.set_attribute(KeyValue::new(
+        "actor_member_id",
+        actor_member_id.to_string(),
+    ));
 
     let mut conn = get_db_connection()
         .await
         .expect("Failed to get database connection");
 
     let result = tokio::task::spawn_blocking(move || {
-        PoolMembership::add_member(&mut conn, pool_id, input.member_id)
-            .expect("Failed to add friend to pool")
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let membership = PoolMembership::add_member(conn, pool_id, input.member_id)?;
+            Notification::send_pool_invitation(conn, pool_id, input.member_id, actor_member_id)?;
+
+            Ok(membership)
+        })
+        .expect("Failed to add friend to pool")
     })
     .await
     .expect("Task panicked");
@@ -966,7 +1045,9 @@ pub async fn get_expense_handler(
         ("expense_id" = uuid::Uuid, Path, description = "ID of the expense to delete")
     ),
     responses(
-        (status = 200, description = "The deleted expense", body = models::Expense),
+        (status = 200, description = "The expense, moved to the pool's trash", body = models::Expense),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Expense not found in the pool"),
         (status = 500, description = "Internal server error")
     )
 )]
@@ -985,12 +1066,14 @@ pub async fn delete_expense_handler(
     span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
     span.set_attribute(KeyValue::new("expense_id", path.expense_id.to_string()));
 
+    ensure_pool_member(path.pool_id, member_id).await?;
+
     let mut conn = get_db_connection()
         .await
         .expect("Failed to get database connection");
 
     let expense = tokio::task::spawn_blocking(move || {
-        models::Expense::delete(&mut conn, path.expense_id, path.pool_id, false)
+        Expense::soft_delete(&mut conn, path.expense_id, path.pool_id, member_id)
     })
     .await
     .expect("Task panicked");
@@ -1083,11 +1166,16 @@ pub async fn signup_handler(
     ),
     request_body = ExpenseInput,
     responses(
-        (status = 200, description = "Create expense", body = Expense),
+        (status = 200, description = "Create expense", body = AddExpenseResult),
+        (status = 400, description = "Unknown custom category or invalid tags"),
+        (status = 403, description = "Not a member of the pool"),
         (status = 500, description = "Internal server error")
     )
 )]
-pub async fn add_expense_handler(Json(input): Json<ExpenseInput>) -> Json<models::Expense> {
+pub async fn add_expense_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Json(input): Json<ExpenseInput>,
+) -> Result<Json<AddExpenseResult>, (StatusCode, Json<serde_json::Value>)> {
     let tracer = get_tracer();
 
     let mut span = tracer
@@ -1095,6 +1183,7 @@ pub async fn add_expense_handler(Json(input): Json<ExpenseInput>) -> Json<models
         .with_kind(SpanKind::Server)
         .start(tracer);
 
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
     span.set_attribute(KeyValue::new("pool_id", input.pool_id.to_string()));
     span.set_attribute(KeyValue::new(
         "paid_by_member_id",
@@ -1102,20 +1191,43 @@ pub async fn add_expense_handler(Json(input): Json<ExpenseInput>) -> Json<models
     ));
     span.set_attribute(KeyValue::new("amount", input.amount.to_string()));
 
+    ensure_pool_member(input.pool_id, member_id).await?;
+
+    let tags = normalize_tags(&input.tags).map_err(tag_error)?;
+
+    let custom_category = match input.custom_category_id {
+        Some(category_id) => Some(
+            find_pool_category(category_id, input.pool_id)
+                .await
+                .map_err(|(_, body)| (StatusCode::BAD_REQUEST, body))?,
+        ),
+        None => None,
+    };
+
     let mut conn = get_db_connection()
         .await
         .expect("Failed to get database connection");
 
-    let new_expense = models::NewExpense {
+    let should_auto_categorize =
+        input.auto_categorize || (input.category.is_none() && custom_category.is_none());
+
+    let mut new_expense = models::NewExpense {
         name: input.name,
         amount: input.amount,
         is_settled: false,
         pool_id: input.pool_id,
         paid_by_member_id: input.paid_by_member_id,
         description: input.description,
-        notes: None,
-        category: input.category,
+        notes: normalize_notes(input.notes.as_deref()),
+        category: custom_category
+            .as_ref()
+            .map(PoolCategory::builtin_category)
+            .or(input.category)
+            .unwrap_or(ExpenseCategory::Miscellaneous),
         split_method: input.split_method,
+        incurred_on: input.incurred_on,
+        custom_category_id: custom_category.map(|category| category.id),
+        categorized_by_rule: false,
     };
 
     let debtor_member_ids: Vec<uuid::Uuid> = input
@@ -1126,21 +1238,93 @@ pub async fn add_expense_handler(Json(input): Json<ExpenseInput>) -> Json<models
 
     let amounts: Vec<f64> = input.line_items.iter().map(|item| item.amount).collect();
 
-    let (expense, _line_items) = tokio::task::spawn_blocking(move || {
-        models::Expense::create_with_line_items(
-            &mut conn,
-            &new_expense,
-            &debtor_member_ids,
-            &amounts,
-        )
-        .expect("Failed to create expense with line items")
+    let (expense, applied_rule, tags, exceeded_budgets) = tokio::task::spawn_blocking(move || {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut applied_rule = None;
+
+            if should_auto_categorize {
+                let rules =
+                    ExpenseCategoryRule::find_for_member(conn, new_expense.paid_by_member_id)?;
+
+                let subject = RuleSubject {
+                    name: &new_expense.name,
+                    amount: new_expense.amount,
+                    pool_id: new_expense.pool_id,
+                };
+
+                if let Some(rule) = find_matching_rule(&rules, &subject) {
+                    new_expense.category = rule.category.clone();
+                    new_expense.custom_category_id = rule.custom_category_id;
+                    new_expense.categorized_by_rule = true;
+                    applied_rule = Some(rule.clone());
+                }
+            }
+
+            let (expense, line_items) = models::Expense::create_with_line_items(
+                conn,
+                &new_expense,
+                &debtor_member_ids,
+                &amounts,
+            )?;
+
+            let tags = Tag::set_for_expense(conn, expense.id, expense.pool_id, &tags)?
+                .into_iter()
+                .map(|tag| tag.name)
+                .collect();
+
+            let snapshot = expense.clone().with_line_items(line_items);
+            ExpenseEvent::record(
+                conn,
+                &NewExpenseEvent::new(
+                    ExpenseEventAction::Created,
+                    member_id,
+                    None,
+                    Some(&snapshot),
+                ),
+            )?;
+            Notification::send_expense_added(conn, &snapshot, member_id)?;
+
+            let exceeded_budgets: Vec<BudgetStatus> = Budget::list_for_pool(conn, expense.pool_id)?
+                .into_iter()
+                .filter(|budget| budget_covers(budget, &expense.category))
+                .map(|budget| budget.status_on(conn, expense.incurred_on))
+                .collect::<Result<Vec<_>, diesel::result::Error>>()?
+                .into_iter()
+                .filter(|status| was_pushed_over(status, expense.amount))
+                .collect();
+
+            Ok((expense, applied_rule, tags, exceeded_budgets))
+        })
     })
     .await
-    .expect("Task panicked");
+    .expect("Task panicked")
+    .map_err(|e| {
+        tracing::error!("Failed to create expense: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Failed to create expense"})),
+        )
+    })?;
 
     span.end();
 
-    Json(expense)
+    Ok(Json(AddExpenseResult {
+        expense,
+        applied_rule,
+        tags,
+        budget_exceeded: !exceeded_budgets.is_empty(),
+        exceeded_budgets,
+    }))
+}
+
+/// Tells a field that was set to `null` (`Some(None)`) apart from one that was
+/// left out (`None`).
+fn deserialize_nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -1148,10 +1332,20 @@ pub struct UpdateExpenseInput {
     name: Option<String>,
     amount: Option<f64>,
     line_items: Option<Vec<ExpenseLineItem>>,
+    /// Setting a built-in category clears any custom category.
     category: Option<ExpenseCategory>,
+    /// A custom category of the pool. Takes precedence over `category`.
+    custom_category_id: Option<uuid::Uuid>,
     description: Option<String>,
     is_settled: Option<bool>,
     split_method: Option<SplitMethod>,
+    incurred_on: Option<NaiveDate>,
+    /// `null` or an empty string clears the notes.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    #[schema(value_type = Option<String>)]
+    notes: Option<Option<String>>,
+    /// Replaces the expense's tags.
+    tags: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -1170,13 +1364,17 @@ pub struct UpdateExpensePath {
     request_body = UpdateExpenseInput,
     responses(
         (status = 200, description = "Create expense", body = Expense),
+        (status = 400, description = "Unknown custom category or invalid tags"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Expense not found in the pool"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn update_expense_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
     Path(path): Path<UpdateExpensePath>,
     Json(input): Json<UpdateExpenseInput>,
-) -> Json<models::Expense> {
+) -> Result<Json<models::Expense>, (StatusCode, Json<serde_json::Value>)> {
     let tracer = get_tracer();
 
     let mut span = tracer
@@ -1184,57 +1382,117 @@ pub async fn update_expense_handler(
         .with_kind(SpanKind::Server)
         .start(tracer);
 
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
     span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
     span.set_attribute(KeyValue::new("expense_id", path.expense_id.to_string()));
 
+    ensure_pool_member(path.pool_id, member_id).await?;
+    ensure_expense_in_pool(path.expense_id, path.pool_id).await?;
+
+    let tags = input
+        .tags
+        .as_deref()
+        .map(normalize_tags)
+        .transpose()
+        .map_err(tag_error)?;
+
+    let (category, custom_category_id) = match input.custom_category_id {
+        Some(category_id) => {
+            let custom_category = find_pool_category(category_id, path.pool_id)
+                .await
+                .map_err(|(_, body)| (StatusCode::BAD_REQUEST, body))?;
+
+            (
+                Some(custom_category.builtin_category()),
+                Some(Some(custom_category.id)),
+            )
+        }
+        None => {
+            let clears_custom_category = input.category.as_ref().map(|_| None);
+            (input.category, clears_custom_category)
+        }
+    };
+
+    // Choosing a category by hand overrides the rule that picked it.
+    let categorized_by_rule = category.as_ref().map(|_| false);
+
     let mut conn = get_db_connection()
         .await
         .expect("Failed to get database connection");
 
-    let result = conn
-        .build_transaction()
-        .run(|tx| {
-            let changeset = models::ExpenseChangeset {
-                name: input.name,
-                amount: input.amount,
-                is_settled: input.is_settled,
-                description: input.description,
-                notes: None,
-                category: input.category,
-                split_method: input.split_method,
-            };
+    let result = conn.build_transaction().run(|tx| {
+        let before = Expense::snapshot(tx, path.expense_id)?;
+
+        let changeset = models::ExpenseChangeset {
+            name: input.name,
+            amount: input.amount,
+            is_settled: input.is_settled,
+            description: input.description,
+            notes: input.notes.map(|notes| normalize_notes(notes.as_deref())),
+            category,
+            split_method: input.split_method,
+            incurred_on: input.incurred_on,
+            custom_category_id,
+            categorized_by_rule,
+        };
 
-            let updated_expense = Expense::update(tx, &path.expense_id, &changeset);
+        let updated_expense = Expense::update(tx, &path.expense_id, path.pool_id, &changeset)?;
 
-            let _ = models::ExpenseLineItem::delete_by_expense_id(tx, path.expense_id);
+        // Only a new split replaces the old one; edits that leave it out
+        // keep the shares as they are.
+        if let Some(line_items) = input.line_items.as_ref() {
+            models::ExpenseLineItem::delete_by_expense_id(tx, path.expense_id)?;
 
-            if input.line_items.is_some() {
-                let mut new_line_items: Vec<NewExpenseLineItem> = Vec::new();
+            let mut new_line_items: Vec<NewExpenseLineItem> = Vec::new();
 
-                for line_item in input.line_items.as_ref().unwrap() {
-                    let line_item = NewExpenseLineItem {
-                        expense_id: path.expense_id,
-                        is_settled: false,
-                        amount: line_item.amount,
-                        debtor_member_id: line_item.debtor_member_id,
-                    };
+            for line_item in line_items {
+                let line_item = NewExpenseLineItem {
+                    expense_id: path.expense_id,
+                    is_settled: false,
+                    amount: line_item.amount,
+                    debtor_member_id: line_item.debtor_member_id,
+                };
 
-                    new_line_items.push(line_item);
-                }
+                new_line_items.push(line_item);
+            }
 
-                let _ = models::ExpenseLineItem::bulk_create(tx, &new_line_items);
-            };
+            models::ExpenseLineItem::bulk_create(tx, &new_line_items)?;
+        };
 
-            return updated_expense;
-        })
-        .map_err(|e| {
-            eprintln!("Transaction failed: {}", e);
-            diesel::result::Error::RollbackTransaction
-        });
+        if let Some(tags) = tags.as_ref() {
+            Tag::set_for_expense(tx, path.expense_id, path.pool_id, tags)?;
+        }
+
+        let after = Expense::snapshot(tx, path.expense_id)?;
+        ExpenseEvent::record(
+            tx,
+            &NewExpenseEvent::new(
+                ExpenseEventAction::Updated,
+                member_id,
+                Some(&before),
+                Some(&after),
+            ),
+        )?;
+
+        Ok::<_, diesel::result::Error>(updated_expense)
+    });
 
     span.end();
 
-    Json(result.expect("Failed to update expense"))
+    result.map(Json).map_err(|e| match e {
+        // Trashed since the check above.
+        diesel::result::Error::NotFound => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Expense not found"})),
+        ),
+        e => {
+            tracing::error!("Failed to update expense: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to update expense"})),
+            )
+        }
+    })
 }
 
 #[derive(Serialize, ToSchema)]
@@ -1330,7 +1588,11 @@ pub async fn settle_up_pool_handler(
         .await
         .expect("Failed to get database connection");
 
-    tokio::task::spawn_blocking(move || models::Pool::settle_up(&mut conn, pool_id, member_id));
+    tokio::task::spawn_blocking(move || {
+        models::Pool::settle_up(&mut conn, pool_id, member_id).expect("Failed to settle up pool")
+    })
+    .await
+    .expect("Task panicked");
 
     let mut conn = get_db_connection()
         .await
@@ -1434,16 +1696,20 @@ pub struct RecentExpenseDetails {
     #[serde(flatten)]
     expense: models::Expense,
     line_amount: f64,
+    tags: Vec<String>,
 }
 
 #[derive(Deserialize, ToSchema)]
 pub struct RecentExpensesQuery {
     limit: Option<i64>,
     category: Option<ExpenseCategory>,
+    custom_category_id: Option<uuid::Uuid>,
+    tags: Option<String>,
     is_settled: bool,
     paid_by_member_id: Option<uuid::Uuid>,
     since: Option<chrono::DateTime<Utc>>,
     until: Option<chrono::DateTime<Utc>>,
+    cursor: Option<String>,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -1457,14 +1723,18 @@ pub struct RecentExpensesPath {
     params(
         ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to fetch expenses for"),
         ("category" = Option<ExpenseCategory>, Query, description = "Filter expenses by category"),
-        ("limit" = Option<i64>, Query, description = "Limit the number of expenses returned"),
+        ("custom_category_id" = Option<uuid::Uuid>, Query, description = "Filter expenses by custom category"),
+        ("tags" = Option<String>, Query, description = "Comma-separated tags. Only expenses with every listed tag are returned"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of expenses per page (default 50, max 200)"),
+        ("cursor" = Option<String>, Query, description = "The `next_cursor` of the previous page"),
         ("is_settled" = bool, Query, description = "Filter expenses by settle status"),
         ("paid_by_member_id" = Option<uuid::Uuid>, Query, description = "Filter expenses by the member who paid"),
-        ("since" = Option<chrono::DateTime<Utc>>, Query, description = "Filter expenses since a specific date"),
-        ("until" = Option<chrono::DateTime<Utc>>, Query, description = "Filter expenses until a specific date"),
+        ("since" = Option<chrono::DateTime<Utc>>, Query, description = "Filter expenses incurred on or after a specific date"),
+        ("until" = Option<chrono::DateTime<Utc>>, Query, description = "Filter expenses incurred on or before a specific date"),
     ),
     responses(
-        (status = 200, description = "Create expense", body = Vec<RecentExpenseDetails>),
+        (status = 200, description = "The caller's expenses in the pool, newest first", body = Page<RecentExpenseDetails>),
+        (status = 400, description = "Invalid cursor"),
         (status = 500, description = "Internal server error")
     )
 )]
@@ -1472,7 +1742,7 @@ pub async fn get_pool_recent_expenses_handler(
     AuthenticatedUser(member_id): AuthenticatedUser,
     Path(path): Path<RecentExpensesPath>,
     Query(query): Query<RecentExpensesQuery>,
-) -> Json<Vec<RecentExpenseDetails>> {
+) -> Result<Json<Page<RecentExpenseDetails>>, (StatusCode, Json<serde_json::Value>)> {
     let tracer = get_tracer();
 
     let mut span = tracer
@@ -1480,48 +1750,79 @@ pub async fn get_pool_recent_expenses_handler(
         .with_kind(SpanKind::Server)
         .start(tracer);
 
-    let limit = query.limit.unwrap_or(5);
-    let since = query
-        .since
-        .unwrap_or_else(|| Utc::now() - chrono::Duration::days(365));
-    let until = query.until.unwrap_or_else(|| Utc::now());
+    let limit = page_limit(query.limit);
+    let since = query.since.map(|since| since.date_naive());
+    let until = query.until.map(|until| until.date_naive());
+
+    let cursor = query
+        .cursor
+        .as_deref()
+        .map(decode_cursor::<ExpenseCursor>)
+        .transpose()
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+        })?;
 
     span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
     span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
     span.set_attribute(KeyValue::new("limit", limit.to_string()));
 
+    let tags = query
+        .tags
+        .as_deref()
+        .map(parse_tag_filter)
+        .unwrap_or_default();
+
     let mut conn = get_db_connection()
         .await
         .expect("Failed to get database connection");
 
-    let expenses = tokio::task::spawn_blocking(move || {
-        models::Expense::get_recent_for_member_in_pool(
+    let (expenses, mut tags_by_expense) = tokio::task::spawn_blocking(move || {
+        let expenses = models::Expense::get_recent_for_member_in_pool(
             &mut conn,
             path.pool_id,
             member_id,
-            limit,
+            limit + 1,
             query.category,
+            query.custom_category_id,
+            &tags,
             query.paid_by_member_id,
             query.is_settled,
             since,
             until,
+            cursor.as_ref(),
         )
-        .expect("Failed to get recent expenses")
+        .expect("Failed to get recent expenses");
+
+        let expense_ids: Vec<uuid::Uuid> = expenses.iter().map(|(e, _)| e.id).collect();
+        let tags_by_expense =
+            Tag::names_for_expenses(&mut conn, &expense_ids).expect("Failed to get expense tags");
+
+        (expenses, tags_by_expense)
     })
     .await
     .expect("Task panicked");
 
     span.end();
 
-    Json(
-        expenses
-            .into_iter()
-            .map(|(expense, line_amount)| RecentExpenseDetails {
-                expense,
-                line_amount,
-            })
-            .collect(),
-    )
+    let expenses = expenses
+        .into_iter()
+        .map(|(expense, line_amount)| RecentExpenseDetails {
+            tags: tags_by_expense.remove(&expense.id).unwrap_or_default(),
+            expense,
+            line_amount,
+        })
+        .collect();
+
+    Ok(Json(Page::from_rows(expenses, limit, |details| {
+        encode_cursor(&ExpenseCursor {
+            incurred_on: details.expense.incurred_on,
+            id: details.expense.id,
+        })
+    })))
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -1569,7 +1870,7 @@ pub async fn get_pool_balances_for_member(
 
     span.end();
 
-    return Json(compute_balances_for_member(member_id, expenses));
+    Json(compute_balances_for_member(member_id, expenses))
 }
 
 #[derive(Deserialize, Serialize, ToSchema)]
@@ -1702,8 +2003,8 @@ pub async fn create_pool_membership_handler(
         .expect("Failed to get database connection");
 
     let new_membership = models::NewPoolMembership {
-        pool_id: pool_id,
-        member_id: member_id,
+        pool_id,
+        member_id,
         role: models::PoolRole::PARTICIPANT,
         default_split_percentage: 0.0,
     };
@@ -1757,6 +2058,116 @@ pub async fn update_member_handler(
     Json(membership)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/members/me/export",
+    responses(
+        (status = 200, description = "Everything stored about the member, as a JSON download", body = MemberDataExport),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn export_member_data_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("export_member_data_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let export = tokio::task::spawn_blocking(move || Member::export_data(&mut conn, member_id))
+        .await
+        .expect("Task panicked")
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to export member data"})),
+            )
+        })?;
+
+    span.end();
+
+    let file_name = format!("medici-data-{}.json", export.exported_at.format("%Y-%m-%d"));
+
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", file_name),
+        )],
+        Json(export),
+    ))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DeleteMemberInput {
+    /// The member's current password, to confirm the deletion.
+    pub password: String,
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/members/me",
+    request_body = DeleteMemberInput,
+    responses(
+        (status = 200, description = "Deleted the member's account and cleared their personal details", body = Member),
+        (status = 403, description = "Incorrect password"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_member_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Json(input): Json<DeleteMemberInput>,
+) -> Result<Json<Member>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("delete_member_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let result = tokio::task::spawn_blocking(move || {
+        let password_hash = hash_password(&input.password);
+
+        match MemberPassword::matches(&mut conn, member_id, &password_hash) {
+            Ok(true) => Member::anonymize(&mut conn, member_id)
+                .inspect(|_| ACTIVE_MEMBERS.forget(member_id))
+                .map_err(|_| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(serde_json::json!({"error": "Failed to delete account"})),
+                    )
+                }),
+            Ok(false) => Err((
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({"error": "Incorrect password"})),
+            )),
+            Err(_) => Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to delete account"})),
+            )),
+        }
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    result.map(Json)
+}
+
 #[utoipa::path(
     get,
     path = "/api/rules",
@@ -1799,13 +2210,15 @@ pub async fn list_expense_category_rules_handler(
     request_body = NewExpenseCategoryRule,
     responses(
         (status = 200, description = "Successfully created rule", body = ExpenseCategoryRule),
+        (status = 400, description = "Invalid rule"),
+        (status = 403, description = "Not a member of the rule's pool"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn create_expense_category_rule_handler(
     AuthenticatedUser(member_id): AuthenticatedUser,
     Json(rule): Json<NewExpenseCategoryRule>,
-) -> Json<ExpenseCategoryRule> {
+) -> Result<Json<ExpenseCategoryRule>, (StatusCode, Json<serde_json::Value>)> {
     let tracer = get_tracer();
 
     let mut span = tracer
@@ -1815,6 +2228,20 @@ pub async fn create_expense_category_rule_handler(
 
     span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
 
+    validate_rule(&rule).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+    })?;
+
+    if let Some(pool_id) = rule.pool_id {
+        ensure_pool_member(pool_id, member_id).await?;
+    }
+
+    let mut rule = rule;
+    resolve_rule_custom_category(&mut rule).await?;
+
     let mut conn = get_db_connection()
         .await
         .expect("Failed to get database connection");
@@ -1828,7 +2255,7 @@ pub async fn create_expense_category_rule_handler(
 
     span.end();
 
-    Json(rule)
+    Ok(Json(rule))
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -1878,39 +2305,4121 @@ pub async fn delete_expense_category_rule_handler(
     Json(serde_json::json!({"deleted": count}))
 }
 
-pub fn handlers_routes() -> OpenApiRouter {
-    let public_routes = OpenApiRouter::new()
-        .routes(routes!(signup_handler))
-        .routes(routes!(login_handler))
-        .routes(routes!(authenticate_handler));
+#[derive(Deserialize, ToSchema)]
+pub struct ExpenseCategoryRulePath {
+    rule_id: uuid::Uuid,
+}
 
-    let protected_routes = OpenApiRouter::new()
-        .routes(routes!(get_member_handler))
-        .routes(routes!(create_pool_handler))
-        .routes(routes!(create_pool_membership_handler))
-        .routes(routes!(add_friend_to_pool_handler))
-        .routes(routes!(remove_friend_from_pool_handler))
-        .routes(routes!(list_friends_handler))
-        .routes(routes!(list_inbound_friend_requests_handler))
-        .routes(routes!(create_friend_request_handler))
-        .routes(routes!(accept_friend_request_handler))
-        .routes(routes!(get_expense_handler))
-        .routes(routes!(add_expense_handler))
-        .routes(routes!(get_pool_details_handler))
-        .routes(routes!(get_pool_recent_expenses_handler))
-        .routes(routes!(list_members_of_pool_handler))
-        .routes(routes!(list_pools_for_member_handler))
-        .routes(routes!(delete_friend_request))
-        .routes(routes!(get_pool_balances_for_member))
-        .routes(routes!(settle_up_pool_handler))
-        .routes(routes!(modify_default_splits_handler))
-        .routes(routes!(delete_expense_handler))
-        .routes(routes!(update_member_handler))
-        .routes(routes!(update_expense_handler))
-        .routes(routes!(list_expense_category_rules_handler))
-        .routes(routes!(create_expense_category_rule_handler))
-        .routes(routes!(delete_expense_category_rule_handler))
+#[utoipa::path(
+    delete,
+    path = "/api/rules/{rule_id}",
+    params(
+        ("rule_id" = uuid::Uuid, Path, description = "ID of the rule to delete")
+    ),
+    responses(
+        (status = 200, description = "The deleted rule", body = ExpenseCategoryRule),
+        (status = 404, description = "Rule not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_expense_category_rule_by_id_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<ExpenseCategoryRulePath>,
+) -> Result<Json<ExpenseCategoryRule>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("delete_expense_category_rule_by_id_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("rule_id", path.rule_id.to_string()));
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let rule = tokio::task::spawn_blocking(move || {
+        ExpenseCategoryRule::delete_by_id(&mut conn, member_id, path.rule_id)
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    rule.map(Json).map_err(|_| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Rule not found"})),
+        )
+    })
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RulePreviewMatch {
+    expense: Expense,
+    /// Whether applying the rule would change the expense's category.
+    would_change: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RulePreview {
+    matches: Vec<RulePreviewMatch>,
+    would_change_count: usize,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/rules/preview",
+    request_body = NewExpenseCategoryRule,
+    responses(
+        (status = 200, description = "The caller's expenses the candidate rule would match", body = RulePreview),
+        (status = 400, description = "Invalid rule"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn preview_expense_category_rule_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Json(rule): Json<NewExpenseCategoryRule>,
+) -> Result<Json<RulePreview>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("preview_expense_category_rule_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+
+    validate_rule(&rule).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+    })?;
+
+    let mut rule = rule;
+    resolve_rule_custom_category(&mut rule).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let expenses = tokio::task::spawn_blocking(move || {
+        Expense::find_paid_by_member(&mut conn, member_id, rule.pool_id)
+            .expect("Failed to list expenses")
+            .into_iter()
+            .filter(|expense| rule_matches(&rule, &expense.into()))
+            .map(|expense| RulePreviewMatch {
+                would_change: changes_category(&rule.category, rule.custom_category_id, &expense),
+                expense,
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .expect("Task panicked");
+
+    let would_change_count = expenses.iter().filter(|m| m.would_change).count();
+
+    span.set_attribute(KeyValue::new("matched", expenses.len() as i64));
+    span.end();
+
+    Ok(Json(RulePreview {
+        matches: expenses,
+        would_change_count,
+    }))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ApplyRulesInput {
+    /// Only re-categorize expenses in this pool.
+    pool_id: Option<uuid::Uuid>,
+    /// Only apply these rules. Defaults to all of the caller's rules.
+    rule_ids: Option<Vec<uuid::Uuid>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CategoryChangeCount {
+    category: ExpenseCategory,
+    count: usize,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ApplyRulesSummary {
+    changed_expenses: usize,
+    /// Number of expenses moved into each category.
+    by_category: Vec<CategoryChangeCount>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/rules/apply",
+    request_body = ApplyRulesInput,
+    responses(
+        (status = 200, description = "Re-categorized the caller's past expenses", body = ApplyRulesSummary),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn apply_expense_category_rules_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Json(input): Json<ApplyRulesInput>,
+) -> Result<Json<ApplyRulesSummary>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("apply_expense_category_rules_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let changed = tokio::task::spawn_blocking(move || {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let rules: Vec<ExpenseCategoryRule> =
+                ExpenseCategoryRule::find_for_member(conn, member_id)?
+                    .into_iter()
+                    .filter(|rule| {
+                        input
+                            .rule_ids
+                            .as_ref()
+                            .is_none_or(|rule_ids| rule_ids.contains(&rule.id))
+                    })
+                    .collect();
+
+            let expenses = Expense::find_paid_by_member(conn, member_id, input.pool_id)?;
+            let plan = plan_recategorization(&rules, &expenses);
+
+            let expense_ids: Vec<uuid::Uuid> = plan.iter().map(|(e, _)| e.id).collect();
+            let before = Expense::snapshots(conn, &expense_ids)?;
+
+            for (expense, rule) in &plan {
+                let changeset = models::ExpenseChangeset {
+                    name: None,
+                    amount: None,
+                    is_settled: None,
+                    description: None,
+                    notes: None,
+                    category: Some(rule.category.clone()),
+                    split_method: None,
+                    incurred_on: None,
+                    custom_category_id: Some(rule.custom_category_id),
+                    categorized_by_rule: Some(true),
+                };
+
+                Expense::update(conn, &expense.id, expense.pool_id, &changeset)?;
+            }
+
+            let after = Expense::snapshots(conn, &expense_ids)?;
+
+            for (before, after) in before.iter().zip(after.iter()) {
+                ExpenseEvent::record(
+                    conn,
+                    &NewExpenseEvent::new(
+                        ExpenseEventAction::Updated,
+                        member_id,
+                        Some(before),
+                        Some(after),
+                    ),
+                )?;
+            }
+
+            Ok(plan
+                .into_iter()
+                .map(|(_, rule)| rule.category.clone())
+                .collect::<Vec<_>>())
+        })
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Failed to apply rules"})),
+        )
+    })?;
+
+    span.set_attribute(KeyValue::new("changed_expenses", changed.len() as i64));
+    span.end();
+
+    Ok(Json(ApplyRulesSummary {
+        changed_expenses: changed.len(),
+        by_category: count_by_category(&changed)
+            .into_iter()
+            .map(|(category, count)| CategoryChangeCount { category, count })
+            .collect(),
+    }))
+}
+
+/// How many past expenses rules are proposed from.
+const CATEGORY_HISTORY_LIMIT: i64 = 5000;
+/// How many distinct expense names category suggestions learn from.
+const CATEGORY_SUGGESTION_NAME_LIMIT: i64 = 1000;
+
+#[derive(Deserialize, ToSchema)]
+pub struct CategorySuggestionsQuery {
+    name: String,
+    pool_id: Option<uuid::Uuid>,
+    limit: Option<usize>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/categories/suggestions",
+    params(
+        ("name" = String, Query, description = "Name of the expense to suggest a category for"),
+        ("pool_id" = Option<uuid::Uuid>, Query, description = "Also learn from this pool's expenses"),
+        ("limit" = Option<usize>, Query, description = "Maximum number of suggestions to return (default 3)"),
+    ),
+    responses(
+        (status = 200, description = "Suggested categories, best first", body = Vec<CategorySuggestion>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn suggest_expense_categories_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Query(query): Query<CategorySuggestionsQuery>,
+) -> Result<Json<Vec<CategorySuggestion>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("suggest_expense_categories_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+
+    if let Some(pool_id) = query.pool_id {
+        span.set_attribute(KeyValue::new("pool_id", pool_id.to_string()));
+        ensure_pool_member(pool_id, member_id).await?;
+    }
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let suggestions = tokio::task::spawn_blocking(move || {
+        let history = Expense::count_categories_by_name(
+            &mut conn,
+            member_id,
+            query.pool_id,
+            CATEGORY_SUGGESTION_NAME_LIMIT,
+        )
+        .expect("Failed to load expense history");
+
+        let suggester = CategorySuggester::from_history(&history);
+
+        suggester.suggest(&query.name, query.limit.unwrap_or(3))
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(suggestions))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/rules/suggestions",
+    responses(
+        (status = 200, description = "Rules proposed from expenses the caller categorized consistently", body = Vec<RuleProposal>),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn suggest_expense_category_rules_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+) -> Json<Vec<RuleProposal>> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("suggest_expense_category_rules_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let proposals = tokio::task::spawn_blocking(move || {
+        let history = Expense::find_category_history(&mut conn, member_id, CATEGORY_HISTORY_LIMIT)
+            .expect("Failed to load expense history");
+        let rules = ExpenseCategoryRule::find_for_member(&mut conn, member_id)
+            .expect("Failed to list expense category rules");
+
+        propose_rules(&history, &rules, 3)
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Json(proposals)
+}
+
+async fn ensure_pool_member(
+    pool_id: uuid::Uuid,
+    member_id: uuid::Uuid,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let is_member = tokio::task::spawn_blocking(move || {
+        PoolMembership::is_member(&mut conn, pool_id, member_id)
+    })
+    .await
+    .expect("Task panicked")
+    .unwrap_or(false);
+
+    if is_member {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "Not a member of this pool"})),
+        ))
+    }
+}
+
+async fn ensure_expense_in_pool(
+    expense_id: uuid::Uuid,
+    pool_id: uuid::Uuid,
+) -> Result<Expense, (StatusCode, Json<serde_json::Value>)> {
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    tokio::task::spawn_blocking(move || Expense::find_in_pool(&mut conn, expense_id, pool_id))
+        .await
+        .expect("Task panicked")
+        .map_err(|_| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Expense not found"})),
+            )
+        })
+}
+
+async fn find_pool_category(
+    category_id: uuid::Uuid,
+    pool_id: uuid::Uuid,
+) -> Result<PoolCategory, (StatusCode, Json<serde_json::Value>)> {
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    tokio::task::spawn_blocking(move || PoolCategory::find(&mut conn, category_id, pool_id))
+        .await
+        .expect("Task panicked")
+        .map_err(|_| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Category not found"})),
+            )
+        })
+}
+
+/// Checks that a rule's custom category belongs to the rule's pool and sets
+/// the rule's built-in category to the custom category's parent.
+async fn resolve_rule_custom_category(
+    rule: &mut NewExpenseCategoryRule,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let Some(category_id) = rule.custom_category_id else {
+        return Ok(());
+    };
+
+    let pool_id = rule.pool_id.ok_or((
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({
+            "error": "Rules with a custom category must be scoped to its pool"
+        })),
+    ))?;
+
+    let custom_category = find_pool_category(category_id, pool_id)
+        .await
+        .map_err(|(_, body)| (StatusCode::BAD_REQUEST, body))?;
+
+    rule.category = custom_category.builtin_category();
+
+    Ok(())
+}
+
+pub static BLOB_STORE: Lazy<Box<dyn BlobStore>> = Lazy::new(blob_store_from_env);
+
+pub static MAILER: Lazy<Box<dyn Mailer>> = Lazy::new(mailer_from_env);
+
+pub static WEBHOOK_ALLOWED_HOSTS: Lazy<AllowedHosts> = Lazy::new(AllowedHosts::from_env);
+
+const ALLOWED_ATTACHMENT_CONTENT_TYPES: [&str; 6] = [
+    "image/jpeg",
+    "image/png",
+    "image/webp",
+    "image/heic",
+    "image/heif",
+    "application/pdf",
+];
+
+fn attachment_max_bytes() -> usize {
+    std::env::var("ATTACHMENT_MAX_BYTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(10 * 1024 * 1024)
+}
+
+fn attachment_storage_key(
+    pool_id: uuid::Uuid,
+    expense_id: uuid::Uuid,
+    attachment_id: uuid::Uuid,
+) -> String {
+    format!(
+        "pools/{}/expenses/{}/{}",
+        pool_id, expense_id, attachment_id
+    )
+}
+
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct AttachmentUpload {
+    #[schema(value_type = String, format = Binary)]
+    file: Vec<u8>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pools/{pool_id}/expenses/{expense_id}/attachments",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the expense belongs to"),
+        ("expense_id" = uuid::Uuid, Path, description = "ID of the expense to attach the file to")
+    ),
+    request_body(content = AttachmentUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Uploaded attachment", body = ExpenseAttachment),
+        (status = 400, description = "Missing or empty file"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Expense not found"),
+        (status = 413, description = "File too large"),
+        (status = 415, description = "Unsupported content type, or contents that aren't a supported image or PDF"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn upload_expense_attachment_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<ExpensePath>,
+    mut multipart: Multipart,
+) -> Result<Json<ExpenseAttachment>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("upload_expense_attachment_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("expense_id", path.expense_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+    ensure_expense_in_pool(path.expense_id, path.pool_id).await?;
+
+    let multipart_error = |e: MultipartError| {
+        (
+            e.status(),
+            Json(serde_json::json!({"error": e.body_text()})),
+        )
+    };
+
+    let mut field = loop {
+        match multipart.next_field().await.map_err(multipart_error)? {
+            Some(field) if field.name() == Some("file") => break field,
+            Some(_) => continue,
+            None => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({"error": "Missing `file` field"})),
+                ));
+            }
+        }
+    };
+
+    let file_name = field.file_name().unwrap_or("attachment").to_string();
+    let declared_content_type = field.content_type().unwrap_or_default().to_string();
+
+    if !ALLOWED_ATTACHMENT_CONTENT_TYPES.contains(&declared_content_type.as_str()) {
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json(serde_json::json!({
+                "error": format!("Unsupported content type `{}`", declared_content_type),
+                "allowed": ALLOWED_ATTACHMENT_CONTENT_TYPES,
+            })),
+        ));
+    }
+
+    let max_bytes = attachment_max_bytes();
+    let mut bytes: Vec<u8> = Vec::new();
+
+    while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+        if bytes.len() + chunk.len() > max_bytes {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Json(serde_json::json!({
+                    "error": format!("Attachments may be at most {} bytes", max_bytes)
+                })),
+            ));
+        }
+
+        bytes.extend_from_slice(&chunk);
+    }
+
+    if bytes.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Attachment is empty"})),
+        ));
+    }
+
+    // The declared type is only the uploader's word, so what's stored and
+    // later served back is decided by the file's contents.
+    let content_type = match sniff_content_type(&bytes) {
+        Some(content_type) if ALLOWED_ATTACHMENT_CONTENT_TYPES.contains(&content_type) => {
+            content_type.to_string()
+        }
+        _ => {
+            return Err((
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                Json(serde_json::json!({
+                    "error": "Attachment contents aren't a supported image or PDF",
+                    "allowed": ALLOWED_ATTACHMENT_CONTENT_TYPES,
+                })),
+            ));
+        }
+    };
+
+    let attachment_id = uuid::Uuid::new_v4();
+    let storage_key = attachment_storage_key(path.pool_id, path.expense_id, attachment_id);
+
+    let new_attachment = NewExpenseAttachment {
+        id: attachment_id,
+        expense_id: path.expense_id,
+        pool_id: path.pool_id,
+        uploaded_by_member_id: member_id,
+        file_name,
+        content_type: content_type.clone(),
+        size_bytes: bytes.len() as i64,
+        storage_key: storage_key.clone(),
+    };
+
+    BLOB_STORE
+        .put(&storage_key, &content_type, bytes)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to store attachment {}: {}", storage_key, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to store attachment"})),
+            )
+        })?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let attachment =
+        tokio::task::spawn_blocking(move || ExpenseAttachment::create(&mut conn, &new_attachment))
+            .await
+            .expect("Task panicked");
+
+    span.end();
+
+    match attachment {
+        Ok(a) => Ok(Json(a)),
+        Err(_) => {
+            let _ = BLOB_STORE.delete(&storage_key).await;
+
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to save attachment"})),
+            ))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/expenses/{expense_id}/attachments",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the expense belongs to"),
+        ("expense_id" = uuid::Uuid, Path, description = "ID of the expense to list attachments for")
+    ),
+    responses(
+        (status = 200, description = "List attachments of an expense", body = Vec<ExpenseAttachment>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_expense_attachments_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<ExpensePath>,
+) -> Result<Json<Vec<ExpenseAttachment>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("list_expense_attachments_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("expense_id", path.expense_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let attachments = tokio::task::spawn_blocking(move || {
+        ExpenseAttachment::find_for_expense(&mut conn, path.expense_id, path.pool_id)
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    match attachments {
+        Ok(attachments) => Ok(Json(attachments)),
+        Err(e) => {
+            tracing::error!("Failed to list attachments: {}", e);
+
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to list attachments"})),
+            ))
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ExpenseAttachmentPath {
+    pool_id: uuid::Uuid,
+    expense_id: uuid::Uuid,
+    attachment_id: uuid::Uuid,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/expenses/{expense_id}/attachments/{attachment_id}",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the expense belongs to"),
+        ("expense_id" = uuid::Uuid, Path, description = "ID of the expense the attachment belongs to"),
+        ("attachment_id" = uuid::Uuid, Path, description = "ID of the attachment to download")
+    ),
+    responses(
+        (status = 200, description = "The attachment contents", content_type = "application/octet-stream"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Attachment not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn download_expense_attachment_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<ExpenseAttachmentPath>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("download_expense_attachment_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new(
+        "attachment_id",
+        path.attachment_id.to_string(),
+    ));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Attachment not found"})),
+        )
+    };
+
+    let attachment = tokio::task::spawn_blocking(move || {
+        ExpenseAttachment::find(&mut conn, path.attachment_id, path.expense_id, path.pool_id)
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(|_| not_found())?;
+
+    let bytes = BLOB_STORE
+        .get(&attachment.storage_key)
+        .await
+        .map_err(|e| match e {
+            StorageError::NotFound(_) => not_found(),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to read attachment"})),
+            ),
+        })?;
+
+    span.end();
+
+    let file_name: String = attachment
+        .file_name
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"' && *c != '\\')
+        .collect();
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}\"", file_name),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/pools/{pool_id}/expenses/{expense_id}/attachments/{attachment_id}",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the expense belongs to"),
+        ("expense_id" = uuid::Uuid, Path, description = "ID of the expense the attachment belongs to"),
+        ("attachment_id" = uuid::Uuid, Path, description = "ID of the attachment to delete")
+    ),
+    responses(
+        (status = 200, description = "The deleted attachment", body = ExpenseAttachment),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Attachment not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_expense_attachment_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<ExpenseAttachmentPath>,
+) -> Result<Json<ExpenseAttachment>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("delete_expense_attachment_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new(
+        "attachment_id",
+        path.attachment_id.to_string(),
+    ));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let attachment = tokio::task::spawn_blocking(move || {
+        ExpenseAttachment::find(&mut conn, path.attachment_id, path.expense_id, path.pool_id)
+            .and_then(|attachment| ExpenseAttachment::delete(&mut conn, attachment.id))
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(|_| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Attachment not found"})),
+        )
+    })?;
+
+    if let Err(e) = BLOB_STORE.delete(&attachment.storage_key).await {
+        tracing::error!(
+            "Failed to delete attachment blob {}: {}",
+            attachment.storage_key,
+            e
+        );
+    }
+
+    span.end();
+
+    Ok(Json(attachment))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ExpenseCommentInput {
+    body: String,
+}
+
+fn comment_error(error: CommentError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = match error {
+        CommentError::EmptyBody | CommentError::BodyTooLong => StatusCode::BAD_REQUEST,
+        CommentError::NotFound => StatusCode::NOT_FOUND,
+        CommentError::NotAuthor => StatusCode::FORBIDDEN,
+    };
+
+    (
+        status,
+        Json(serde_json::json!({"error": error.to_string()})),
+    )
+}
+
+fn map_comment_write_error(error: diesel::result::Error) -> (StatusCode, Json<serde_json::Value>) {
+    match error {
+        diesel::result::Error::NotFound => comment_error(CommentError::NotFound),
+        error => {
+            tracing::error!("Failed to write comment: {}", error);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to write comment"})),
+            )
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/expenses/{expense_id}/comments",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the expense belongs to"),
+        ("expense_id" = uuid::Uuid, Path, description = "ID of the expense to list comments for")
+    ),
+    responses(
+        (status = 200, description = "List comments on an expense, oldest first", body = Vec<ExpenseComment>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_expense_comments_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<ExpensePath>,
+) -> Result<Json<Vec<ExpenseComment>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("list_expense_comments_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("expense_id", path.expense_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let comments = tokio::task::spawn_blocking(move || {
+        ExpenseComment::find_for_expense(&mut conn, path.expense_id, path.pool_id)
+            .expect("Failed to list comments")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(comments))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pools/{pool_id}/expenses/{expense_id}/comments",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the expense belongs to"),
+        ("expense_id" = uuid::Uuid, Path, description = "ID of the expense to comment on")
+    ),
+    request_body = ExpenseCommentInput,
+    responses(
+        (status = 200, description = "The created comment", body = ExpenseComment),
+        (status = 400, description = "Invalid comment body"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Expense not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_expense_comment_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<ExpensePath>,
+    Json(input): Json<ExpenseCommentInput>,
+) -> Result<Json<ExpenseComment>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("create_expense_comment_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("expense_id", path.expense_id.to_string()));
+
+    let body = validate_comment_body(&input.body).map_err(comment_error)?;
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+    ensure_expense_in_pool(path.expense_id, path.pool_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let new_comment = models::NewExpenseComment {
+        expense_id: path.expense_id,
+        pool_id: path.pool_id,
+        author_member_id: member_id,
+        body,
+    };
+
+    let comment = tokio::task::spawn_blocking(move || {
+        ExpenseComment::create(&mut conn, &new_comment).expect("Failed to create comment")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(comment))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ExpenseCommentPath {
+    pool_id: uuid::Uuid,
+    expense_id: uuid::Uuid,
+    comment_id: uuid::Uuid,
+}
+
+/// Looks up a live comment and checks that `member_id` wrote it.
+async fn find_own_comment(
+    path: &ExpenseCommentPath,
+    member_id: uuid::Uuid,
+) -> Result<ExpenseComment, (StatusCode, Json<serde_json::Value>)> {
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let (comment_id, expense_id, pool_id) = (path.comment_id, path.expense_id, path.pool_id);
+
+    let comment = tokio::task::spawn_blocking(move || {
+        ExpenseComment::find(&mut conn, comment_id, expense_id, pool_id)
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(|_| comment_error(CommentError::NotFound))?;
+
+    ensure_author(&comment, member_id).map_err(comment_error)?;
+
+    Ok(comment)
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/pools/{pool_id}/expenses/{expense_id}/comments/{comment_id}",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the expense belongs to"),
+        ("expense_id" = uuid::Uuid, Path, description = "ID of the expense the comment belongs to"),
+        ("comment_id" = uuid::Uuid, Path, description = "ID of the comment to edit")
+    ),
+    request_body = ExpenseCommentInput,
+    responses(
+        (status = 200, description = "The edited comment", body = ExpenseComment),
+        (status = 400, description = "Invalid comment body"),
+        (status = 403, description = "Not a member of the pool or not the author"),
+        (status = 404, description = "Comment not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn edit_expense_comment_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<ExpenseCommentPath>,
+    Json(input): Json<ExpenseCommentInput>,
+) -> Result<Json<ExpenseComment>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("edit_expense_comment_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("comment_id", path.comment_id.to_string()));
+
+    let body = validate_comment_body(&input.body).map_err(comment_error)?;
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+    let comment = find_own_comment(&path, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    // The comment can be deleted between the ownership check and the edit.
+    let comment =
+        tokio::task::spawn_blocking(move || ExpenseComment::edit(&mut conn, comment.id, &body))
+            .await
+            .expect("Task panicked")
+            .map_err(map_comment_write_error)?;
+
+    span.end();
+
+    Ok(Json(comment))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/pools/{pool_id}/expenses/{expense_id}/comments/{comment_id}",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the expense belongs to"),
+        ("expense_id" = uuid::Uuid, Path, description = "ID of the expense the comment belongs to"),
+        ("comment_id" = uuid::Uuid, Path, description = "ID of the comment to delete")
+    ),
+    responses(
+        (status = 200, description = "The deleted comment", body = ExpenseComment),
+        (status = 403, description = "Not a member of the pool or not the author"),
+        (status = 404, description = "Comment not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_expense_comment_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<ExpenseCommentPath>,
+) -> Result<Json<ExpenseComment>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("delete_expense_comment_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("comment_id", path.comment_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+    let comment = find_own_comment(&path, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let comment =
+        tokio::task::spawn_blocking(move || ExpenseComment::soft_delete(&mut conn, comment.id))
+            .await
+            .expect("Task panicked")
+            .map_err(map_comment_write_error)?;
+
+    span.end();
+
+    Ok(Json(comment))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PoolActivityQuery {
+    before: Option<DateTime<Utc>>,
+    limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/activity",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to fetch activity for"),
+        ("before" = Option<chrono::DateTime<Utc>>, Query, description = "Only return events recorded before this time"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of events to return (default 50, max 200)"),
+    ),
+    responses(
+        (status = 200, description = "Expense events in the pool, newest first", body = Vec<ExpenseEvent>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_pool_activity_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Query(query): Query<PoolActivityQuery>,
+) -> Result<Json<Vec<ExpenseEvent>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("get_pool_activity_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let events = tokio::task::spawn_blocking(move || {
+        ExpenseEvent::list_for_pool(&mut conn, path.pool_id, query.before, limit)
+            .expect("Failed to list pool activity")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(events))
+}
+
+/// Changes from every replica, fed by the pool event listener and fanned out
+/// to the streams open on this one.
+pub static POOL_EVENTS: Lazy<broadcast::Sender<PoolFeedMessage>> =
+    Lazy::new(|| broadcast::channel(1024).0);
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/events",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to stream changes for")
+    ),
+    responses(
+        (status = 200, description = "Server-sent events. `expense` events carry a PoolNotification for each change to the pool's expenses. `balances` events carry the member's balances in the pool, on connect and after changes. `resync` events mean changes may have been missed, so refetch.", content_type = "text/event-stream", body = String),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn pool_events_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+) -> Result<Sse<ReceiverStream<Result<Event, Infallible>>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("pool_events_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    // Subscribe before the first balances are read so nothing falls between.
+    let subscription = POOL_EVENTS.subscribe();
+    let (sender, receiver) = tokio::sync::mpsc::channel(16);
+
+    tokio::spawn(stream_pool_events(
+        path.pool_id,
+        member_id,
+        subscription,
+        sender,
+    ));
+
+    span.end();
+
+    Ok(Sse::new(ReceiverStream::new(receiver)).keep_alive(KeepAlive::default()))
+}
+
+/// Feeds one client's stream until it disconnects or leaves the pool. Each
+/// burst of changes is followed by a single update of the member's balances.
+async fn stream_pool_events(
+    pool_id: uuid::Uuid,
+    member_id: uuid::Uuid,
+    mut subscription: broadcast::Receiver<PoolFeedMessage>,
+    sender: tokio::sync::mpsc::Sender<Result<Event, Infallible>>,
+) {
+    let mut needs_balances = true;
+
+    loop {
+        if needs_balances {
+            let event = match pool_balances_event(pool_id, member_id).await {
+                Ok(Some(event)) => event,
+                Ok(None) => return,
+                Err(e) => {
+                    tracing::error!("Failed to compute balances for pool stream: {}", e);
+                    return;
+                }
+            };
+
+            if sender.send(Ok(event)).await.is_err() {
+                return;
+            }
+
+            needs_balances = false;
+        }
+
+        let mut message = Some(tokio::select! {
+            _ = sender.closed() => return,
+            message = subscription.recv() => message,
+        });
+
+        let mut membership_checked = false;
+
+        while let Some(received) = message.take() {
+            message = next_pool_feed_message(&mut subscription);
+
+            let event = match received {
+                Ok(PoolFeedMessage::Notification(notification))
+                    if notification.pool_id == pool_id =>
+                {
+                    Event::default()
+                        .event("expense")
+                        .json_data(notification)
+                        .expect("Notifications serialize")
+                }
+                Ok(PoolFeedMessage::Notification(_)) => continue,
+                Ok(PoolFeedMessage::Reconnected) | Err(RecvError::Lagged(_)) => {
+                    Event::default().event("resync").data("{}")
+                }
+                Err(RecvError::Closed) => return,
+            };
+
+            // Someone removed from the pool mustn't see what happens in it
+            // after they've gone, so check before passing anything on.
+            if !membership_checked {
+                match is_pool_member(pool_id, member_id).await {
+                    Ok(true) => membership_checked = true,
+                    Ok(false) => return,
+                    Err(e) => {
+                        tracing::error!("Failed to check membership for pool stream: {}", e);
+                        return;
+                    }
+                }
+            }
+
+            if sender.send(Ok(event)).await.is_err() {
+                return;
+            }
+
+            needs_balances = true;
+        }
+    }
+}
+
+/// The next message if one is already waiting, with errors mapped onto
+/// `recv`'s so they're handled the same way.
+fn next_pool_feed_message(
+    subscription: &mut broadcast::Receiver<PoolFeedMessage>,
+) -> Option<Result<PoolFeedMessage, RecvError>> {
+    match subscription.try_recv() {
+        Ok(message) => Some(Ok(message)),
+        Err(TryRecvError::Empty) => None,
+        Err(TryRecvError::Lagged(skipped)) => Some(Err(RecvError::Lagged(skipped))),
+        Err(TryRecvError::Closed) => Some(Err(RecvError::Closed)),
+    }
+}
+
+async fn is_pool_member(pool_id: uuid::Uuid, member_id: uuid::Uuid) -> Result<bool, anyhow::Error> {
+    let mut conn = get_db_connection().await?;
+
+    let is_member = tokio::task::spawn_blocking(move || {
+        PoolMembership::is_member(&mut conn, pool_id, member_id)
+    })
+    .await??;
+
+    Ok(is_member)
+}
+
+/// The member's balances in the pool, or `None` once they've left it.
+async fn pool_balances_event(
+    pool_id: uuid::Uuid,
+    member_id: uuid::Uuid,
+) -> Result<Option<Event>, anyhow::Error> {
+    let mut conn = get_db_connection().await?;
+
+    let expenses = tokio::task::spawn_blocking(move || {
+        if !PoolMembership::is_member(&mut conn, pool_id, member_id)? {
+            return Ok(None);
+        }
+
+        Expense::list_unpaid_for_balance_computation(&mut conn, pool_id).map(Some)
+    })
+    .await??;
+
+    Ok(expenses.map(|expenses| {
+        Event::default()
+            .event("balances")
+            .json_data(compute_balances_for_member(member_id, expenses))
+            .expect("Balances serialize")
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/trash",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to list deleted expenses for")
+    ),
+    responses(
+        (status = 200, description = "Deleted expenses awaiting purge, most recently deleted first", body = Vec<Expense>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_pool_trash_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+) -> Result<Json<Vec<Expense>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("list_pool_trash_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let expenses = tokio::task::spawn_blocking(move || {
+        Expense::list_deleted_for_pool(&mut conn, path.pool_id)
+            .expect("Failed to list deleted expenses")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(expenses))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pools/{pool_id}/expenses/{expense_id}/restore",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the expense belongs to"),
+        ("expense_id" = uuid::Uuid, Path, description = "ID of the deleted expense to restore")
+    ),
+    responses(
+        (status = 200, description = "The restored expense", body = Expense),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Expense not found in the pool's trash"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn restore_expense_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<ExpensePath>,
+) -> Result<Json<Expense>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("restore_expense_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("expense_id", path.expense_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let expense = tokio::task::spawn_blocking(move || {
+        Expense::restore(&mut conn, path.expense_id, path.pool_id, member_id)
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    expense.map(Json).map_err(|_| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Expense not found in trash"})),
+        )
+    })
+}
+
+fn category_error(error: CategoryError) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({"error": error.to_string()})),
+    )
+}
+
+fn map_category_write_error(error: diesel::result::Error) -> (StatusCode, Json<serde_json::Value>) {
+    match error {
+        diesel::result::Error::DatabaseError(
+            diesel::result::DatabaseErrorKind::UniqueViolation,
+            _,
+        ) => (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "A category with this name already exists"})),
+        ),
+        diesel::result::Error::NotFound => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Category not found"})),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Failed to save category"})),
+        ),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/categories",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to list custom categories for")
+    ),
+    responses(
+        (status = 200, description = "The pool's custom categories, by name", body = Vec<PoolCategory>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_pool_categories_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+) -> Result<Json<Vec<PoolCategory>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("list_pool_categories_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let categories = tokio::task::spawn_blocking(move || {
+        PoolCategory::list_for_pool(&mut conn, path.pool_id).expect("Failed to list categories")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(categories))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PoolCategoryInput {
+    name: String,
+    /// A hex color like `#1f8a70`.
+    color: String,
+    /// An emoji or icon name.
+    icon: Option<String>,
+    /// The built-in category this one rolls up into. Defaults to
+    /// `Miscellaneous` wherever a built-in category is needed.
+    parent_category: Option<ExpenseCategory>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pools/{pool_id}/categories",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to add the category to")
+    ),
+    request_body = PoolCategoryInput,
+    responses(
+        (status = 200, description = "The created category", body = PoolCategory),
+        (status = 400, description = "Invalid category"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 409, description = "A category with this name already exists"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_pool_category_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Json(input): Json<PoolCategoryInput>,
+) -> Result<Json<PoolCategory>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("create_pool_category_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    let new_category = NewPoolCategory {
+        pool_id: path.pool_id,
+        name: validate_category_name(&input.name).map_err(category_error)?,
+        color: validate_category_color(&input.color).map_err(category_error)?,
+        icon: match input.icon {
+            Some(icon) => validate_category_icon(&icon).map_err(category_error)?,
+            None => None,
+        },
+        parent_category: input.parent_category,
+    };
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let category =
+        tokio::task::spawn_blocking(move || PoolCategory::create(&mut conn, &new_category))
+            .await
+            .expect("Task panicked")
+            .map_err(map_category_write_error)?;
+
+    span.end();
+
+    Ok(Json(category))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PoolCategoryPath {
+    pool_id: uuid::Uuid,
+    category_id: uuid::Uuid,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdatePoolCategoryInput {
+    name: Option<String>,
+    color: Option<String>,
+    /// An empty string removes the icon.
+    icon: Option<String>,
+    /// Changing the parent also moves the category's expenses and rules to
+    /// the new built-in category.
+    parent_category: Option<ExpenseCategory>,
+    /// Detaches the category from its built-in parent.
+    #[serde(default)]
+    clear_parent_category: bool,
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/pools/{pool_id}/categories/{category_id}",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the category belongs to"),
+        ("category_id" = uuid::Uuid, Path, description = "ID of the category to update")
+    ),
+    request_body = UpdatePoolCategoryInput,
+    responses(
+        (status = 200, description = "The updated category", body = PoolCategory),
+        (status = 400, description = "Invalid category"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Category not found"),
+        (status = 409, description = "A category with this name already exists"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_pool_category_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolCategoryPath>,
+    Json(input): Json<UpdatePoolCategoryInput>,
+) -> Result<Json<PoolCategory>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("update_pool_category_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("category_id", path.category_id.to_string()));
+
+    let changeset = PoolCategoryChangeset {
+        name: input
+            .name
+            .map(|name| validate_category_name(&name))
+            .transpose()
+            .map_err(category_error)?,
+        color: input
+            .color
+            .map(|color| validate_category_color(&color))
+            .transpose()
+            .map_err(category_error)?,
+        icon: input
+            .icon
+            .map(|icon| validate_category_icon(&icon))
+            .transpose()
+            .map_err(category_error)?,
+        parent_category: if input.clear_parent_category {
+            Some(None)
+        } else {
+            input.parent_category.map(Some)
+        },
+    };
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+    find_pool_category(path.category_id, path.pool_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let category = tokio::task::spawn_blocking(move || {
+        PoolCategory::update(&mut conn, path.category_id, &changeset)
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(map_category_write_error)?;
+
+    span.end();
+
+    Ok(Json(category))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/pools/{pool_id}/categories/{category_id}",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the category belongs to"),
+        ("category_id" = uuid::Uuid, Path, description = "ID of the category to delete")
+    ),
+    responses(
+        (status = 200, description = "The deleted category. Its expenses keep their built-in category and rules using it are removed", body = PoolCategory),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Category not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_pool_category_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolCategoryPath>,
+) -> Result<Json<PoolCategory>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("delete_pool_category_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("category_id", path.category_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+    find_pool_category(path.category_id, path.pool_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let category =
+        tokio::task::spawn_blocking(move || PoolCategory::delete(&mut conn, path.category_id))
+            .await
+            .expect("Task panicked")
+            .map_err(map_category_write_error)?;
+
+    span.end();
+
+    Ok(Json(category))
+}
+
+fn tag_error(error: TagError) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({"error": error.to_string()})),
+    )
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PoolTagsQuery {
+    prefix: Option<String>,
+    limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/tags",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to list tags for"),
+        ("prefix" = Option<String>, Query, description = "Only return tags starting with this text, for autocomplete"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of tags to return (default 20, max 100)")
+    ),
+    responses(
+        (status = 200, description = "The pool's tags, most used first", body = Vec<TagUsage>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_pool_tags_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Query(query): Query<PoolTagsQuery>,
+) -> Result<Json<Vec<TagUsage>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("list_pool_tags_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("limit", limit));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let tags = tokio::task::spawn_blocking(move || {
+        let prefix = query.prefix.as_deref().map(str::trim);
+        Tag::list_for_pool(&mut conn, path.pool_id, prefix, limit).expect("Failed to list tags")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(tags))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TagTotalsQuery {
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/tags/totals",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to total tags for"),
+        ("since" = Option<NaiveDate>, Query, description = "Only count expenses incurred on or after this date"),
+        ("until" = Option<NaiveDate>, Query, description = "Only count expenses incurred on or before this date")
+    ),
+    responses(
+        (status = 200, description = "Spending per tag, largest first, with the caller's share", body = Vec<TagTotal>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_pool_tag_totals_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Query(query): Query<TagTotalsQuery>,
+) -> Result<Json<Vec<TagTotal>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("get_pool_tag_totals_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let totals = tokio::task::spawn_blocking(move || {
+        Tag::totals_for_pool(&mut conn, path.pool_id, member_id, query.since, query.until)
+            .expect("Failed to total tags")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(totals))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ExpenseTagsInput {
+    tags: Vec<String>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/pools/{pool_id}/expenses/{expense_id}/tags",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the expense belongs to"),
+        ("expense_id" = uuid::Uuid, Path, description = "ID of the expense to tag")
+    ),
+    request_body = ExpenseTagsInput,
+    responses(
+        (status = 200, description = "The expense's tags, which replace any it had", body = Vec<Tag>),
+        (status = 400, description = "Invalid tags"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Expense not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn set_expense_tags_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<ExpensePath>,
+    Json(input): Json<ExpenseTagsInput>,
+) -> Result<Json<Vec<Tag>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("set_expense_tags_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("expense_id", path.expense_id.to_string()));
+
+    let tags = normalize_tags(&input.tags).map_err(tag_error)?;
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+    ensure_expense_in_pool(path.expense_id, path.pool_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let tags = tokio::task::spawn_blocking(move || {
+        Tag::set_for_expense(&mut conn, path.expense_id, path.pool_id, &tags)
+            .expect("Failed to set expense tags")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(tags))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SearchQuery {
+    q: String,
+    pool_id: Option<uuid::Uuid>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchResult {
+    #[serde(flatten)]
+    expense: Expense,
+    /// Relevance of the text match. Searches with only an amount rank every
+    /// result equally, newest first.
+    rank: f32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchResults {
+    results: Vec<SearchResult>,
+    /// The offset of the next page, if there is one.
+    next_offset: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/search",
+    params(
+        ("q" = String, Query, description = "Words to find in expense names, descriptions and notes, and amounts like $84 or 12.50"),
+        ("pool_id" = Option<uuid::Uuid>, Query, description = "Only search this pool"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of results to return (default 20, max 100)"),
+        ("offset" = Option<i64>, Query, description = "Number of results to skip")
+    ),
+    responses(
+        (status = 200, description = "Matching expenses across the caller's pools, best matches first", body = SearchResults),
+        (status = 400, description = "Empty search"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn search_expenses_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResults>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("search_expenses_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("limit", limit));
+    span.set_attribute(KeyValue::new("offset", offset));
+
+    let search = parse_search_query(&query.q);
+
+    if search.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Search cannot be empty"})),
+        ));
+    }
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let mut results = tokio::task::spawn_blocking(move || {
+        Expense::search_for_member(
+            &mut conn,
+            member_id,
+            query.pool_id,
+            &search,
+            limit + 1,
+            offset,
+        )
+        .expect("Failed to search expenses")
+    })
+    .await
+    .expect("Task panicked");
+
+    let next_offset = (results.len() as i64 > limit).then_some(offset + limit);
+    results.truncate(limit as usize);
+
+    span.set_attribute(KeyValue::new("results", results.len() as i64));
+    span.end();
+
+    Ok(Json(SearchResults {
+        results: results
+            .into_iter()
+            .map(|(expense, rank)| SearchResult { expense, rank })
+            .collect(),
+        next_offset,
+    }))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CategoryAnalyticsQuery {
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CategoryAnalytics {
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    /// Largest share first.
+    categories: Vec<CategoryTotal>,
+    total_paid: f64,
+    total_share: f64,
+}
+
+async fn category_analytics(
+    member_id: uuid::Uuid,
+    pool_id: Option<uuid::Uuid>,
+    query: CategoryAnalyticsQuery,
+) -> Result<CategoryAnalytics, (StatusCode, Json<serde_json::Value>)> {
+    if let (Some(since), Some(until)) = (query.since, query.until)
+        && since > until
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "since cannot be after until"})),
+        ));
+    }
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let categories = tokio::task::spawn_blocking(move || {
+        Expense::category_totals_for_member(&mut conn, member_id, pool_id, query.since, query.until)
+            .expect("Failed to total categories")
+    })
+    .await
+    .expect("Task panicked");
+
+    Ok(CategoryAnalytics {
+        since: query.since,
+        until: query.until,
+        total_paid: categories.iter().map(|total| total.paid).sum(),
+        total_share: categories.iter().map(|total| total.share).sum(),
+        categories,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/analytics/categories",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to analyze"),
+        ("since" = Option<NaiveDate>, Query, description = "Only count expenses incurred on or after this date"),
+        ("until" = Option<NaiveDate>, Query, description = "Only count expenses incurred on or before this date")
+    ),
+    responses(
+        (status = 200, description = "What the caller paid and their share of the pool's expenses, per category", body = CategoryAnalytics),
+        (status = 400, description = "Invalid date range"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_pool_category_analytics_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Query(query): Query<CategoryAnalyticsQuery>,
+) -> Result<Json<CategoryAnalytics>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("get_pool_category_analytics_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let analytics = category_analytics(member_id, Some(path.pool_id), query).await?;
+
+    span.end();
+
+    Ok(Json(analytics))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/members/me/analytics/categories",
+    params(
+        ("since" = Option<NaiveDate>, Query, description = "Only count expenses incurred on or after this date"),
+        ("until" = Option<NaiveDate>, Query, description = "Only count expenses incurred on or before this date")
+    ),
+    responses(
+        (status = 200, description = "What the caller paid and their share across all of their pools, per category", body = CategoryAnalytics),
+        (status = 400, description = "Invalid date range"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_member_category_analytics_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Query(query): Query<CategoryAnalyticsQuery>,
+) -> Result<Json<CategoryAnalytics>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("get_member_category_analytics_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+
+    let analytics = category_analytics(member_id, None, query).await?;
+
+    span.end();
+
+    Ok(Json(analytics))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TimeSeriesQuery {
+    #[serde(default)]
+    interval: TimeSeriesInterval,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    timezone: Option<String>,
+    #[serde(default)]
+    by_category: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TimeSeries {
+    interval: TimeSeriesInterval,
+    timezone: String,
+    points: Vec<TimeSeriesPoint>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/analytics/timeseries",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to chart"),
+        ("interval" = Option<TimeSeriesInterval>, Query, description = "Bucket size. Defaults to Month"),
+        ("since" = Option<NaiveDate>, Query, description = "Only count expenses incurred on or after this date. Defaults to the start of the 12th most recent bucket"),
+        ("until" = Option<NaiveDate>, Query, description = "Only count expenses incurred on or before this date. Defaults to today"),
+        ("timezone" = Option<String>, Query, description = "IANA time zone used to decide what today is, like America/New_York. Defaults to UTC"),
+        ("by_category" = Option<bool>, Query, description = "Split each bucket by category")
+    ),
+    responses(
+        (status = 200, description = "Pool spending and each member's share per bucket, oldest first, with empty buckets zero-filled", body = TimeSeries),
+        (status = 400, description = "Invalid date range or time zone"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_pool_time_series_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Query(query): Query<TimeSeriesQuery>,
+) -> Result<Json<TimeSeries>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("get_pool_time_series_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    let timezone = query.timezone.unwrap_or_else(|| "UTC".to_string());
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("interval", query.interval.as_sql()));
+    span.set_attribute(KeyValue::new("timezone", timezone.clone()));
+
+    if let Some(since) = query.since {
+        let until = query.until.unwrap_or_else(|| Utc::now().date_naive());
+
+        if since > until {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "since cannot be after until"})),
+            ));
+        }
+
+        if query.interval.bucket_count(since, until) > MAX_TIME_SERIES_BUCKETS {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": format!("A time series cannot span more than {} buckets", MAX_TIME_SERIES_BUCKETS)
+                })),
+            ));
+        }
+    }
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let zone = timezone.clone();
+    let rows = tokio::task::spawn_blocking(move || {
+        if !models::is_valid_timezone(&mut conn, &zone).expect("Failed to check time zone") {
+            return None;
+        }
+
+        let rows = Expense::time_series_for_pool(
+            &mut conn,
+            path.pool_id,
+            query.interval,
+            query.since,
+            query.until,
+            &zone,
+            query.by_category,
+            DEFAULT_TIME_SERIES_BUCKETS,
+        )
+        .expect("Failed to compute time series");
+
+        Some(rows)
+    })
+    .await
+    .expect("Task panicked")
+    .ok_or((
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({"error": "Unknown time zone"})),
+    ))?;
+
+    span.end();
+
+    Ok(Json(TimeSeries {
+        interval: query.interval,
+        timezone,
+        points: group_time_series(rows),
+    }))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct BudgetInput {
+    /// Leave out to cap all of the pool's spending.
+    category: Option<ExpenseCategory>,
+    amount: f64,
+    #[serde(default)]
+    period: BudgetPeriod,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateBudgetInput {
+    amount: f64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct BudgetPath {
+    pool_id: uuid::Uuid,
+    budget_id: uuid::Uuid,
+}
+
+fn validate_budget_amount(amount: f64) -> Result<f64, (StatusCode, Json<serde_json::Value>)> {
+    if amount.is_finite() && amount > 0.0 {
+        Ok(amount)
+    } else {
+        Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Budget amount must be greater than zero"})),
+        ))
+    }
+}
+
+fn map_budget_write_error(error: diesel::result::Error) -> (StatusCode, Json<serde_json::Value>) {
+    match error {
+        diesel::result::Error::DatabaseError(
+            diesel::result::DatabaseErrorKind::UniqueViolation,
+            _,
+        ) => (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": "The pool already has a budget for this category and period"
+            })),
+        ),
+        diesel::result::Error::NotFound => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Budget not found"})),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Failed to save budget"})),
+        ),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/budgets",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to list budgets for")
+    ),
+    responses(
+        (status = 200, description = "The pool's budgets", body = Vec<Budget>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_pool_budgets_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+) -> Result<Json<Vec<Budget>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("list_pool_budgets_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let budgets = tokio::task::spawn_blocking(move || {
+        Budget::list_for_pool(&mut conn, path.pool_id).expect("Failed to list budgets")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(budgets))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pools/{pool_id}/budgets",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to add the budget to")
+    ),
+    request_body = BudgetInput,
+    responses(
+        (status = 200, description = "The created budget", body = Budget),
+        (status = 400, description = "Invalid amount"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 409, description = "The pool already has a budget for this category and period"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_pool_budget_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Json(input): Json<BudgetInput>,
+) -> Result<Json<Budget>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("create_pool_budget_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    let new_budget = NewBudget {
+        pool_id: path.pool_id,
+        category: input.category,
+        amount: validate_budget_amount(input.amount)?,
+        period: input.period,
+        created_by_member_id: member_id,
+    };
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let budget = tokio::task::spawn_blocking(move || Budget::create(&mut conn, &new_budget))
+        .await
+        .expect("Task panicked")
+        .map_err(map_budget_write_error)?;
+
+    span.end();
+
+    Ok(Json(budget))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/pools/{pool_id}/budgets/{budget_id}",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the budget belongs to"),
+        ("budget_id" = uuid::Uuid, Path, description = "ID of the budget to update")
+    ),
+    request_body = UpdateBudgetInput,
+    responses(
+        (status = 200, description = "The updated budget", body = Budget),
+        (status = 400, description = "Invalid amount"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Budget not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_pool_budget_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<BudgetPath>,
+    Json(input): Json<UpdateBudgetInput>,
+) -> Result<Json<Budget>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("update_pool_budget_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("budget_id", path.budget_id.to_string()));
+
+    let changeset = BudgetChangeset {
+        amount: Some(validate_budget_amount(input.amount)?),
+    };
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let budget = tokio::task::spawn_blocking(move || {
+        Budget::find(&mut conn, path.budget_id, path.pool_id)?;
+        Budget::update(&mut conn, path.budget_id, &changeset)
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(map_budget_write_error)?;
+
+    span.end();
+
+    Ok(Json(budget))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/pools/{pool_id}/budgets/{budget_id}",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the budget belongs to"),
+        ("budget_id" = uuid::Uuid, Path, description = "ID of the budget to delete")
+    ),
+    responses(
+        (status = 200, description = "The deleted budget", body = Budget),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Budget not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_pool_budget_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<BudgetPath>,
+) -> Result<Json<Budget>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("delete_pool_budget_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("budget_id", path.budget_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let budget = tokio::task::spawn_blocking(move || {
+        Budget::find(&mut conn, path.budget_id, path.pool_id)?;
+        Budget::delete(&mut conn, path.budget_id)
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(map_budget_write_error)?;
+
+    span.end();
+
+    Ok(Json(budget))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct BudgetStatusQuery {
+    on: Option<NaiveDate>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/budgets/status",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to report on"),
+        ("on" = Option<NaiveDate>, Query, description = "Report on the periods containing this date. Defaults to today")
+    ),
+    responses(
+        (status = 200, description = "Spent, remaining and projected amounts for each of the pool's budgets", body = Vec<BudgetStatus>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_pool_budget_status_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Query(query): Query<BudgetStatusQuery>,
+) -> Result<Json<Vec<BudgetStatus>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("get_pool_budget_status_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let on = query.on.unwrap_or_else(|| Utc::now().date_naive());
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let statuses = tokio::task::spawn_blocking(move || {
+        Budget::list_for_pool(&mut conn, path.pool_id)
+            .and_then(|budgets| {
+                budgets
+                    .into_iter()
+                    .map(|budget| budget.status_on(&mut conn, on))
+                    .collect::<Result<Vec<_>, diesel::result::Error>>()
+            })
+            .expect("Failed to compute budget status")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(statuses))
+}
+
+fn import_error(error: ImportError) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({"error": error.to_string()})),
+    )
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CsvImportInput {
+    /// The CSV file's contents, including its header row.
+    csv: String,
+    mapping: ColumnMapping,
+    /// Validate and preview the import without saving anything.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CsvImportRow {
+    row: usize,
+    name: String,
+    amount: f64,
+    incurred_on: NaiveDate,
+    paid_by_member_id: uuid::Uuid,
+    category: ExpenseCategory,
+    custom_category_id: Option<uuid::Uuid>,
+    /// The payer's rule that categorized a row without a category.
+    applied_rule_id: Option<uuid::Uuid>,
+    split_method: SplitMethod,
+    line_items: Vec<ImportLineItem>,
+    /// The created expense. Always empty on a dry run.
+    expense_id: Option<uuid::Uuid>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CsvImportResult {
+    dry_run: bool,
+    imported: usize,
+    rows: Vec<CsvImportRow>,
+    errors: Vec<ImportRowError>,
+}
+
+/// Saves an import row as an expense, recording who imported it.
+fn create_imported_expense(
+    conn: &mut PgConnection,
+    pool_id: uuid::Uuid,
+    member_id: uuid::Uuid,
+    imported: &mut CsvImportRow,
+) -> diesel::QueryResult<()> {
+    let new_expense = models::NewExpense {
+        name: imported.name.clone(),
+        amount: imported.amount,
+        is_settled: false,
+        pool_id,
+        paid_by_member_id: imported.paid_by_member_id,
+        description: None,
+        notes: None,
+        category: imported.category.clone(),
+        split_method: imported.split_method.clone(),
+        incurred_on: Some(imported.incurred_on),
+        custom_category_id: imported.custom_category_id,
+        categorized_by_rule: imported.applied_rule_id.is_some(),
+    };
+
+    let debtor_member_ids: Vec<uuid::Uuid> = imported
+        .line_items
+        .iter()
+        .map(|item| item.debtor_member_id)
+        .collect();
+    let amounts: Vec<f64> = imported.line_items.iter().map(|item| item.amount).collect();
+
+    let (expense, line_items) =
+        models::Expense::create_with_line_items(conn, &new_expense, &debtor_member_ids, &amounts)?;
+
+    imported.expense_id = Some(expense.id);
+
+    let snapshot = expense.with_line_items(line_items);
+    ExpenseEvent::record(
+        conn,
+        &NewExpenseEvent::new(
+            ExpenseEventAction::Created,
+            member_id,
+            None,
+            Some(&snapshot),
+        ),
+    )?;
+
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pools/{pool_id}/import/csv",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to import expenses into")
+    ),
+    request_body = CsvImportInput,
+    responses(
+        (status = 200, description = "The imported rows, or a preview of them on a dry run", body = CsvImportResult),
+        (status = 400, description = "Unreadable CSV or a mapped column is missing"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 422, description = "Some rows are invalid, so nothing was imported"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn import_pool_expenses_csv_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Json(input): Json<CsvImportInput>,
+) -> Result<Json<CsvImportResult>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("import_pool_expenses_csv_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("dry_run", input.dry_run));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let pool_id = path.pool_id;
+    let dry_run = input.dry_run;
+
+    let (members, custom_categories) = tokio::task::spawn_blocking(move || {
+        let members = PoolMembership::list(&mut conn, pool_id)?;
+        let custom_categories = PoolCategory::list_for_pool(&mut conn, pool_id)?;
+
+        Ok::<_, diesel::result::Error>((members, custom_categories))
+    })
+    .await
+    .expect("Task panicked")
+    .expect("Failed to load pool members and categories");
+
+    let members: Vec<ImportMember> = members
+        .into_iter()
+        .map(|(membership, member)| ImportMember {
+            id: member.id,
+            first_name: member.first_name,
+            last_name: member.last_name,
+            email: member.email,
+            default_split_percentage: membership.default_split_percentage,
+        })
+        .collect();
+
+    let plan = plan_import(&input.csv, &input.mapping, &members, &custom_categories)
+        .map_err(import_error)?;
+
+    span.set_attribute(KeyValue::new("row_count", plan.rows.len() as i64));
+    span.set_attribute(KeyValue::new("error_count", plan.errors.len() as i64));
+
+    if !dry_run && !plan.errors.is_empty() {
+        span.end();
+
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({
+                "error": "Some rows are invalid, so nothing was imported",
+                "errors": plan.errors,
+            })),
+        ));
+    }
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let rows = tokio::task::spawn_blocking(move || {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let rules_by_payer = plan
+                .rows
+                .iter()
+                .filter(|row| row.category.is_none())
+                .map(|row| row.paid_by_member_id)
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .map(|payer_id| {
+                    ExpenseCategoryRule::find_for_member(conn, payer_id)
+                        .map(|rules| (payer_id, rules))
+                })
+                .collect::<Result<HashMap<_, _>, _>>()?;
+
+            let mut rows = Vec::new();
+
+            for row in plan.rows {
+                let (category, custom_category_id, applied_rule_id) = match row.category {
+                    Some((category, custom_category_id)) => (category, custom_category_id, None),
+                    None => {
+                        let subject = RuleSubject {
+                            name: &row.name,
+                            amount: row.amount,
+                            pool_id,
+                        };
+
+                        match rules_by_payer
+                            .get(&row.paid_by_member_id)
+                            .and_then(|rules| find_matching_rule(rules, &subject))
+                        {
+                            Some(rule) => (
+                                rule.category.clone(),
+                                rule.custom_category_id,
+                                Some(rule.id),
+                            ),
+                            None => (ExpenseCategory::Miscellaneous, None, None),
+                        }
+                    }
+                };
+
+                let mut imported = CsvImportRow {
+                    row: row.row,
+                    name: row.name,
+                    amount: row.amount,
+                    incurred_on: row.incurred_on,
+                    paid_by_member_id: row.paid_by_member_id,
+                    category,
+                    custom_category_id,
+                    applied_rule_id,
+                    split_method: row.split_method,
+                    line_items: row.line_items,
+                    expense_id: None,
+                };
+
+                if !dry_run {
+                    create_imported_expense(conn, pool_id, member_id, &mut imported)?;
+                }
+
+                rows.push(imported);
+            }
+
+            Ok(rows)
+        })
+    })
+    .await
+    .expect("Task panicked")
+    .expect("Failed to import expenses");
+
+    span.end();
+
+    Ok(Json(CsvImportResult {
+        dry_run,
+        imported: if dry_run { 0 } else { rows.len() },
+        rows,
+        errors: plan.errors,
+    }))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SplitwiseImportInput {
+    /// The contents of a Splitwise group export.
+    csv: String,
+    /// Maps member names in the export to pool members. Names left out are
+    /// matched against pool members' names.
+    #[serde(default)]
+    members: HashMap<String, uuid::Uuid>,
+    /// Create placeholder members for names that match no pool member.
+    #[serde(default)]
+    create_placeholders: bool,
+    /// Validate and preview the import without saving anything.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SplitwiseMemberMapping {
+    name: String,
+    member_id: uuid::Uuid,
+    is_placeholder: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SplitwiseImportResult {
+    dry_run: bool,
+    imported: usize,
+    members: Vec<SplitwiseMemberMapping>,
+    rows: Vec<CsvImportRow>,
+    errors: Vec<ImportRowError>,
+    /// Payments between members, which aren't imported. They're left out of
+    /// the balance check too.
+    skipped: Vec<ImportRowError>,
+    balances: Vec<SplitwiseBalanceCheck>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pools/{pool_id}/import/splitwise",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to import expenses into")
+    ),
+    request_body = SplitwiseImportInput,
+    responses(
+        (status = 200, description = "The imported rows, or a preview of them on a dry run", body = SplitwiseImportResult),
+        (status = 400, description = "Not a Splitwise export, or a mapped member isn't in the pool or has several names mapped to it"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 422, description = "Some rows are invalid or balances don't match the export, so nothing was imported"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn import_pool_expenses_splitwise_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Json(input): Json<SplitwiseImportInput>,
+) -> Result<Json<SplitwiseImportResult>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("import_pool_expenses_splitwise_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("dry_run", input.dry_run));
+
+    let export = parse_splitwise_export(&input.csv).map_err(import_error)?;
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let pool_id = path.pool_id;
+    let dry_run = input.dry_run;
+
+    let pool_members =
+        tokio::task::spawn_blocking(move || PoolMembership::list(&mut conn, pool_id))
+            .await
+            .expect("Task panicked")
+            .expect("Failed to list members of pool");
+
+    let pool_members: Vec<ImportMember> = pool_members
+        .into_iter()
+        .map(|(membership, member)| ImportMember {
+            id: member.id,
+            first_name: member.first_name,
+            last_name: member.last_name,
+            email: member.email,
+            default_split_percentage: membership.default_split_percentage,
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    let mut members = Vec::new();
+
+    for name in &export.members {
+        let mapping = match input.members.get(name) {
+            Some(mapped_id) if pool_members.iter().any(|member| member.id == *mapped_id) => {
+                Some((*mapped_id, false))
+            }
+            Some(mapped_id) => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "error": format!("{mapped_id} is not a member of this pool")
+                    })),
+                ));
+            }
+            None => match resolve_member(&pool_members, name) {
+                Ok(resolved_id) => Some((resolved_id, false)),
+                Err(_) if input.create_placeholders => Some((uuid::Uuid::new_v4(), true)),
+                Err(message) => {
+                    errors.push(ImportRowError {
+                        row: 1,
+                        column: Some(name.clone()),
+                        message,
+                    });
+                    None
+                }
+            },
+        };
+
+        if let Some((mapped_id, is_placeholder)) = mapping {
+            members.push(SplitwiseMemberMapping {
+                name: name.clone(),
+                member_id: mapped_id,
+                is_placeholder,
+            });
+        }
+    }
+
+    if let Some((first, second)) = find_duplicate_mapping(
+        members
+            .iter()
+            .map(|member| (member.name.as_str(), member.member_id)),
+    ) {
+        span.end();
+
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("{first} and {second} can't both be the same pool member")
+            })),
+        ));
+    }
+
+    let skipped: Vec<ImportRowError> = export
+        .payments
+        .iter()
+        .map(|payment| ImportRowError {
+            row: payment.row,
+            column: Some("Category".to_string()),
+            message: format!(
+                "\"{}\" is a payment, which isn't imported",
+                payment.description
+            ),
+        })
+        .collect();
+
+    if !errors.is_empty() {
+        span.end();
+
+        if dry_run {
+            return Ok(Json(SplitwiseImportResult {
+                dry_run,
+                imported: 0,
+                members,
+                rows: Vec::new(),
+                errors,
+                skipped,
+                balances: Vec::new(),
+            }));
+        }
+
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({
+                "error": "Some Splitwise members match no pool member",
+                "errors": errors,
+            })),
+        ));
+    }
+
+    let member_ids: Vec<uuid::Uuid> = members.iter().map(|member| member.member_id).collect();
+
+    let import_rows: Vec<ImportRow> = export
+        .expenses
+        .iter()
+        .flat_map(|expense| splitwise_expense_rows(expense, &member_ids))
+        .collect();
+
+    let balances = check_balances(&export, &import_rows, &member_ids);
+    errors.extend(export.errors);
+
+    span.set_attribute(KeyValue::new("row_count", import_rows.len() as i64));
+    span.set_attribute(KeyValue::new("error_count", errors.len() as i64));
+
+    if !dry_run && (!errors.is_empty() || balances.iter().any(|balance| !balance.matches)) {
+        span.end();
+
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({
+                "error": "Some rows are invalid or balances don't match the export, so nothing was imported",
+                "errors": errors,
+                "balances": balances,
+            })),
+        ));
+    }
+
+    let mut rows: Vec<CsvImportRow> = import_rows
+        .into_iter()
+        .map(|row| {
+            let (category, custom_category_id) = row
+                .category
+                .unwrap_or((ExpenseCategory::Miscellaneous, None));
+
+            CsvImportRow {
+                row: row.row,
+                name: row.name,
+                amount: row.amount,
+                incurred_on: row.incurred_on,
+                paid_by_member_id: row.paid_by_member_id,
+                category,
+                custom_category_id,
+                applied_rule_id: None,
+                split_method: row.split_method,
+                line_items: row.line_items,
+                expense_id: None,
+            }
+        })
+        .collect();
+
+    if !dry_run {
+        let mut conn = get_db_connection()
+            .await
+            .expect("Failed to get database connection");
+
+        let placeholders: Vec<(uuid::Uuid, String)> = members
+            .iter()
+            .filter(|member| member.is_placeholder)
+            .map(|member| (member.member_id, member.name.clone()))
+            .collect();
+
+        rows = tokio::task::spawn_blocking(move || {
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                for (placeholder_id, name) in &placeholders {
+                    let (first_name, last_name) = name.split_once(' ').unwrap_or((name, ""));
+                    Member::create_placeholder(conn, *placeholder_id, first_name, last_name)?;
+                    PoolMembership::add_member(conn, pool_id, *placeholder_id)?;
+                }
+
+                for row in rows.iter_mut() {
+                    create_imported_expense(conn, pool_id, member_id, row)?;
+                }
+
+                Ok(rows)
+            })
+        })
+        .await
+        .expect("Task panicked")
+        .expect("Failed to import expenses");
+    }
+
+    span.end();
+
+    Ok(Json(SplitwiseImportResult {
+        dry_run,
+        imported: if dry_run { 0 } else { rows.len() },
+        members,
+        rows,
+        errors,
+        skipped,
+        balances,
+    }))
+}
+
+/// How many expenses the export reads from the database at a time.
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+#[derive(Deserialize, ToSchema)]
+pub struct PoolExportQuery {
+    #[serde(default)]
+    format: ExportFormat,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    currency: Option<String>,
+}
+
+fn export_error(error: ExportError) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({"error": error.to_string()})),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/export",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to export"),
+        ("format" = Option<ExportFormat>, Query, description = "Export format (default Csv)"),
+        ("since" = Option<NaiveDate>, Query, description = "Only include expenses incurred on or after this date"),
+        ("until" = Option<NaiveDate>, Query, description = "Only include expenses incurred on or before this date"),
+        ("currency" = Option<String>, Query, description = "Commodity for the ledger formats (default USD)")
+    ),
+    responses(
+        (status = 200, description = "The pool's history, streamed as CSV, JSON or a ledger journal", content(
+            ("text/csv"),
+            ("application/json"),
+            ("text/plain")
+        )),
+        (status = 400, description = "Invalid currency or date range"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn export_pool_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Query(query): Query<PoolExportQuery>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("export_pool_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new(
+        "format",
+        query.format.file_extension().to_string(),
+    ));
+
+    let currency = validate_currency(query.currency.as_deref().unwrap_or(DEFAULT_EXPORT_CURRENCY))
+        .map_err(export_error)?;
+
+    if let (Some(since), Some(until)) = (query.since, query.until)
+        && since > until
+    {
+        return Err(export_error(ExportError::InvalidDateRange));
+    }
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let pool_id = path.pool_id;
+    let format = query.format;
+    let since = query.since;
+    let until = query.until;
+
+    let pool = tokio::task::spawn_blocking(move || models::Pool::find(&mut conn, pool_id))
+        .await
+        .expect("Task panicked")
+        .expect("Failed to load pool for export");
+
+    let file_name = format!(
+        "{}-{}.{}",
+        pool.name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+            .to_lowercase(),
+        Utc::now().format("%Y-%m-%d"),
+        format.file_extension()
+    );
+
+    let (sender, receiver) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(4);
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    // The export is written batch by batch on a blocking thread while the
+    // response streams it out, all from one snapshot so expenses and
+    // settlements written later in the stream agree with earlier ones.
+    // Sending fails once the client disconnects, which stops the export.
+    tokio::task::spawn_blocking(move || {
+        let result = conn
+            .build_transaction()
+            .repeatable_read()
+            .read_only()
+            .run(|conn| {
+                let pool = models::Pool::find(conn, pool_id)?;
+                let members: Vec<Member> = PoolMembership::list(conn, pool_id)?
+                    .into_iter()
+                    .map(|(_, member)| member)
+                    .collect();
+                let custom_categories = PoolCategory::list_for_pool(conn, pool_id)?;
+
+                let mut writer = ExportWriter::new(format, &currency, &members, &custom_categories);
+
+                let header = writer.begin(
+                    &pool,
+                    &members,
+                    &custom_categories,
+                    since,
+                    until,
+                    Utc::now(),
+                );
+                if sender.blocking_send(Ok(header)).is_err() {
+                    return Ok(());
+                }
+
+                let mut after: Option<ExpenseCursor> = None;
+
+                loop {
+                    let batch = Expense::export_batch(
+                        conn,
+                        pool_id,
+                        since,
+                        until,
+                        after.as_ref(),
+                        EXPORT_BATCH_SIZE,
+                    )?;
+
+                    let chunk: String = batch
+                        .iter()
+                        .map(|expense| writer.expense(expense))
+                        .collect();
+                    if sender.blocking_send(Ok(chunk)).is_err() {
+                        return Ok(());
+                    }
+
+                    match batch.last() {
+                        Some(last) if batch.len() as i64 == EXPORT_BATCH_SIZE => {
+                            after = Some(ExpenseCursor {
+                                incurred_on: last.incurred_on,
+                                id: last.id,
+                            });
+                        }
+                        _ => break,
+                    }
+                }
+
+                let settlements: Vec<ExportSettlement> =
+                    ExpenseEvent::settlements_for_pool(conn, pool_id, since, until)?
+                        .into_iter()
+                        .map(|event| ExportSettlement {
+                            expense_id: event.expense_id,
+                            settled_by_member_id: event.actor_member_id,
+                            settled_at: event.inserted_at,
+                        })
+                        .collect();
+
+                let _ = sender.blocking_send(Ok(writer.finish(&settlements)));
+
+                Ok::<_, diesel::result::Error>(())
+            });
+
+        if let Err(e) = result {
+            let _ = sender.blocking_send(Err(std::io::Error::other(e)));
+        }
+    });
+
+    span.end();
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        axum::body::Body::from_stream(ReceiverStream::new(receiver)),
+    )
+        .into_response())
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct WebhookInput {
+    url: String,
+    events: Vec<WebhookEvent>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateWebhookInput {
+    url: Option<String>,
+    events: Option<Vec<WebhookEvent>>,
+    is_active: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    webhook: Webhook,
+    /// Used to sign deliveries. It's only ever returned here, so keep it.
+    secret: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct WebhookPath {
+    pool_id: uuid::Uuid,
+    webhook_id: uuid::Uuid,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct WebhookDeliveryPath {
+    pool_id: uuid::Uuid,
+    webhook_id: uuid::Uuid,
+    delivery_id: uuid::Uuid,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct WebhookDeliveriesQuery {
+    before: Option<DateTime<Utc>>,
+    limit: Option<i64>,
+}
+
+fn webhook_error(error: WebhookError) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({"error": error.to_string()})),
+    )
+}
+
+fn map_webhook_write_error(error: diesel::result::Error) -> (StatusCode, Json<serde_json::Value>) {
+    match error {
+        diesel::result::Error::NotFound => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Webhook not found"})),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Failed to save webhook"})),
+        ),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/webhooks",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to list webhooks for")
+    ),
+    responses(
+        (status = 200, description = "The pool's webhooks", body = Vec<Webhook>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_pool_webhooks_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+) -> Result<Json<Vec<Webhook>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("list_pool_webhooks_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let webhooks = tokio::task::spawn_blocking(move || {
+        Webhook::list_for_pool(&mut conn, path.pool_id).expect("Failed to list webhooks")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(webhooks))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pools/{pool_id}/webhooks",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to add the webhook to")
+    ),
+    request_body = WebhookInput,
+    responses(
+        (status = 200, description = "The created webhook, including its signing secret", body = CreatedWebhook),
+        (status = 400, description = "Invalid or private URL, or no events"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_pool_webhook_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Json(input): Json<WebhookInput>,
+) -> Result<Json<CreatedWebhook>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("create_pool_webhook_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    let new_webhook = NewWebhook {
+        pool_id: path.pool_id,
+        created_by_member_id: member_id,
+        url: validate_webhook_url(&input.url, &WEBHOOK_ALLOWED_HOSTS)
+            .await
+            .map_err(webhook_error)?,
+        secret: generate_secret(),
+        events: validate_events(&input.events).map_err(webhook_error)?,
+    };
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let webhook = tokio::task::spawn_blocking(move || Webhook::create(&mut conn, &new_webhook))
+        .await
+        .expect("Task panicked")
+        .map_err(map_webhook_write_error)?;
+
+    span.end();
+
+    Ok(Json(CreatedWebhook {
+        secret: webhook.secret.clone(),
+        webhook,
+    }))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/pools/{pool_id}/webhooks/{webhook_id}",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the webhook belongs to"),
+        ("webhook_id" = uuid::Uuid, Path, description = "ID of the webhook to update")
+    ),
+    request_body = UpdateWebhookInput,
+    responses(
+        (status = 200, description = "The updated webhook", body = Webhook),
+        (status = 400, description = "Invalid or private URL, or no events"),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Webhook not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_pool_webhook_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<WebhookPath>,
+    Json(input): Json<UpdateWebhookInput>,
+) -> Result<Json<Webhook>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("update_pool_webhook_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("webhook_id", path.webhook_id.to_string()));
+
+    let changeset = WebhookChangeset {
+        url: match input.url.as_deref() {
+            Some(url) => Some(
+                validate_webhook_url(url, &WEBHOOK_ALLOWED_HOSTS)
+                    .await
+                    .map_err(webhook_error)?,
+            ),
+            None => None,
+        },
+        events: input
+            .events
+            .as_deref()
+            .map(validate_events)
+            .transpose()
+            .map_err(webhook_error)?,
+        is_active: input.is_active,
+    };
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let webhook = tokio::task::spawn_blocking(move || {
+        let webhook = Webhook::find(&mut conn, path.webhook_id, path.pool_id)?;
+
+        if changeset.url.is_none() && changeset.events.is_none() && changeset.is_active.is_none() {
+            return Ok(webhook);
+        }
+
+        Webhook::update(&mut conn, path.webhook_id, &changeset)
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(map_webhook_write_error)?;
+
+    span.end();
+
+    Ok(Json(webhook))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/pools/{pool_id}/webhooks/{webhook_id}",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the webhook belongs to"),
+        ("webhook_id" = uuid::Uuid, Path, description = "ID of the webhook to delete")
+    ),
+    responses(
+        (status = 200, description = "The deleted webhook", body = Webhook),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Webhook not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_pool_webhook_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<WebhookPath>,
+) -> Result<Json<Webhook>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("delete_pool_webhook_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("webhook_id", path.webhook_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let webhook = tokio::task::spawn_blocking(move || {
+        Webhook::find(&mut conn, path.webhook_id, path.pool_id)?;
+        Webhook::delete(&mut conn, path.webhook_id)
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(map_webhook_write_error)?;
+
+    span.end();
+
+    Ok(Json(webhook))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/webhooks/{webhook_id}/deliveries",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the webhook belongs to"),
+        ("webhook_id" = uuid::Uuid, Path, description = "ID of the webhook to list deliveries for"),
+        ("before" = Option<chrono::DateTime<Utc>>, Query, description = "Only return deliveries queued before this time"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of deliveries to return (default 50, max 200)"),
+    ),
+    responses(
+        (status = 200, description = "The webhook's deliveries, newest first", body = Vec<WebhookDelivery>),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Webhook not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_webhook_deliveries_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<WebhookPath>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+) -> Result<Json<Vec<WebhookDelivery>>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("list_webhook_deliveries_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("webhook_id", path.webhook_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let deliveries = tokio::task::spawn_blocking(move || {
+        Webhook::find(&mut conn, path.webhook_id, path.pool_id)?;
+        WebhookDelivery::list_for_webhook(&mut conn, path.webhook_id, query.before, limit)
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(map_webhook_write_error)?;
+
+    span.end();
+
+    Ok(Json(deliveries))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pools/{pool_id}/webhooks/{webhook_id}/deliveries/{delivery_id}/retry",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool the webhook belongs to"),
+        ("webhook_id" = uuid::Uuid, Path, description = "ID of the webhook the delivery was sent to"),
+        ("delivery_id" = uuid::Uuid, Path, description = "ID of the delivery to send again")
+    ),
+    responses(
+        (status = 200, description = "The delivery, queued to be sent again", body = WebhookDelivery),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 404, description = "Webhook or delivery not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn retry_webhook_delivery_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<WebhookDeliveryPath>,
+) -> Result<Json<WebhookDelivery>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("retry_webhook_delivery_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+    span.set_attribute(KeyValue::new("webhook_id", path.webhook_id.to_string()));
+    span.set_attribute(KeyValue::new("delivery_id", path.delivery_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let delivery = tokio::task::spawn_blocking(move || {
+        Webhook::find(&mut conn, path.webhook_id, path.pool_id)?;
+        WebhookDelivery::retry(&mut conn, path.delivery_id, path.webhook_id)
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(|e| match e {
+        diesel::result::Error::NotFound => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Webhook delivery not found"})),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Failed to retry webhook delivery"})),
+        ),
+    })?;
+
+    span.end();
+
+    Ok(Json(delivery))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct NotificationsQuery {
+    #[serde(default)]
+    unread: bool,
+    before: Option<DateTime<Utc>>,
+    limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct NotificationList {
+    /// Across all of the member's notifications, not just this page.
+    unread_count: i64,
+    notifications: Vec<Notification>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct NotificationPath {
+    notification_id: uuid::Uuid,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notifications",
+    params(
+        ("unread" = Option<bool>, Query, description = "Only return unread notifications"),
+        ("before" = Option<chrono::DateTime<Utc>>, Query, description = "Only return notifications sent before this time"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of notifications to return (default 50, max 200)"),
+    ),
+    responses(
+        (status = 200, description = "The member's notifications, newest first", body = NotificationList),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_notifications_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Query(query): Query<NotificationsQuery>,
+) -> Result<Json<NotificationList>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("list_notifications_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let list = tokio::task::spawn_blocking(move || {
+        let notifications =
+            Notification::list_for_member(&mut conn, member_id, query.unread, query.before, limit)?;
+        let unread_count = Notification::unread_count(&mut conn, member_id)?;
+
+        Ok::<_, diesel::result::Error>(NotificationList {
+            unread_count,
+            notifications,
+        })
+    })
+    .await
+    .expect("Task panicked")
+    .expect("Failed to list notifications");
+
+    span.end();
+
+    Ok(Json(list))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/notifications/{notification_id}/read",
+    params(
+        ("notification_id" = uuid::Uuid, Path, description = "ID of the notification to mark read")
+    ),
+    responses(
+        (status = 200, description = "The notification, marked read", body = Notification),
+        (status = 404, description = "Notification not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn mark_notification_read_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<NotificationPath>,
+) -> Result<Json<Notification>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("mark_notification_read_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new(
+        "notification_id",
+        path.notification_id.to_string(),
+    ));
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let notification = tokio::task::spawn_blocking(move || {
+        Notification::mark_read(&mut conn, path.notification_id, member_id)
+    })
+    .await
+    .expect("Task panicked")
+    .map_err(|e| match e {
+        diesel::result::Error::NotFound => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Notification not found"})),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Failed to mark notification read"})),
+        ),
+    })?;
+
+    span.end();
+
+    Ok(Json(notification))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/notifications/read-all",
+    responses(
+        (status = 200, description = "How many notifications were marked read", body = serde_json::Value),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn mark_all_notifications_read_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+) -> Json<serde_json::Value> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("mark_all_notifications_read_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let updated = tokio::task::spawn_blocking(move || {
+        Notification::mark_all_read(&mut conn, member_id)
+            .expect("Failed to mark notifications read")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Json(serde_json::json!({"success": true, "updated": updated}))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notifications/preferences",
+    responses(
+        (status = 200, description = "Which kinds of notification the member gets", body = NotificationPreferences),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_notification_preferences_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+) -> Json<NotificationPreferences> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("get_notification_preferences_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let preferences = tokio::task::spawn_blocking(move || {
+        NotificationPreferences::for_member(&mut conn, member_id)
+            .expect("Failed to get notification preferences")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Json(preferences)
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/notifications/preferences",
+    request_body = NotificationPreferencesChangeset,
+    responses(
+        (status = 200, description = "The updated preferences", body = NotificationPreferences),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_notification_preferences_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Json(changeset): Json<NotificationPreferencesChangeset>,
+) -> Json<NotificationPreferences> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("update_notification_preferences_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let preferences = tokio::task::spawn_blocking(move || {
+        NotificationPreferences::update(&mut conn, member_id, &changeset)
+            .expect("Failed to update notification preferences")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Json(preferences)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/pools/{pool_id}/email-preferences",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to get email preferences for")
+    ),
+    responses(
+        (status = 200, description = "Which emails the member gets about the pool", body = PoolEmailPreferences),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_pool_email_preferences_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+) -> Result<Json<PoolEmailPreferences>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("get_pool_email_preferences_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let preferences = tokio::task::spawn_blocking(move || {
+        PoolEmailPreferences::for_member(&mut conn, path.pool_id, member_id)
+            .expect("Failed to get email preferences")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(preferences))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/pools/{pool_id}/email-preferences",
+    params(
+        ("pool_id" = uuid::Uuid, Path, description = "ID of the pool to update email preferences for")
+    ),
+    request_body = PoolEmailPreferencesChangeset,
+    responses(
+        (status = 200, description = "The updated preferences", body = PoolEmailPreferences),
+        (status = 403, description = "Not a member of the pool"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_pool_email_preferences_handler(
+    AuthenticatedUser(member_id): AuthenticatedUser,
+    Path(path): Path<PoolDetailsPath>,
+    Json(changeset): Json<PoolEmailPreferencesChangeset>,
+) -> Result<Json<PoolEmailPreferences>, (StatusCode, Json<serde_json::Value>)> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("update_pool_email_preferences_handler")
+        .with_kind(SpanKind::Server)
+        .start(tracer);
+
+    span.set_attribute(KeyValue::new("member_id", member_id.to_string()));
+    span.set_attribute(KeyValue::new("pool_id", path.pool_id.to_string()));
+
+    ensure_pool_member(path.pool_id, member_id).await?;
+
+    let mut conn = get_db_connection()
+        .await
+        .expect("Failed to get database connection");
+
+    let preferences = tokio::task::spawn_blocking(move || {
+        PoolEmailPreferences::update(&mut conn, path.pool_id, member_id, &changeset)
+            .expect("Failed to update email preferences")
+    })
+    .await
+    .expect("Task panicked");
+
+    span.end();
+
+    Ok(Json(preferences))
+}
+
+pub fn handlers_routes() -> OpenApiRouter {
+    let public_routes = OpenApiRouter::new()
+        .routes(routes!(signup_handler))
+        .routes(routes!(login_handler))
+        .routes(routes!(authenticate_handler));
+
+    let protected_routes = OpenApiRouter::new()
+        .routes(routes!(get_member_handler))
+        .routes(routes!(create_pool_handler))
+        .routes(routes!(create_pool_membership_handler))
+        .routes(routes!(add_friend_to_pool_handler))
+        .routes(routes!(remove_friend_from_pool_handler))
+        .routes(routes!(list_friends_handler))
+        .routes(routes!(list_inbound_friend_requests_handler))
+        .routes(routes!(create_friend_request_handler))
+        .routes(routes!(accept_friend_request_handler))
+        .routes(routes!(get_expense_handler))
+        .routes(routes!(add_expense_handler))
+        .routes(routes!(get_pool_details_handler))
+        .routes(routes!(get_pool_recent_expenses_handler))
+        .routes(routes!(list_members_of_pool_handler))
+        .routes(routes!(list_pools_for_member_handler))
+        .routes(routes!(delete_friend_request))
+        .routes(routes!(get_pool_balances_for_member))
+        .routes(routes!(settle_up_pool_handler))
+        .routes(routes!(modify_default_splits_handler))
+        .routes(routes!(delete_expense_handler))
+        .routes(routes!(update_member_handler))
+        .routes(routes!(update_expense_handler))
+        .routes(routes!(list_expense_category_rules_handler))
+        .routes(routes!(create_expense_category_rule_handler))
+        .routes(routes!(delete_expense_category_rule_handler))
+        .routes(routes!(delete_expense_category_rule_by_id_handler))
+        .routes(routes!(preview_expense_category_rule_handler))
+        .routes(routes!(apply_expense_category_rules_handler))
+        .routes(routes!(suggest_expense_category_rules_handler))
+        .routes(routes!(suggest_expense_categories_handler))
+        .routes(routes!(list_expense_attachments_handler))
+        .routes(routes!(download_expense_attachment_handler))
+        .routes(routes!(delete_expense_attachment_handler))
+        .routes(routes!(
+            list_expense_comments_handler,
+            create_expense_comment_handler
+        ))
+        .routes(routes!(
+            edit_expense_comment_handler,
+            delete_expense_comment_handler
+        ))
+        .routes(routes!(get_pool_activity_handler))
+        .routes(routes!(pool_events_handler))
+        .routes(routes!(list_pool_trash_handler))
+        .routes(routes!(restore_expense_handler))
+        .routes(routes!(
+            list_pool_categories_handler,
+            create_pool_category_handler
+        ))
+        .routes(routes!(
+            update_pool_category_handler,
+            delete_pool_category_handler
+        ))
+        .routes(routes!(list_pool_tags_handler))
+        .routes(routes!(get_pool_tag_totals_handler))
+        .routes(routes!(set_expense_tags_handler))
+        .routes(routes!(search_expenses_handler))
+        .routes(routes!(get_pool_category_analytics_handler))
+        .routes(routes!(get_member_category_analytics_handler))
+        .routes(routes!(get_pool_time_series_handler))
+        .routes(routes!(
+            list_pool_budgets_handler,
+            create_pool_budget_handler
+        ))
+        .routes(routes!(
+            update_pool_budget_handler,
+            delete_pool_budget_handler
+        ))
+        .routes(routes!(get_pool_budget_status_handler))
+        .routes(routes!(import_pool_expenses_csv_handler))
+        .routes(routes!(import_pool_expenses_splitwise_handler))
+        .routes(routes!(export_pool_handler))
+        .routes(routes!(export_member_data_handler))
+        .routes(routes!(delete_member_handler))
+        .routes(routes!(
+            list_pool_webhooks_handler,
+            create_pool_webhook_handler
+        ))
+        .routes(routes!(
+            update_pool_webhook_handler,
+            delete_pool_webhook_handler
+        ))
+        .routes(routes!(list_webhook_deliveries_handler))
+        .routes(routes!(retry_webhook_delivery_handler))
+        .routes(routes!(list_notifications_handler))
+        .routes(routes!(mark_notification_read_handler))
+        .routes(routes!(mark_all_notifications_read_handler))
+        .routes(routes!(
+            get_notification_preferences_handler,
+            update_notification_preferences_handler
+        ))
+        .routes(routes!(
+            get_pool_email_preferences_handler,
+            update_pool_email_preferences_handler
+        ))
+        .route_layer(middleware::from_fn(trace_middleware));
+
+    let upload_routes = OpenApiRouter::new()
+        .routes(routes!(upload_expense_attachment_handler))
+        .layer(DefaultBodyLimit::max(attachment_max_bytes() + 64 * 1024))
         .route_layer(middleware::from_fn(trace_middleware));
 
-    public_routes.merge(protected_routes)
+    public_routes.merge(protected_routes).merge(upload_routes)
 }
@@ -0,0 +1,451 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::categories::parse_builtin_category;
+use crate::models::{ExpenseCategory, PoolCategory, SplitMethod};
+
+pub const MAX_IMPORT_ROWS: usize = 5000;
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ImportError {
+    #[error("could not read CSV: {0}")]
+    Csv(String),
+    #[error("column \"{0}\" is not in the CSV header")]
+    MissingColumn(String),
+    #[error("the CSV has no rows to import")]
+    NoRows,
+    #[error("an import cannot have more than {MAX_IMPORT_ROWS} rows")]
+    TooManyRows,
+}
+
+/// Which CSV header holds each expense field. Category and split columns
+/// are optional; rows without a category are left to the payer's rules and
+/// rows without a split use the pool's default split.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ColumnMapping {
+    pub date: String,
+    pub name: String,
+    pub amount: String,
+    pub payer: String,
+    pub category: Option<String>,
+    /// Shares written as `member=60%; member=40%` or `member=30; member=54.20`,
+    /// where members are referenced like the payer.
+    pub split: Option<String>,
+    /// A chrono format string for the date column. Defaults to `%Y-%m-%d`.
+    pub date_format: Option<String>,
+}
+
+/// A pool member that rows can refer to by email or name.
+#[derive(Debug, Clone)]
+pub struct ImportMember {
+    pub id: uuid::Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub default_split_percentage: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ImportRowError {
+    /// The row's line in the file, counting the header as line 1.
+    pub row: usize,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ImportLineItem {
+    pub debtor_member_id: uuid::Uuid,
+    pub amount: f64,
+}
+
+/// A row that passed validation. `category` is `None` when the row left it
+/// blank, in which case the payer's rules decide.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRow {
+    pub row: usize,
+    pub incurred_on: NaiveDate,
+    pub name: String,
+    pub amount: f64,
+    pub paid_by_member_id: uuid::Uuid,
+    pub category: Option<(ExpenseCategory, Option<uuid::Uuid>)>,
+    pub split_method: SplitMethod,
+    pub line_items: Vec<ImportLineItem>,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportPlan {
+    pub rows: Vec<ImportRow>,
+    pub errors: Vec<ImportRowError>,
+}
+
+enum Share {
+    Percentage(f64),
+    Amount(f64),
+}
+
+/// Reads CSV text whose rows may have differing lengths.
+pub(crate) fn csv_reader(data: &str) -> csv::Reader<&[u8]> {
+    csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data.as_bytes())
+}
+
+/// The line a record starts on. The csv crate positions a record at the
+/// blank lines before it, which would point people at the wrong row, so
+/// those are skipped from the record's position.
+pub(crate) fn record_line(data: &str, record: &csv::StringRecord) -> usize {
+    let bytes = data.as_bytes();
+    let position = record
+        .position()
+        .cloned()
+        .unwrap_or_else(csv::Position::new);
+    let mut offset = (position.byte() as usize).min(bytes.len());
+    let mut line = position.line() as usize;
+
+    while offset < bytes.len() && matches!(bytes[offset], b'\r' | b'\n') {
+        if bytes[offset] == b'\n' {
+            line += 1;
+        }
+
+        offset += 1;
+    }
+
+    line
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Parses an amount like `84`, `$1,204.50` or ` 12.5 `. Amounts must be
+/// positive and are rounded to cents.
+pub fn parse_amount(value: &str) -> Result<f64, String> {
+    let cleaned: String = value
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '$' | ',' | ' '))
+        .collect();
+
+    match cleaned.parse::<f64>() {
+        Ok(amount) if amount.is_finite() && amount > 0.0 => Ok(round_cents(amount)),
+        Ok(_) => Err("amount must be greater than zero".to_string()),
+        Err(_) => Err(format!("\"{}\" is not an amount", value.trim())),
+    }
+}
+
+/// Resolves a member reference by email, then full name, then first name.
+/// References are case-insensitive and must match exactly one member.
+pub fn resolve_member(members: &[ImportMember], reference: &str) -> Result<uuid::Uuid, String> {
+    let reference = reference.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if reference.is_empty() {
+        return Err("member cannot be empty".to_string());
+    }
+
+    let matchers: [&dyn Fn(&ImportMember) -> bool; 3] = [
+        &|member| member.email.eq_ignore_ascii_case(&reference),
+        &|member| {
+            format!("{} {}", member.first_name.trim(), member.last_name.trim())
+                .eq_ignore_ascii_case(&reference)
+        },
+        &|member| member.first_name.trim().eq_ignore_ascii_case(&reference),
+    ];
+
+    for matcher in matchers {
+        let matched: Vec<&ImportMember> = members.iter().filter(|member| matcher(member)).collect();
+
+        match matched.as_slice() {
+            [] => continue,
+            [member] => return Ok(member.id),
+            _ => {
+                return Err(format!("\"{reference}\" matches more than one pool member"));
+            }
+        }
+    }
+
+    Err(format!("\"{reference}\" is not a member of this pool"))
+}
+
+/// Resolves a category by a custom category's name first, then as a
+/// built-in category.
+pub fn resolve_category(
+    custom_categories: &[PoolCategory],
+    value: &str,
+) -> Result<(ExpenseCategory, Option<uuid::Uuid>), String> {
+    let value = value.trim();
+
+    if let Some(custom_category) = custom_categories
+        .iter()
+        .find(|category| category.name.eq_ignore_ascii_case(value))
+    {
+        return Ok((custom_category.builtin_category(), Some(custom_category.id)));
+    }
+
+    parse_builtin_category(value)
+        .map(|category| (category, None))
+        .ok_or_else(|| format!("\"{value}\" is not a category"))
+}
+
+/// Splits an amount by percentages, rounding each share to cents and giving
+/// the rounding difference to the first member.
+fn split_by_percentages(amount: f64, percentages: &[(uuid::Uuid, f64)]) -> Vec<ImportLineItem> {
+    let mut line_items: Vec<ImportLineItem> = percentages
+        .iter()
+        .map(|(member_id, percentage)| ImportLineItem {
+            debtor_member_id: *member_id,
+            amount: round_cents(amount * percentage / 100.0),
+        })
+        .collect();
+
+    let difference = round_cents(amount - line_items.iter().map(|item| item.amount).sum::<f64>());
+
+    if let Some(first) = line_items.first_mut() {
+        first.amount = round_cents(first.amount + difference);
+    }
+
+    line_items
+}
+
+/// Splits by the members' default split percentages, or evenly when those
+/// don't add up to 100.
+pub fn default_split(amount: f64, members: &[ImportMember]) -> Vec<ImportLineItem> {
+    let total: f64 = members
+        .iter()
+        .map(|member| member.default_split_percentage)
+        .sum();
+
+    let percentages: Vec<(uuid::Uuid, f64)> = if (total - 100.0).abs() < 0.01 {
+        members
+            .iter()
+            .filter(|member| member.default_split_percentage > 0.0)
+            .map(|member| (member.id, member.default_split_percentage))
+            .collect()
+    } else {
+        members
+            .iter()
+            .map(|member| (member.id, 100.0 / members.len() as f64))
+            .collect()
+    };
+
+    split_by_percentages(amount, &percentages)
+}
+
+/// Parses a split cell into line items. Every share must be of the same
+/// kind, percentages must add up to 100 and amounts to the expense amount.
+pub fn parse_split(
+    value: &str,
+    amount: f64,
+    members: &[ImportMember],
+) -> Result<(SplitMethod, Vec<ImportLineItem>), String> {
+    let mut seen = HashSet::new();
+    let mut shares = Vec::new();
+
+    for entry in value.split(';').filter(|entry| !entry.trim().is_empty()) {
+        let (reference, share) = entry
+            .rsplit_once('=')
+            .ok_or_else(|| format!("\"{}\" is not written as member=share", entry.trim()))?;
+
+        let member_id = resolve_member(members, reference)?;
+
+        if !seen.insert(member_id) {
+            return Err(format!("\"{}\" appears more than once", reference.trim()));
+        }
+
+        let share = share.trim();
+        let parsed = match share.strip_suffix('%') {
+            Some(percentage) => percentage
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .map(Share::Percentage),
+            None => parse_amount(share).ok().map(Share::Amount),
+        };
+
+        shares.push((
+            member_id,
+            parsed.ok_or_else(|| format!("\"{share}\" is not a share"))?,
+        ));
+    }
+
+    if shares.is_empty() {
+        return Ok((SplitMethod::Default, default_split(amount, members)));
+    }
+
+    if shares
+        .iter()
+        .all(|(_, share)| matches!(share, Share::Percentage(_)))
+    {
+        let percentages: Vec<(uuid::Uuid, f64)> = shares
+            .iter()
+            .filter_map(|(member_id, share)| match share {
+                Share::Percentage(percentage) => Some((*member_id, *percentage)),
+                Share::Amount(_) => None,
+            })
+            .collect();
+
+        let total: f64 = percentages.iter().map(|(_, percentage)| percentage).sum();
+        if (total - 100.0).abs() >= 0.01 {
+            return Err(format!("split percentages add up to {total}, not 100"));
+        }
+
+        return Ok((
+            SplitMethod::Percentage,
+            split_by_percentages(amount, &percentages),
+        ));
+    }
+
+    let line_items = shares
+        .into_iter()
+        .map(|(member_id, share)| match share {
+            Share::Amount(share) => Ok(ImportLineItem {
+                debtor_member_id: member_id,
+                amount: share,
+            }),
+            Share::Percentage(_) => Err("a split cannot mix percentages and amounts".to_string()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let total = round_cents(line_items.iter().map(|item| item.amount).sum());
+    if (total - amount).abs() >= 0.01 {
+        return Err(format!("split amounts add up to {total}, not {amount}"));
+    }
+
+    Ok((SplitMethod::Amount, line_items))
+}
+
+/// Reads a CSV export and validates every row against the pool's members
+/// and categories. Rows that fail are reported instead of stopping the
+/// import, so a dry run can list every problem at once.
+pub fn plan_import(
+    data: &str,
+    mapping: &ColumnMapping,
+    members: &[ImportMember],
+    custom_categories: &[PoolCategory],
+) -> Result<ImportPlan, ImportError> {
+    let data = data.trim_start_matches('\u{feff}');
+    let mut reader = csv_reader(data);
+
+    let headers: HashMap<String, usize> = reader
+        .headers()
+        .map_err(|e| ImportError::Csv(e.to_string()))?
+        .iter()
+        .enumerate()
+        .map(|(index, header)| (header.to_lowercase(), index))
+        .collect();
+
+    let column = |name: &str| {
+        headers
+            .get(&name.trim().to_lowercase())
+            .copied()
+            .ok_or_else(|| ImportError::MissingColumn(name.to_string()))
+    };
+
+    let date_column = column(&mapping.date)?;
+    let name_column = column(&mapping.name)?;
+    let amount_column = column(&mapping.amount)?;
+    let payer_column = column(&mapping.payer)?;
+    let category_column = mapping.category.as_deref().map(column).transpose()?;
+    let split_column = mapping.split.as_deref().map(column).transpose()?;
+
+    let date_format = mapping
+        .date_format
+        .as_deref()
+        .unwrap_or(DEFAULT_DATE_FORMAT);
+
+    let mut plan = ImportPlan::default();
+    let mut row_count = 0;
+
+    for record in reader.records() {
+        let record = record.map_err(|e| ImportError::Csv(e.to_string()))?;
+
+        if record.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+
+        row_count += 1;
+        if row_count > MAX_IMPORT_ROWS {
+            return Err(ImportError::TooManyRows);
+        }
+
+        let row = record_line(data, &record);
+
+        let field = |index: usize| record.get(index).unwrap_or_default();
+        let mut errors = Vec::new();
+        let mut fail = |column: &str, message: String| {
+            errors.push(ImportRowError {
+                row,
+                column: Some(column.to_string()),
+                message,
+            })
+        };
+
+        let incurred_on = NaiveDate::parse_from_str(field(date_column), date_format)
+            .map_err(|_| {
+                fail(
+                    &mapping.date,
+                    format!("\"{}\" is not a {date_format} date", field(date_column)),
+                )
+            })
+            .ok();
+
+        let name = field(name_column).to_string();
+        if name.is_empty() {
+            fail(&mapping.name, "name cannot be empty".to_string());
+        }
+
+        let amount = parse_amount(field(amount_column))
+            .map_err(|message| fail(&mapping.amount, message))
+            .ok();
+
+        let paid_by_member_id = resolve_member(members, field(payer_column))
+            .map_err(|message| fail(&mapping.payer, message))
+            .ok();
+
+        let category = match category_column.map(field).filter(|value| !value.is_empty()) {
+            Some(value) => resolve_category(custom_categories, value)
+                .map(Some)
+                .map_err(|message| fail(mapping.category.as_deref().unwrap_or_default(), message))
+                .ok(),
+            None => Some(None),
+        };
+
+        let split = amount.and_then(|amount| {
+            parse_split(split_column.map(field).unwrap_or_default(), amount, members)
+                .map_err(|message| fail(mapping.split.as_deref().unwrap_or_default(), message))
+                .ok()
+        });
+
+        match (incurred_on, amount, paid_by_member_id, category, split) {
+            (
+                Some(incurred_on),
+                Some(amount),
+                Some(paid_by_member_id),
+                Some(category),
+                Some((split_method, line_items)),
+            ) if errors.is_empty() => plan.rows.push(ImportRow {
+                row,
+                incurred_on,
+                name,
+                amount,
+                paid_by_member_id,
+                category,
+                split_method,
+                line_items,
+            }),
+            _ => plan.errors.append(&mut errors),
+        }
+    }
+
+    if row_count == 0 {
+        return Err(ImportError::NoRows);
+    }
+
+    Ok(plan)
+}
@@ -1,5 +1,24 @@
+pub mod analytics;
+pub mod auth;
+pub mod backup;
+pub mod budgets;
+pub mod categories;
+pub mod comments;
+pub mod export;
+pub mod import;
+pub mod mail;
 pub mod models;
+pub mod pagination;
+pub mod realtime;
+pub mod reminders;
+pub mod rules;
 pub mod schema;
+pub mod search;
+pub mod splitwise;
+pub mod storage;
+pub mod suggestions;
+pub mod tags;
+pub mod webhooks;
 use diesel::prelude::*;
 use dotenvy::dotenv;
 use petgraph::{Graph, algo::ford_fulkerson};
@@ -9,6 +28,7 @@ use std::{
 };
 
 #[cfg(test)]
+#[allow(clippy::module_inception, clippy::unnecessary_sort_by)]
 mod tests;
 
 pub fn establish_connection() -> PgConnection {
@@ -24,7 +44,7 @@ fn build_necessary_payments(
     pairwise_expenses: &HashMap<(uuid::Uuid, uuid::Uuid), f64>,
 ) -> HashMap<(uuid::Uuid, uuid::Uuid), f64> {
     unique_member_ids
-        .into_iter()
+        .iter()
         .fold(HashMap::new(), |mut acc, a| {
             for b in unique_member_ids {
                 let a_to_b = pairwise_expenses.get(&(*a, *b)).unwrap_or(&0.0);
@@ -96,7 +116,7 @@ fn debt_pairs_to_graph(
 ) {
     let (pairwise_expenses, unique_member_ids) =
         expenses
-            .into_iter()
+            .iter()
             .fold((HashMap::new(), HashSet::new()), |acc, expense| {
                 let (mut m, mut s) = acc;
 
@@ -131,7 +151,7 @@ fn debt_pairs_to_graph(
         graph.add_edge(from_node, to_node, *payment);
     }
 
-    return (graph, net_payers, net_receivers);
+    (graph, net_payers, net_receivers)
 }
 
 fn graph_to_payments(
@@ -140,31 +160,30 @@ fn graph_to_payments(
 ) -> Vec<models::Balance> {
     graph
         .edge_indices()
-        .into_iter()
         .map(|edge| {
             let (source_node_index, target_node_index) = graph.edge_endpoints(edge).unwrap();
             let source_member_id = graph[source_node_index];
             let target_member_id = graph[target_node_index];
             let amount = *graph.edge_weight(edge).unwrap();
 
-            return (amount, source_member_id, target_member_id);
+            (amount, source_member_id, target_member_id)
         })
         .filter(|(amount, source_member_id, target_member_id)| {
             *amount > 0.0 && (*source_member_id == member_id || *target_member_id == member_id)
         })
         .map(|(amount, source_member_id, target_member_id)| {
             if source_member_id == member_id {
-                return models::Balance {
+                models::Balance {
                     member_id: target_member_id,
                     amount,
                     direction: models::PaymentDirection::Outbound,
-                };
+                }
             } else {
-                return models::Balance {
+                models::Balance {
                     member_id: source_member_id,
                     amount,
                     direction: models::PaymentDirection::Inbound,
-                };
+                }
             }
         })
         .collect()
@@ -210,7 +229,7 @@ pub fn compute_balances_for_member(
                 }
 
                 if let Some(weight) = graph.edge_weight_mut(edge_index) {
-                    *weight = *weight - flow;
+                    *weight -= flow;
                 }
             }
         }
@@ -3,15 +3,31 @@ use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tower_http::cors::CorsLayer;
 
+mod commands;
 mod handlers;
+mod tasks;
 use handlers::{MaybeTracerProvider, handlers_routes, init_tracer_provider};
 
 #[tokio::main]
 async fn main() {
     let _ = dotenvy::dotenv();
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Err(e) = commands::run(&args) {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let tracer_provider = init_tracer_provider().expect("Failed to initialize tracer provider");
 
+    tokio::spawn(tasks::purge_deleted_expenses_task());
+    tokio::spawn(tasks::deliver_webhooks_task());
+    tokio::spawn(tasks::listen_for_pool_events_task());
+    tokio::spawn(tasks::send_emails_task());
+
     let (router, openapi) = handlers_routes().split_for_parts();
 
     let app = router
@@ -1,14 +1,25 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
 use diesel::sql_types::Uuid;
-use diesel::sql_types::{Double, Uuid as SqlUuid};
+use diesel::sql_types::{BigInt, Double, Float, Text, Uuid as SqlUuid};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use utoipa::ToSchema;
 
+use crate::analytics::TimeSeriesInterval;
+use crate::budgets::{BudgetStatus, budget_status, period_bounds};
+use crate::pagination::ExpenseCursor;
+use crate::realtime::PoolNotification;
 use crate::schema::{
-    expense, expense_category_rule, expense_line_item, friendship, member, member_password, pool, pool_membership
+    budget, email_digest, expense, expense_attachment, expense_category_rule, expense_comment,
+    expense_event, expense_line_item, expense_tag, friendship, member, member_password,
+    notification, notification_preference, payment_reminder, pool, pool_category,
+    pool_email_preference, pool_membership, tag, webhook, webhook_delivery,
 };
+use crate::search::ParsedSearch;
+
+diesel::define_sql_function!(fn lower(x: Text) -> Text);
 
 #[derive(
     diesel_derive_enum::DbEnum, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema,
@@ -48,7 +59,17 @@ pub enum SplitMethod {
 }
 
 #[derive(
-    diesel_derive_enum::DbEnum, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema, Hash,
+    diesel_derive_enum::DbEnum,
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    ToSchema,
+    Hash,
 )]
 #[db_enum(
     existing_type_path = "crate::schema::sql_types::ExpenseCategory",
@@ -82,6 +103,44 @@ pub enum ExpenseCategory {
     ProfessionalServices,
 }
 
+#[derive(
+    diesel_derive_enum::DbEnum, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema,
+)]
+#[db_enum(
+    existing_type_path = "crate::schema::sql_types::ExpenseEventAction",
+    value_style = "snake_case"
+)]
+pub enum ExpenseEventAction {
+    Created,
+    Updated,
+    Deleted,
+    Settled,
+    Restored,
+}
+
+#[derive(
+    diesel_derive_enum::DbEnum,
+    Debug,
+    Clone,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    ToSchema,
+)]
+#[db_enum(
+    existing_type_path = "crate::schema::sql_types::RuleMatchType",
+    value_style = "snake_case"
+)]
+pub enum RuleMatchType {
+    #[default]
+    Contains,
+    Exact,
+    StartsWith,
+    Regex,
+}
+
 #[derive(Debug, Queryable, Identifiable, Serialize, Deserialize, ToSchema)]
 #[diesel(table_name = member)]
 pub struct Member {
@@ -93,6 +152,10 @@ pub struct Member {
     pub updated_at: DateTime<Utc>,
     pub bio: Option<String>,
     pub venmo_handle: Option<String>,
+    pub is_placeholder: bool,
+    /// Set when the member deleted their account. Their personal details
+    /// have been cleared, but they stay in their pools' ledgers.
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Insertable, Deserialize, ToSchema)]
@@ -132,6 +195,29 @@ pub struct NewMemberPassword {
     pub password_hash: String,
 }
 
+/// Everything stored about a member, for the personal data export.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MemberDataExport {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub member: Member,
+    pub friendships: Vec<Friendship>,
+    pub pools: Vec<Pool>,
+    pub pool_memberships: Vec<PoolMembership>,
+    /// Expenses the member paid or has a share of, including ones in a
+    /// pool's trash.
+    pub expenses: Vec<ExpenseWithLineItems>,
+    pub comments: Vec<ExpenseComment>,
+    pub attachments: Vec<ExpenseAttachment>,
+    pub expense_events: Vec<ExpenseEvent>,
+    pub category_rules: Vec<ExpenseCategoryRule>,
+    pub budgets: Vec<Budget>,
+    pub notifications: Vec<Notification>,
+    pub notification_preferences: NotificationPreferences,
+    /// Only pools the member changed their email preferences for.
+    pub pool_email_preferences: Vec<PoolEmailPreferences>,
+}
+
 #[derive(Debug, Queryable, Identifiable, Serialize, Deserialize, ToSchema)]
 #[diesel(table_name = pool)]
 pub struct Pool {
@@ -179,7 +265,7 @@ pub struct NewPoolMembership {
     pub default_split_percentage: f64,
 }
 
-#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
 #[diesel(table_name = expense)]
 #[diesel(belongs_to(Pool))]
 #[diesel(belongs_to(Member, foreign_key = paid_by_member_id))]
@@ -197,6 +283,11 @@ pub struct Expense {
     pub notes: Option<String>,
     pub category: ExpenseCategory,
     pub split_method: SplitMethod,
+    pub incurred_on: NaiveDate,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub custom_category_id: Option<uuid::Uuid>,
+    /// Whether one of the payer's rules picked the category.
+    pub categorized_by_rule: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -213,6 +304,10 @@ pub struct ExpenseWithLineItems {
     pub notes: Option<String>,
     pub category: ExpenseCategory,
     pub split_method: SplitMethod,
+    pub incurred_on: NaiveDate,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub custom_category_id: Option<uuid::Uuid>,
+    pub categorized_by_rule: bool,
     pub line_items: Vec<ExpenseLineItem>,
 }
 
@@ -228,6 +323,9 @@ pub struct NewExpense {
     pub notes: Option<String>,
     pub category: ExpenseCategory,
     pub split_method: SplitMethod,
+    pub incurred_on: Option<NaiveDate>,
+    pub custom_category_id: Option<uuid::Uuid>,
+    pub categorized_by_rule: bool,
 }
 
 #[derive(Debug, AsChangeset, Deserialize, ToSchema)]
@@ -237,9 +335,12 @@ pub struct ExpenseChangeset {
     pub amount: Option<f64>,
     pub is_settled: Option<bool>,
     pub description: Option<String>,
-    pub notes: Option<String>,
+    pub notes: Option<Option<String>>,
     pub category: Option<ExpenseCategory>,
     pub split_method: Option<SplitMethod>,
+    pub incurred_on: Option<NaiveDate>,
+    pub custom_category_id: Option<Option<uuid::Uuid>>,
+    pub categorized_by_rule: Option<bool>,
 }
 
 #[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
@@ -272,7 +373,567 @@ pub struct ExpenseLineItemChangeset {
     pub amount: Option<f64>,
 }
 
-pub struct DummyMember(Member);
+#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = expense_attachment)]
+#[diesel(belongs_to(Pool))]
+#[diesel(belongs_to(Member, foreign_key = uploaded_by_member_id))]
+pub struct ExpenseAttachment {
+    pub id: uuid::Uuid,
+    pub expense_id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    pub uploaded_by_member_id: uuid::Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    pub inserted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = expense_attachment)]
+pub struct NewExpenseAttachment {
+    pub id: uuid::Uuid,
+    pub expense_id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    pub uploaded_by_member_id: uuid::Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+}
+
+#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = expense_comment)]
+#[diesel(belongs_to(Pool))]
+#[diesel(belongs_to(Member, foreign_key = author_member_id))]
+pub struct ExpenseComment {
+    pub id: uuid::Uuid,
+    pub expense_id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    pub author_member_id: uuid::Uuid,
+    pub body: String,
+    pub is_edited: bool,
+    pub is_deleted: bool,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub inserted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = expense_comment)]
+pub struct NewExpenseComment {
+    pub expense_id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    pub author_member_id: uuid::Uuid,
+    pub body: String,
+}
+
+#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = expense_event)]
+#[diesel(belongs_to(Pool))]
+#[diesel(belongs_to(Member, foreign_key = actor_member_id))]
+pub struct ExpenseEvent {
+    pub id: uuid::Uuid,
+    pub expense_id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    pub actor_member_id: uuid::Uuid,
+    pub action: ExpenseEventAction,
+    #[schema(value_type = Option<ExpenseWithLineItems>)]
+    pub before_snapshot: Option<serde_json::Value>,
+    #[schema(value_type = Option<ExpenseWithLineItems>)]
+    pub after_snapshot: Option<serde_json::Value>,
+    pub inserted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = expense_event)]
+pub struct NewExpenseEvent {
+    pub expense_id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    pub actor_member_id: uuid::Uuid,
+    pub action: ExpenseEventAction,
+    pub before_snapshot: Option<serde_json::Value>,
+    pub after_snapshot: Option<serde_json::Value>,
+}
+
+impl NewExpenseEvent {
+    /// Builds an event from the expense as it looked before and after the
+    /// change. At least one snapshot must be present; creations have no
+    /// `before` and deletions have no `after`.
+    pub fn new(
+        action: ExpenseEventAction,
+        actor_member_id: uuid::Uuid,
+        before: Option<&ExpenseWithLineItems>,
+        after: Option<&ExpenseWithLineItems>,
+    ) -> Self {
+        let subject = after
+            .or(before)
+            .expect("An expense event needs a before or after snapshot");
+
+        Self {
+            expense_id: subject.id,
+            pool_id: subject.pool_id,
+            actor_member_id,
+            action,
+            before_snapshot: before.map(|e| serde_json::to_value(e).expect("Expense serializes")),
+            after_snapshot: after.map(|e| serde_json::to_value(e).expect("Expense serializes")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = pool_category)]
+#[diesel(belongs_to(Pool))]
+pub struct PoolCategory {
+    pub id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    pub name: String,
+    pub color: String,
+    pub icon: Option<String>,
+    pub parent_category: Option<ExpenseCategory>,
+    pub inserted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = pool_category)]
+pub struct NewPoolCategory {
+    pub pool_id: uuid::Uuid,
+    pub name: String,
+    pub color: String,
+    pub icon: Option<String>,
+    pub parent_category: Option<ExpenseCategory>,
+}
+
+#[derive(Debug, AsChangeset)]
+#[diesel(table_name = pool_category)]
+pub struct PoolCategoryChangeset {
+    pub name: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<Option<String>>,
+    pub parent_category: Option<Option<ExpenseCategory>>,
+}
+
+#[derive(Debug, Clone, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = tag)]
+#[diesel(belongs_to(Pool))]
+pub struct Tag {
+    pub id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    pub name: String,
+    pub inserted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = tag)]
+pub struct NewTag {
+    pub pool_id: uuid::Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = expense_tag)]
+pub struct NewExpenseTag {
+    pub expense_id: uuid::Uuid,
+    pub tag_id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+}
+
+#[derive(QueryableByName, Debug, Clone, Serialize, ToSchema)]
+pub struct TagUsage {
+    #[diesel(sql_type = SqlUuid)]
+    pub id: uuid::Uuid,
+
+    #[diesel(sql_type = Text)]
+    pub name: String,
+
+    /// How many live expenses carry the tag.
+    #[diesel(sql_type = BigInt)]
+    pub expense_count: i64,
+}
+
+#[derive(QueryableByName, Debug, Clone, Serialize, ToSchema)]
+pub struct TagTotal {
+    #[diesel(sql_type = SqlUuid)]
+    pub tag_id: uuid::Uuid,
+
+    #[diesel(sql_type = Text)]
+    pub name: String,
+
+    #[diesel(sql_type = BigInt)]
+    pub expense_count: i64,
+
+    /// The full amount of the tagged expenses.
+    #[diesel(sql_type = Double)]
+    pub total_amount: f64,
+
+    /// The member's share of the tagged expenses.
+    #[diesel(sql_type = Double)]
+    pub member_share: f64,
+}
+
+#[derive(
+    diesel_derive_enum::DbEnum,
+    Debug,
+    Clone,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    ToSchema,
+)]
+#[db_enum(
+    existing_type_path = "crate::schema::sql_types::BudgetPeriod",
+    value_style = "snake_case"
+)]
+pub enum BudgetPeriod {
+    Weekly,
+    #[default]
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = budget)]
+#[diesel(belongs_to(Pool))]
+pub struct Budget {
+    pub id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    /// Unset for a budget covering all of the pool's spending.
+    pub category: Option<ExpenseCategory>,
+    pub amount: f64,
+    pub period: BudgetPeriod,
+    pub created_by_member_id: uuid::Uuid,
+    pub inserted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = budget)]
+pub struct NewBudget {
+    pub pool_id: uuid::Uuid,
+    pub category: Option<ExpenseCategory>,
+    pub amount: f64,
+    pub period: BudgetPeriod,
+    pub created_by_member_id: uuid::Uuid,
+}
+
+#[derive(Debug, AsChangeset)]
+#[diesel(table_name = budget)]
+pub struct BudgetChangeset {
+    pub amount: Option<f64>,
+}
+
+#[derive(
+    diesel_derive_enum::DbEnum,
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    ToSchema,
+)]
+#[db_enum(existing_type_path = "crate::schema::sql_types::WebhookEvent")]
+pub enum WebhookEvent {
+    #[serde(rename = "expense.created")]
+    #[db_enum(rename = "expense.created")]
+    ExpenseCreated,
+    #[serde(rename = "expense.updated")]
+    #[db_enum(rename = "expense.updated")]
+    ExpenseUpdated,
+    #[serde(rename = "expense.deleted")]
+    #[db_enum(rename = "expense.deleted")]
+    ExpenseDeleted,
+    #[serde(rename = "expense.restored")]
+    #[db_enum(rename = "expense.restored")]
+    ExpenseRestored,
+    #[serde(rename = "settlement.recorded")]
+    #[db_enum(rename = "settlement.recorded")]
+    SettlementRecorded,
+    #[serde(rename = "member.joined")]
+    #[db_enum(rename = "member.joined")]
+    MemberJoined,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::ExpenseCreated => "expense.created",
+            WebhookEvent::ExpenseUpdated => "expense.updated",
+            WebhookEvent::ExpenseDeleted => "expense.deleted",
+            WebhookEvent::ExpenseRestored => "expense.restored",
+            WebhookEvent::SettlementRecorded => "settlement.recorded",
+            WebhookEvent::MemberJoined => "member.joined",
+        }
+    }
+
+    /// The event a change to an expense is announced as. Settle-ups are
+    /// announced once for the whole settle-up rather than per expense.
+    pub fn for_expense_action(action: &ExpenseEventAction) -> Option<Self> {
+        match action {
+            ExpenseEventAction::Created => Some(WebhookEvent::ExpenseCreated),
+            ExpenseEventAction::Updated => Some(WebhookEvent::ExpenseUpdated),
+            ExpenseEventAction::Deleted => Some(WebhookEvent::ExpenseDeleted),
+            ExpenseEventAction::Restored => Some(WebhookEvent::ExpenseRestored),
+            ExpenseEventAction::Settled => None,
+        }
+    }
+}
+
+/// Postgres arrays can hold NULLs, so diesel reads `webhook_event[]` as a
+/// list of options. The column has no NULLs, so they're dropped on read.
+#[derive(Debug, diesel::deserialize::FromSqlRow)]
+pub struct WebhookEventList(Vec<Option<WebhookEvent>>);
+
+impl
+    diesel::deserialize::FromSql<
+        diesel::sql_types::Array<
+            diesel::sql_types::Nullable<crate::schema::sql_types::WebhookEvent>,
+        >,
+        diesel::pg::Pg,
+    > for WebhookEventList
+{
+    fn from_sql(bytes: diesel::pg::PgValue<'_>) -> diesel::deserialize::Result<Self> {
+        Ok(WebhookEventList(
+            <Vec<Option<WebhookEvent>> as diesel::deserialize::FromSql<
+                diesel::sql_types::Array<
+                    diesel::sql_types::Nullable<crate::schema::sql_types::WebhookEvent>,
+                >,
+                diesel::pg::Pg,
+            >>::from_sql(bytes)?,
+        ))
+    }
+}
+
+impl From<WebhookEventList> for Vec<WebhookEvent> {
+    fn from(list: WebhookEventList) -> Self {
+        list.0.into_iter().flatten().collect()
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Identifiable, Associations, Serialize, ToSchema)]
+#[diesel(table_name = webhook)]
+#[diesel(belongs_to(Pool))]
+pub struct Webhook {
+    pub id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    pub created_by_member_id: uuid::Uuid,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    #[diesel(deserialize_as = WebhookEventList)]
+    pub events: Vec<WebhookEvent>,
+    pub is_active: bool,
+    pub inserted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = webhook)]
+pub struct NewWebhook {
+    pub pool_id: uuid::Uuid,
+    pub created_by_member_id: uuid::Uuid,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Debug, AsChangeset)]
+#[diesel(table_name = webhook)]
+pub struct WebhookChangeset {
+    pub url: Option<String>,
+    pub events: Option<Vec<WebhookEvent>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(
+    diesel_derive_enum::DbEnum, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema,
+)]
+#[db_enum(
+    existing_type_path = "crate::schema::sql_types::WebhookDeliveryStatus",
+    value_style = "snake_case"
+)]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first attempt or a retry.
+    Pending,
+    Delivered,
+    /// Gave up after too many failed attempts.
+    Failed,
+}
+
+#[derive(Debug, Clone, Queryable, Identifiable, Associations, Serialize, ToSchema)]
+#[diesel(table_name = webhook_delivery)]
+#[diesel(belongs_to(Webhook))]
+pub struct WebhookDelivery {
+    pub id: uuid::Uuid,
+    pub webhook_id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    pub event: WebhookEvent,
+    /// The exact body that is sent, and that the signature covers.
+    pub payload: serde_json::Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub inserted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = webhook_delivery)]
+pub struct NewWebhookDelivery {
+    pub id: uuid::Uuid,
+    pub webhook_id: uuid::Uuid,
+    pub pool_id: uuid::Uuid,
+    pub event: WebhookEvent,
+    pub payload: serde_json::Value,
+}
+
+#[derive(
+    diesel_derive_enum::DbEnum, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema,
+)]
+#[db_enum(
+    existing_type_path = "crate::schema::sql_types::NotificationKind",
+    value_style = "snake_case"
+)]
+pub enum NotificationKind {
+    FriendRequest,
+    /// Someone added the member to a pool.
+    PoolInvitation,
+    /// Someone added an expense the member paid for or has a share of.
+    ExpenseAdded,
+    Settlement,
+}
+
+#[derive(Debug, Clone, Queryable, Identifiable, Associations, Serialize, ToSchema)]
+#[diesel(table_name = notification)]
+#[diesel(belongs_to(Member))]
+pub struct Notification {
+    pub id: uuid::Uuid,
+    pub member_id: uuid::Uuid,
+    pub kind: NotificationKind,
+    pub actor_member_id: Option<uuid::Uuid>,
+    pub pool_id: Option<uuid::Uuid>,
+    pub expense_id: Option<uuid::Uuid>,
+    /// Details for showing the notification, like the pool's name or the
+    /// member's share of an expense, as they were when it was sent.
+    pub data: serde_json::Value,
+    pub read_at: Option<DateTime<Utc>>,
+    pub inserted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = notification)]
+pub struct NewNotification {
+    pub member_id: uuid::Uuid,
+    pub kind: NotificationKind,
+    pub actor_member_id: Option<uuid::Uuid>,
+    pub pool_id: Option<uuid::Uuid>,
+    pub expense_id: Option<uuid::Uuid>,
+    pub data: serde_json::Value,
+}
+
+/// Which kinds of notification a member gets. Members who've never changed
+/// them get everything.
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = notification_preference)]
+pub struct NotificationPreferences {
+    pub friend_requests: bool,
+    pub pool_invitations: bool,
+    pub expenses: bool,
+    pub settlements: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            friend_requests: true,
+            pool_invitations: true,
+            expenses: true,
+            settlements: true,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    pub fn allows(&self, kind: &NotificationKind) -> bool {
+        match kind {
+            NotificationKind::FriendRequest => self.friend_requests,
+            NotificationKind::PoolInvitation => self.pool_invitations,
+            NotificationKind::ExpenseAdded => self.expenses,
+            NotificationKind::Settlement => self.settlements,
+        }
+    }
+}
+
+#[derive(Debug, AsChangeset, Insertable, Deserialize, ToSchema)]
+#[diesel(table_name = notification_preference)]
+pub struct NotificationPreferencesChangeset {
+    pub friend_requests: Option<bool>,
+    pub pool_invitations: Option<bool>,
+    pub expenses: Option<bool>,
+    pub settlements: Option<bool>,
+}
+
+/// Which emails a member gets about a pool. Members who've never changed
+/// them get both.
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = pool_email_preference)]
+pub struct PoolEmailPreferences {
+    pub pool_id: uuid::Uuid,
+    pub payment_reminders: bool,
+    pub weekly_digest: bool,
+}
+
+#[derive(Debug, AsChangeset, Insertable, Deserialize, ToSchema)]
+#[diesel(table_name = pool_email_preference)]
+pub struct PoolEmailPreferencesChangeset {
+    pub payment_reminders: Option<bool>,
+    pub weekly_digest: Option<bool>,
+}
+
+/// A member who's owed more than the reminder threshold in a pool since
+/// `owing_since`.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = payment_reminder)]
+pub struct PaymentReminder {
+    pub pool_id: uuid::Uuid,
+    pub member_id: uuid::Uuid,
+    pub owing_since: DateTime<Utc>,
+    pub last_sent_at: Option<DateTime<Utc>>,
+}
+
+pub struct EmailDigest;
+
+/// What happened in one of a member's pools, for their weekly digest.
+#[derive(Debug, Clone, QueryableByName)]
+pub struct PoolActivity {
+    #[diesel(sql_type = SqlUuid)]
+    pub pool_id: uuid::Uuid,
+    #[diesel(sql_type = Text)]
+    pub pool_name: String,
+    #[diesel(sql_type = BigInt)]
+    pub expenses_added: i64,
+    #[diesel(sql_type = Double)]
+    pub amount_added: f64,
+    #[diesel(sql_type = BigInt)]
+    pub expenses_settled: i64,
+}
+
+// Only names the friend side of `Friendship`'s second association.
+pub struct DummyMember(#[allow(dead_code)] Member);
 
 #[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
 #[diesel(table_name = friendship)]
@@ -301,23 +962,42 @@ pub struct FriendshipChangeset {
     pub status: Option<FriendshipStatus>,
 }
 
-#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Queryable, Identifiable, Associations, Serialize, Deserialize, ToSchema)]
 #[diesel(table_name = expense_category_rule)]
 #[diesel(belongs_to(Member, foreign_key = member_id))]
-#[diesel(primary_key(member_id, rule, category))]
 pub struct ExpenseCategoryRule {
     pub member_id: uuid::Uuid,
     pub rule: String,
     pub category: ExpenseCategory,
     pub inserted_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub id: uuid::Uuid,
+    pub match_type: RuleMatchType,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    pub pool_id: Option<uuid::Uuid>,
+    pub priority: i32,
+    pub custom_category_id: Option<uuid::Uuid>,
 }
 
-#[derive(Debug, Insertable, Deserialize, ToSchema)]
+#[derive(Debug, Insertable, Serialize, Deserialize, ToSchema)]
 #[diesel(table_name = expense_category_rule)]
 pub struct NewExpenseCategoryRule {
     pub rule: String,
     pub category: ExpenseCategory,
+    #[serde(default)]
+    pub match_type: RuleMatchType,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    /// Limits the rule to expenses in this pool. Rules without a pool apply
+    /// everywhere.
+    pub pool_id: Option<uuid::Uuid>,
+    /// Higher priorities win when several rules match.
+    #[serde(default)]
+    pub priority: i32,
+    /// A custom category of the rule's pool. `category` is then taken from
+    /// the custom category's parent.
+    pub custom_category_id: Option<uuid::Uuid>,
 }
 
 impl Member {
@@ -345,38 +1025,203 @@ impl Member {
         member::table.find(id).get_result(conn)
     }
 
-    pub fn authenticate(
-        conn: &mut PgConnection,
-        email: &str,
-        password_hash: &str,
-    ) -> QueryResult<(uuid::Uuid, bool)> {
-        let result: QueryResult<(uuid::Uuid, String)> = member::table
-            .inner_join(member_password::table.on(member::id.eq(member_password::member_id)))
-            .filter(member::email.eq(email))
-            .select((member::id, member_password::password_hash))
-            .first(conn);
-
-        match result {
-            Ok((id, stored_hash)) => Ok((id, stored_hash == password_hash)),
-            Err(e) => Err(e),
-        }
+    pub fn find_many(conn: &mut PgConnection, ids: &[uuid::Uuid]) -> QueryResult<Vec<Self>> {
+        member::table
+            .filter(member::id.eq_any(ids))
+            .get_results(conn)
     }
-}
 
-impl ExpenseCategoryRule {
-    pub fn create(
+    /// Creates a member without a password for someone an import refers to
+    /// who doesn't have an account. The id is chosen by the caller so that
+    /// import previews can refer to placeholders before they exist.
+    pub fn create_placeholder(
         conn: &mut PgConnection,
-        member_id: &uuid::Uuid,
-        rule: &NewExpenseCategoryRule,
+        id: uuid::Uuid,
+        first_name: &str,
+        last_name: &str,
     ) -> QueryResult<Self> {
-        diesel::insert_into(expense_category_rule::table)
-            .values(
-                (
-                    expense_category_rule::member_id.eq(&member_id),
-                    expense_category_rule::rule.eq(&rule.rule),
-                    expense_category_rule::category.eq(&rule.category),
-                )
+        diesel::insert_into(member::table)
+            .values((
+                member::id.eq(id),
+                member::first_name.eq(first_name),
+                member::last_name.eq(last_name),
+                member::email.eq(format!("placeholder+{id}@medici.invalid")),
+                member::is_placeholder.eq(true),
+            ))
+            .get_result(conn)
+    }
+
+    pub fn is_deleted(conn: &mut PgConnection, id: uuid::Uuid) -> QueryResult<bool> {
+        member::table
+            .find(id)
+            .select(member::deleted_at.is_not_null())
+            .get_result(conn)
+    }
+
+    /// Gathers everything stored about a member. Expenses they have a share
+    /// of come with every line item, since a share only makes sense next to
+    /// the rest of the split.
+    pub fn export_data(conn: &mut PgConnection, id: uuid::Uuid) -> QueryResult<MemberDataExport> {
+        let member = Self::find(conn, id)?;
+
+        let friendships = friendship::table
+            .filter(
+                friendship::inviting_member_id
+                    .eq(id)
+                    .or(friendship::friend_member_id.eq(id)),
+            )
+            .order_by(friendship::inserted_at.asc())
+            .get_results(conn)?;
+
+        let pool_memberships = pool_membership::table
+            .filter(pool_membership::member_id.eq(id))
+            .order_by(pool_membership::inserted_at.asc())
+            .get_results(conn)?;
+
+        let shared_expense_ids = expense_line_item::table
+            .filter(expense_line_item::debtor_member_id.eq(id))
+            .select(expense_line_item::expense_id);
+
+        let expense_ids: Vec<uuid::Uuid> = expense::table
+            .filter(
+                expense::paid_by_member_id
+                    .eq(id)
+                    .or(expense::id.eq_any(shared_expense_ids)),
+            )
+            .select(expense::id)
+            .get_results(conn)?;
+
+        Ok(MemberDataExport {
+            version: crate::export::MEMBER_EXPORT_VERSION,
+            exported_at: Utc::now(),
+            member,
+            friendships,
+            pools: Pool::find_by_member_id(conn, id)?,
+            pool_memberships,
+            expenses: Expense::snapshots(conn, &expense_ids)?,
+            comments: expense_comment::table
+                .filter(expense_comment::author_member_id.eq(id))
+                .order_by(expense_comment::inserted_at.asc())
+                .get_results(conn)?,
+            attachments: expense_attachment::table
+                .filter(expense_attachment::uploaded_by_member_id.eq(id))
+                .order_by(expense_attachment::inserted_at.asc())
+                .get_results(conn)?,
+            expense_events: expense_event::table
+                .filter(expense_event::actor_member_id.eq(id))
+                .order_by((expense_event::inserted_at.asc(), expense_event::id.asc()))
+                .get_results(conn)?,
+            category_rules: ExpenseCategoryRule::find_for_member(conn, id)?,
+            budgets: budget::table
+                .filter(budget::created_by_member_id.eq(id))
+                .order_by(budget::inserted_at.asc())
+                .get_results(conn)?,
+            notifications: notification::table
+                .filter(notification::member_id.eq(id))
+                .order_by(notification::inserted_at.asc())
+                .get_results(conn)?,
+            notification_preferences: NotificationPreferences::for_member(conn, id)?,
+            pool_email_preferences: PoolEmailPreferences::list_for_member(conn, id)?,
+        })
+    }
+
+    /// Deletes a member's account without deleting what other members rely
+    /// on. Their name, email, bio and Venmo handle are cleared and they can
+    /// no longer log in, but their expenses, shares and pool memberships
+    /// stay so every pool's balances and default splits still add up. Their
+    /// friendships, category rules, notifications and email settings only
+    /// matter to them, so those go, along with any friend requests they'd sent.
+    pub fn anonymize(conn: &mut PgConnection, id: uuid::Uuid) -> QueryResult<Self> {
+        conn.transaction(|conn| {
+            diesel::delete(member_password::table.filter(member_password::member_id.eq(id)))
+                .execute(conn)?;
+
+            diesel::delete(
+                friendship::table.filter(
+                    friendship::inviting_member_id
+                        .eq(id)
+                        .or(friendship::friend_member_id.eq(id)),
+                ),
+            )
+            .execute(conn)?;
+
+            diesel::delete(
+                expense_category_rule::table.filter(expense_category_rule::member_id.eq(id)),
             )
+            .execute(conn)?;
+
+            diesel::delete(
+                notification::table.filter(
+                    notification::member_id
+                        .eq(id)
+                        .or(notification::actor_member_id
+                            .eq(id)
+                            .and(notification::kind.eq(NotificationKind::FriendRequest))),
+                ),
+            )
+            .execute(conn)?;
+
+            diesel::delete(notification_preference::table.find(id)).execute(conn)?;
+
+            diesel::delete(
+                pool_email_preference::table.filter(pool_email_preference::member_id.eq(id)),
+            )
+            .execute(conn)?;
+
+            diesel::delete(payment_reminder::table.filter(payment_reminder::member_id.eq(id)))
+                .execute(conn)?;
+
+            diesel::delete(email_digest::table.find(id)).execute(conn)?;
+
+            diesel::update(member::table.find(id))
+                .set((
+                    member::first_name.eq("Deleted"),
+                    member::last_name.eq("member"),
+                    member::email.eq(format!("deleted+{id}@medici.invalid")),
+                    member::bio.eq(None::<String>),
+                    member::venmo_handle.eq(None::<String>),
+                    member::deleted_at.eq(Some(Utc::now())),
+                ))
+                .get_result(conn)
+        })
+    }
+
+    pub fn authenticate(
+        conn: &mut PgConnection,
+        email: &str,
+        password_hash: &str,
+    ) -> QueryResult<(uuid::Uuid, bool)> {
+        let result: QueryResult<(uuid::Uuid, String)> = member::table
+            .inner_join(member_password::table.on(member::id.eq(member_password::member_id)))
+            .filter(member::email.eq(email))
+            .select((member::id, member_password::password_hash))
+            .first(conn);
+
+        match result {
+            Ok((id, stored_hash)) => Ok((id, stored_hash == password_hash)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl ExpenseCategoryRule {
+    pub fn create(
+        conn: &mut PgConnection,
+        member_id: &uuid::Uuid,
+        rule: &NewExpenseCategoryRule,
+    ) -> QueryResult<Self> {
+        diesel::insert_into(expense_category_rule::table)
+            .values((
+                expense_category_rule::member_id.eq(&member_id),
+                expense_category_rule::rule.eq(&rule.rule),
+                expense_category_rule::category.eq(&rule.category),
+                expense_category_rule::match_type.eq(&rule.match_type),
+                expense_category_rule::min_amount.eq(&rule.min_amount),
+                expense_category_rule::max_amount.eq(&rule.max_amount),
+                expense_category_rule::pool_id.eq(&rule.pool_id),
+                expense_category_rule::priority.eq(&rule.priority),
+                expense_category_rule::custom_category_id.eq(&rule.custom_category_id),
+            ))
             .get_result(conn)
     }
 
@@ -386,6 +1231,10 @@ impl ExpenseCategoryRule {
     ) -> QueryResult<Vec<Self>> {
         expense_category_rule::table
             .filter(expense_category_rule::member_id.eq(member_id))
+            .order_by((
+                expense_category_rule::priority.desc(),
+                expense_category_rule::inserted_at.asc(),
+            ))
             .get_results(conn)
     }
 
@@ -405,6 +1254,19 @@ impl ExpenseCategoryRule {
         )
         .execute(conn)
     }
+
+    pub fn delete_by_id(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+        rule_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        diesel::delete(
+            expense_category_rule::table
+                .filter(expense_category_rule::id.eq(rule_id))
+                .filter(expense_category_rule::member_id.eq(member_id)),
+        )
+        .get_result(conn)
+    }
 }
 
 impl MemberPassword {
@@ -413,6 +1275,19 @@ impl MemberPassword {
             .values(new_password)
             .get_result(conn)
     }
+
+    pub fn matches(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+        password_hash: &str,
+    ) -> QueryResult<bool> {
+        diesel::select(diesel::dsl::exists(
+            member_password::table
+                .filter(member_password::member_id.eq(member_id))
+                .filter(member_password::password_hash.eq(password_hash)),
+        ))
+        .get_result(conn)
+    }
 }
 
 impl Pool {
@@ -422,6 +1297,10 @@ impl Pool {
             .get_result(conn)
     }
 
+    pub fn find(conn: &mut PgConnection, id: uuid::Uuid) -> QueryResult<Self> {
+        pool::table.find(id).get_result(conn)
+    }
+
     pub fn find_by_member_id(
         conn: &mut PgConnection,
         member_id: uuid::Uuid,
@@ -453,6 +1332,7 @@ impl Pool {
             )
             .filter(expense_line_item::debtor_member_id.eq(member_id))
             .filter(expense::pool_id.eq(pool_id))
+            .filter(expense::deleted_at.is_null())
             .select((
                 expense::paid_by_member_id,
                 expense_line_item::amount,
@@ -463,7 +1343,7 @@ impl Pool {
         let mut total_debt = f64::from(0);
         for (paid_by_id, line_amount, expense_amount) in debts {
             if paid_by_id == member_id {
-                total_debt += &line_amount - &expense_amount;
+                total_debt += line_amount - expense_amount;
             } else {
                 total_debt += line_amount;
             }
@@ -478,9 +1358,23 @@ impl Pool {
         conn: &mut PgConnection,
         pool_id: uuid::Uuid,
         confirming_member_id: uuid::Uuid,
-    ) -> () {
-        let _ = diesel::sql_query(
-            "
+    ) -> QueryResult<usize> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            if !PoolMembership::is_member(conn, pool_id, confirming_member_id)? {
+                return Ok(0);
+            }
+
+            let expense_ids: Vec<uuid::Uuid> = expense::table
+                .filter(expense::pool_id.eq(pool_id))
+                .filter(expense::is_settled.eq(false))
+                .filter(expense::deleted_at.is_null())
+                .select(expense::id)
+                .get_results(conn)?;
+
+            let before = Expense::snapshots(conn, &expense_ids)?;
+
+            diesel::sql_query(
+                "
             WITH expense_ids AS (
                 SELECT e.id
                 FROM expense e
@@ -490,6 +1384,7 @@ impl Pool {
                     pm.pool_id = $1
                     AND pm.member_id = $2
                     AND e.is_settled = false
+                    AND e.deleted_at IS NULL
             ), line_items AS (
                 UPDATE expense_line_item
                 SET is_settled = true
@@ -501,10 +1396,86 @@ impl Pool {
             WHERE id IN (SELECT id FROM expense_ids)
             ;
             ",
-        )
-        .bind::<Uuid, _>(pool_id)
-        .bind::<Uuid, _>(confirming_member_id)
-        .execute(conn);
+            )
+            .bind::<Uuid, _>(pool_id)
+            .bind::<Uuid, _>(confirming_member_id)
+            .execute(conn)?;
+
+            let after = Expense::snapshots(conn, &expense_ids)?;
+
+            for (before, after) in before.iter().zip(after.iter()) {
+                ExpenseEvent::record(
+                    conn,
+                    &NewExpenseEvent::new(
+                        ExpenseEventAction::Settled,
+                        confirming_member_id,
+                        Some(before),
+                        Some(after),
+                    ),
+                )?;
+            }
+
+            if !expense_ids.is_empty() {
+                let pool_name: String = pool::table.find(pool_id).select(pool::name).first(conn)?;
+                let mut involved: Vec<uuid::Uuid> = Vec::new();
+
+                for expense in &after {
+                    let member_ids = std::iter::once(expense.paid_by_member_id).chain(
+                        expense
+                            .line_items
+                            .iter()
+                            .map(|line_item| line_item.debtor_member_id),
+                    );
+
+                    for member_id in member_ids {
+                        if !involved.contains(&member_id) {
+                            involved.push(member_id);
+                        }
+                    }
+                }
+
+                Notification::send(
+                    conn,
+                    involved
+                        .into_iter()
+                        .map(|member_id| NewNotification {
+                            member_id,
+                            kind: NotificationKind::Settlement,
+                            actor_member_id: Some(confirming_member_id),
+                            pool_id: Some(pool_id),
+                            expense_id: None,
+                            data: serde_json::json!({
+                                "pool_name": pool_name,
+                                "expense_count": expense_ids.len(),
+                            }),
+                        })
+                        .collect(),
+                )?;
+
+                crate::realtime::notify(
+                    conn,
+                    &PoolNotification {
+                        pool_id,
+                        actor_member_id: confirming_member_id,
+                        action: ExpenseEventAction::Settled,
+                        expense_id: None,
+                    },
+                )?;
+
+                WebhookDelivery::enqueue(
+                    conn,
+                    pool_id,
+                    WebhookEvent::SettlementRecorded,
+                    serde_json::json!({
+                        "settled_by_member_id": confirming_member_id,
+                        "expense_ids": expense_ids,
+                        "expenses": after,
+                    }),
+                )?;
+            }
+
+            Ok(expense_ids.len())
+        })
     }
 }
 
@@ -519,9 +1490,36 @@ impl PoolMembership {
         conn: &mut PgConnection,
         new_membership: &NewPoolMembership,
     ) -> QueryResult<Self> {
-        diesel::insert_into(pool_membership::table)
-            .values(new_membership)
-            .get_result(conn)
+        conn.transaction(|conn| {
+            let membership: Self = diesel::insert_into(pool_membership::table)
+                .values(new_membership)
+                .get_result(conn)?;
+
+            let (first_name, last_name, is_placeholder) = member::table
+                .find(membership.member_id)
+                .select((
+                    member::first_name,
+                    member::last_name,
+                    member::is_placeholder,
+                ))
+                .first::<(String, String, bool)>(conn)?;
+
+            WebhookDelivery::enqueue(
+                conn,
+                membership.pool_id,
+                WebhookEvent::MemberJoined,
+                serde_json::json!({
+                    "member_id": membership.member_id,
+                    "first_name": first_name,
+                    "last_name": last_name,
+                    "is_placeholder": is_placeholder,
+                    "role": membership.role,
+                    "default_split_percentage": membership.default_split_percentage,
+                }),
+            )?;
+
+            Ok(membership)
+        })
     }
 
     pub fn add_member(
@@ -530,8 +1528,8 @@ impl PoolMembership {
         member_id: uuid::Uuid,
     ) -> QueryResult<Self> {
         let new_membership = NewPoolMembership {
-            pool_id: pool_id,
-            member_id: member_id,
+            pool_id,
+            member_id,
             role: PoolRole::PARTICIPANT,
             default_split_percentage: 0.0,
         };
@@ -583,10 +1581,10 @@ impl PoolMembership {
 
             let num_updated = Self::update_default_split_percentage(conn, pool_id, inputs);
 
-            return Ok(num_updated.unwrap_or(0));
+            Ok(num_updated.unwrap_or(0))
         });
 
-        return Ok(num_updated.unwrap_or(0));
+        Ok(num_updated.unwrap_or(0))
     }
 
     pub fn update_default_split_percentage(
@@ -619,7 +1617,20 @@ impl PoolMembership {
             Ok(())
         })?;
 
-        return Ok(1);
+        Ok(1)
+    }
+
+    pub fn is_member(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        member_id: uuid::Uuid,
+    ) -> QueryResult<bool> {
+        diesel::select(diesel::dsl::exists(
+            pool_membership::table
+                .filter(pool_membership::pool_id.eq(pool_id))
+                .filter(pool_membership::member_id.eq(member_id)),
+        ))
+        .get_result(conn)
     }
 
     pub fn list(conn: &mut PgConnection, pool_id: uuid::Uuid) -> QueryResult<Vec<(Self, Member)>> {
@@ -636,6 +1647,80 @@ pub struct ExpenseForBalanceCalculation {
     pub amount: f64,
 }
 
+#[derive(QueryableByName, Debug, Clone, Serialize, ToSchema)]
+pub struct CategoryTotal {
+    #[diesel(sql_type = crate::schema::sql_types::ExpenseCategory)]
+    pub category: ExpenseCategory,
+
+    #[diesel(sql_type = BigInt)]
+    pub expense_count: i64,
+
+    /// The full amount of the expenses the member paid for.
+    #[diesel(sql_type = Double)]
+    pub paid: f64,
+
+    /// The member's share of the expenses, whoever paid.
+    #[diesel(sql_type = Double)]
+    pub share: f64,
+}
+
+#[derive(QueryableByName, Debug, Clone)]
+pub struct TimeSeriesRow {
+    #[diesel(sql_type = diesel::sql_types::Date)]
+    pub bucket: NaiveDate,
+
+    #[diesel(sql_type = diesel::sql_types::Nullable<crate::schema::sql_types::ExpenseCategory>)]
+    pub category: Option<ExpenseCategory>,
+
+    /// Unset on the row holding the bucket's total.
+    #[diesel(sql_type = diesel::sql_types::Nullable<SqlUuid>)]
+    pub member_id: Option<uuid::Uuid>,
+
+    #[diesel(sql_type = Double)]
+    pub amount: f64,
+}
+
+/// How many times expenses with the same name were given a category, for
+/// learning category suggestions from.
+#[derive(QueryableByName, Debug, Clone, PartialEq)]
+pub struct CategoryNameCount {
+    #[diesel(sql_type = Text)]
+    pub name: String,
+
+    #[diesel(sql_type = crate::schema::sql_types::ExpenseCategory)]
+    pub category: ExpenseCategory,
+
+    /// Whether the member paid for these expenses themselves.
+    #[diesel(sql_type = diesel::sql_types::Bool)]
+    pub is_own: bool,
+
+    #[diesel(sql_type = BigInt)]
+    pub occurrences: i64,
+}
+
+#[derive(QueryableByName)]
+struct Exists {
+    #[diesel(sql_type = diesel::sql_types::Bool)]
+    exists: bool,
+}
+
+/// Whether Postgres knows the IANA time zone, like `America/New_York`.
+pub fn is_valid_timezone(conn: &mut PgConnection, timezone: &str) -> QueryResult<bool> {
+    diesel::sql_query("SELECT EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = $1) AS exists")
+        .bind::<Text, _>(timezone)
+        .get_result::<Exists>(conn)
+        .map(|row| row.exists)
+}
+
+#[derive(QueryableByName)]
+struct SearchHit {
+    #[diesel(sql_type = SqlUuid)]
+    id: uuid::Uuid,
+
+    #[diesel(sql_type = Float)]
+    rank: f32,
+}
+
 #[derive(QueryableByName, Debug, Clone)]
 pub struct DebtPair {
     #[diesel(sql_type = SqlUuid)]
@@ -668,11 +1753,207 @@ impl Expense {
                     .eq(expense_id)
                     .and(expense::pool_id.eq(pool_id))
                     .and(expense::paid_by_member_id.eq(member_id))
-                    .and(expense::is_settled.eq(is_settled)),
+                    .and(expense::is_settled.eq(is_settled))
+                    .and(expense::deleted_at.is_null()),
             )
             .get_result(conn)
     }
 
+    pub fn find_in_pool(
+        conn: &mut PgConnection,
+        expense_id: uuid::Uuid,
+        pool_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        expense::table
+            .filter(expense::id.eq(expense_id))
+            .filter(expense::pool_id.eq(pool_id))
+            .filter(expense::deleted_at.is_null())
+            .first(conn)
+    }
+
+    /// Moves an unsettled expense to the pool's trash and records who did it.
+    /// Trashed expenses are excluded from listings and balances until
+    /// restored or purged.
+    pub fn soft_delete(
+        conn: &mut PgConnection,
+        expense_id: uuid::Uuid,
+        pool_id: uuid::Uuid,
+        actor_member_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let before = Expense::snapshot(conn, expense_id)?;
+            let expense = diesel::update(
+                expense::table
+                    .filter(expense::id.eq(expense_id))
+                    .filter(expense::pool_id.eq(pool_id))
+                    .filter(expense::is_settled.eq(false))
+                    .filter(expense::deleted_at.is_null()),
+            )
+            .set(expense::deleted_at.eq(diesel::dsl::now))
+            .get_result(conn)?;
+
+            ExpenseEvent::record(
+                conn,
+                &NewExpenseEvent::new(
+                    ExpenseEventAction::Deleted,
+                    actor_member_id,
+                    Some(&before),
+                    None,
+                ),
+            )?;
+
+            Ok(expense)
+        })
+    }
+
+    /// Takes an expense back out of the pool's trash and records who did it.
+    pub fn restore(
+        conn: &mut PgConnection,
+        expense_id: uuid::Uuid,
+        pool_id: uuid::Uuid,
+        actor_member_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let before = Expense::snapshot(conn, expense_id)?;
+            let expense = diesel::update(
+                expense::table
+                    .filter(expense::id.eq(expense_id))
+                    .filter(expense::pool_id.eq(pool_id))
+                    .filter(expense::deleted_at.is_not_null()),
+            )
+            .set(expense::deleted_at.eq(None::<DateTime<Utc>>))
+            .get_result(conn)?;
+            let after = Expense::snapshot(conn, expense_id)?;
+
+            ExpenseEvent::record(
+                conn,
+                &NewExpenseEvent::new(
+                    ExpenseEventAction::Restored,
+                    actor_member_id,
+                    Some(&before),
+                    Some(&after),
+                ),
+            )?;
+
+            Ok(expense)
+        })
+    }
+
+    /// Lists the live expenses a member paid for, optionally limited to one
+    /// pool. Category rules are evaluated against these.
+    pub fn find_paid_by_member(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+        pool_id: Option<uuid::Uuid>,
+    ) -> QueryResult<Vec<Self>> {
+        let mut query = expense::table
+            .filter(expense::paid_by_member_id.eq(member_id))
+            .filter(expense::deleted_at.is_null())
+            .into_boxed();
+
+        if let Some(pool_id) = pool_id {
+            query = query.filter(expense::pool_id.eq(pool_id));
+        }
+
+        query
+            .order_by((expense::incurred_on.desc(), expense::inserted_at.desc()))
+            .get_results(conn)
+    }
+
+    /// Recent live expenses the member categorized themselves, used to
+    /// propose rules from. Expenses a rule categorized are left out, so
+    /// rules don't end up proposing themselves.
+    pub fn find_category_history(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+        limit: i64,
+    ) -> QueryResult<Vec<Self>> {
+        expense::table
+            .filter(expense::deleted_at.is_null())
+            .filter(expense::categorized_by_rule.eq(false))
+            .filter(expense::paid_by_member_id.eq(member_id))
+            .order_by(expense::inserted_at.desc())
+            .limit(limit)
+            .get_results(conn)
+    }
+
+    /// Counts how live expenses the member paid for, or that belong to the
+    /// given pool, were categorized, grouped by name. Names that differ only
+    /// in case or spacing are counted together, and the `limit` most
+    /// recently used names are returned. Expenses a rule categorized are
+    /// left out, since suggestions should learn from people's choices.
+    pub fn count_categories_by_name(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+        pool_id: Option<uuid::Uuid>,
+        limit: i64,
+    ) -> QueryResult<Vec<CategoryNameCount>> {
+        diesel::sql_query(
+            "
+            SELECT
+                MIN(e.name) AS name,
+                e.category,
+                e.paid_by_member_id = $1 AS is_own,
+                COUNT(*) AS occurrences
+            FROM expense e
+            WHERE
+                e.deleted_at IS NULL
+                AND NOT e.categorized_by_rule
+                AND (e.paid_by_member_id = $1 OR e.pool_id = $2)
+            GROUP BY LOWER(REGEXP_REPLACE(TRIM(e.name), '\\s+', ' ', 'g')), e.category, is_own
+            ORDER BY MAX(e.inserted_at) DESC, name, e.category
+            LIMIT $3
+            ",
+        )
+        .bind::<SqlUuid, _>(member_id)
+        .bind::<diesel::sql_types::Nullable<SqlUuid>, _>(pool_id)
+        .bind::<BigInt, _>(limit)
+        .load(conn)
+    }
+
+    pub fn list_deleted_for_pool(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+    ) -> QueryResult<Vec<Self>> {
+        expense::table
+            .filter(expense::pool_id.eq(pool_id))
+            .filter(expense::deleted_at.is_not_null())
+            .order_by(expense::deleted_at.desc())
+            .get_results(conn)
+    }
+
+    /// Permanently removes expenses that have been in the trash since before
+    /// `cutoff`, along with their line items, attachments and comments. The
+    /// expense event log is kept. Returns the removed attachments so their
+    /// blobs can be cleaned up.
+    pub fn purge_deleted_before(
+        conn: &mut PgConnection,
+        cutoff: DateTime<Utc>,
+    ) -> QueryResult<(usize, Vec<ExpenseAttachment>)> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let expense_ids: Vec<uuid::Uuid> =
+                diesel::delete(expense::table.filter(expense::deleted_at.lt(cutoff)))
+                    .returning(expense::id)
+                    .get_results(conn)?;
+
+            let attachments = diesel::delete(
+                expense_attachment::table
+                    .filter(expense_attachment::expense_id.eq_any(&expense_ids)),
+            )
+            .get_results(conn)?;
+
+            diesel::delete(
+                expense_comment::table.filter(expense_comment::expense_id.eq_any(&expense_ids)),
+            )
+            .execute(conn)?;
+
+            diesel::delete(expense_tag::table.filter(expense_tag::expense_id.eq_any(&expense_ids)))
+                .execute(conn)?;
+
+            Ok((expense_ids.len(), attachments))
+        })
+    }
+
     pub fn delete(
         conn: &mut PgConnection,
         expense_id: uuid::Uuid,
@@ -701,33 +1982,140 @@ impl Expense {
         let expense = Self::find(conn, expense_id, member_id, pool_id, is_settled)?;
         let line_items = ExpenseLineItem::find_for_expense(conn, expense_id)?;
 
-        Ok(ExpenseWithLineItems {
-            id: expense.id,
-            name: expense.name,
-            amount: expense.amount,
-            is_settled: expense.is_settled,
-            inserted_at: expense.inserted_at,
-            updated_at: expense.updated_at,
-            pool_id: expense.pool_id,
-            paid_by_member_id: expense.paid_by_member_id,
-            description: expense.description,
-            notes: expense.notes,
-            category: expense.category,
-            split_method: expense.split_method,
+        Ok(expense.with_line_items(line_items))
+    }
+
+    pub fn with_line_items(self, line_items: Vec<ExpenseLineItem>) -> ExpenseWithLineItems {
+        ExpenseWithLineItems {
+            id: self.id,
+            name: self.name,
+            amount: self.amount,
+            is_settled: self.is_settled,
+            inserted_at: self.inserted_at,
+            updated_at: self.updated_at,
+            pool_id: self.pool_id,
+            paid_by_member_id: self.paid_by_member_id,
+            description: self.description,
+            notes: self.notes,
+            category: self.category,
+            split_method: self.split_method,
+            incurred_on: self.incurred_on,
+            deleted_at: self.deleted_at,
+            custom_category_id: self.custom_category_id,
+            categorized_by_rule: self.categorized_by_rule,
             line_items,
-        })
+        }
+    }
+
+    /// Loads the current state of the given expenses, with their line items,
+    /// for recording in the expense event log.
+    pub fn snapshots(
+        conn: &mut PgConnection,
+        expense_ids: &[uuid::Uuid],
+    ) -> QueryResult<Vec<ExpenseWithLineItems>> {
+        let expenses: Vec<Self> = expense::table
+            .filter(expense::id.eq_any(expense_ids))
+            .order_by((expense::inserted_at.asc(), expense::id.asc()))
+            .get_results(conn)?;
+
+        let mut line_items: Vec<ExpenseLineItem> = expense_line_item::table
+            .filter(expense_line_item::expense_id.eq_any(expense_ids))
+            .get_results(conn)?;
+
+        Ok(expenses
+            .into_iter()
+            .map(|expense| {
+                let (own, rest) = line_items
+                    .drain(..)
+                    .partition(|item| item.expense_id == expense.id);
+                line_items = rest;
+
+                expense.with_line_items(own)
+            })
+            .collect())
+    }
+
+    pub fn snapshot(
+        conn: &mut PgConnection,
+        expense_id: uuid::Uuid,
+    ) -> QueryResult<ExpenseWithLineItems> {
+        Self::snapshots(conn, &[expense_id])?
+            .pop()
+            .ok_or(diesel::result::Error::NotFound)
+    }
+
+    /// Reads a pool's live expenses with their line items, oldest first, for
+    /// export. `after` continues from the last expense of the previous batch.
+    pub fn export_batch(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+        after: Option<&ExpenseCursor>,
+        limit: i64,
+    ) -> QueryResult<Vec<ExpenseWithLineItems>> {
+        let mut query = expense::table
+            .filter(expense::pool_id.eq(pool_id))
+            .filter(expense::deleted_at.is_null())
+            .into_boxed();
+
+        if let Some(since) = since {
+            query = query.filter(expense::incurred_on.ge(since));
+        }
+
+        if let Some(until) = until {
+            query = query.filter(expense::incurred_on.le(until));
+        }
+
+        if let Some(after) = after {
+            query = query.filter(
+                expense::incurred_on
+                    .gt(after.incurred_on)
+                    .or(expense::incurred_on
+                        .eq(after.incurred_on)
+                        .and(expense::id.gt(after.id))),
+            );
+        }
+
+        let expenses: Vec<Self> = query
+            .order_by((expense::incurred_on.asc(), expense::id.asc()))
+            .limit(limit)
+            .get_results(conn)?;
+
+        let expense_ids: Vec<uuid::Uuid> = expenses.iter().map(|expense| expense.id).collect();
+
+        let mut line_items: HashMap<uuid::Uuid, Vec<ExpenseLineItem>> = HashMap::new();
+        for item in expense_line_item::table
+            .filter(expense_line_item::expense_id.eq_any(&expense_ids))
+            .order_by(expense_line_item::inserted_at.asc())
+            .get_results::<ExpenseLineItem>(conn)?
+        {
+            line_items.entry(item.expense_id).or_default().push(item);
+        }
+
+        Ok(expenses
+            .into_iter()
+            .map(|expense| {
+                let own = line_items.remove(&expense.id).unwrap_or_default();
+                expense.with_line_items(own)
+            })
+            .collect())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn get_recent_for_member_in_pool(
         conn: &mut PgConnection,
         pool_id: uuid::Uuid,
         member_id: uuid::Uuid,
         limit: i64,
         expense_category: Option<ExpenseCategory>,
+        custom_category_id: Option<uuid::Uuid>,
+        tags: &[String],
         paying_member_id: Option<uuid::Uuid>,
         is_settled: bool,
-        since: DateTime<Utc>,
-        until: DateTime<Utc>,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+        cursor: Option<&ExpenseCursor>,
     ) -> QueryResult<Vec<(Self, f64)>> {
         let mut query = expense::table
             .inner_join(
@@ -738,20 +2126,53 @@ impl Expense {
             )
             .filter(expense::pool_id.eq(pool_id))
             .filter(expense::is_settled.eq(is_settled))
-            .filter(expense::inserted_at.le(until))
-            .filter(expense::inserted_at.ge(since))
+            .filter(expense::deleted_at.is_null())
             .into_boxed();
 
-        if let Some(value) = expense_category {
-            query = query.filter(expense::category.eq(value));
+        if let Some(value) = since {
+            query = query.filter(expense::incurred_on.ge(value));
         }
 
-        if let Some(paid_by_member_id_filter) = paying_member_id {
+        if let Some(value) = until {
+            query = query.filter(expense::incurred_on.le(value));
+        }
+
+        if let Some(cursor) = cursor {
+            query = query.filter(
+                expense::incurred_on
+                    .lt(cursor.incurred_on)
+                    .or(expense::incurred_on
+                        .eq(cursor.incurred_on)
+                        .and(expense::id.lt(cursor.id))),
+            );
+        }
+
+        if let Some(value) = expense_category {
+            query = query.filter(expense::category.eq(value));
+        }
+
+        if let Some(value) = custom_category_id {
+            query = query.filter(expense::custom_category_id.eq(value));
+        }
+
+        for name in tags {
+            query = query.filter(
+                expense::id.eq_any(
+                    expense_tag::table
+                        .inner_join(tag::table)
+                        .filter(tag::pool_id.eq(pool_id))
+                        .filter(lower(tag::name).eq(name.to_lowercase()))
+                        .select(expense_tag::expense_id),
+                ),
+            );
+        }
+
+        if let Some(paid_by_member_id_filter) = paying_member_id {
             query = query.filter(expense::paid_by_member_id.eq(paid_by_member_id_filter));
         }
 
         let results = query
-            .order_by(expense::inserted_at.desc())
+            .order_by((expense::incurred_on.desc(), expense::id.desc()))
             .limit(limit)
             .select((
                 expense::all_columns,
@@ -763,7 +2184,7 @@ impl Expense {
         let mut formatted_results = Vec::new();
         for (expense, paid_by_id, line_amount) in results {
             let amount_owed = if paid_by_id == member_id {
-                &line_amount - &expense.amount
+                line_amount - expense.amount
             } else {
                 line_amount
             };
@@ -789,7 +2210,7 @@ impl Expense {
                     let line_item = NewExpenseLineItem {
                         expense_id: expense.id,
                         is_settled: false,
-                        amount: amounts[i].clone(),
+                        amount: amounts[i],
                         debtor_member_id: debtor_id,
                     };
 
@@ -801,20 +2222,229 @@ impl Expense {
             Ok((expense, line_items))
         })?;
 
-        return Ok(result);
+        Ok(result)
     }
 
     pub fn update(
         conn: &mut PgConnection,
         expense_id: &uuid::Uuid,
+        pool_id: uuid::Uuid,
         new_expense: &ExpenseChangeset,
     ) -> QueryResult<Self> {
         let expense = diesel::update(expense::table)
             .filter(expense::id.eq(expense_id))
+            .filter(expense::pool_id.eq(pool_id))
+            .filter(expense::deleted_at.is_null())
             .set(new_expense)
             .get_result(conn)?;
 
-        return Ok(expense);
+        Ok(expense)
+    }
+
+    /// Searches the live expenses of every pool the member belongs to, best
+    /// matches first. Expenses must match one of the search's terms, if it
+    /// has any, and one of its amounts, if it has any.
+    pub fn search_for_member(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+        pool_id: Option<uuid::Uuid>,
+        search: &ParsedSearch,
+        limit: i64,
+        offset: i64,
+    ) -> QueryResult<Vec<(Self, f32)>> {
+        let (amount_mins, amount_maxes): (Vec<f64>, Vec<f64>) = search
+            .amounts
+            .iter()
+            .map(|range| (range.min, range.max))
+            .unzip();
+
+        let hits = diesel::sql_query(
+            "
+            SELECT
+                e.id,
+                COALESCE(
+                    TS_RANK(expense_search_vector(e.name, e.description, e.notes), q.query),
+                    0
+                ) AS rank
+            FROM expense e
+            JOIN pool_membership pm ON pm.pool_id = e.pool_id AND pm.member_id = $1
+            CROSS JOIN (SELECT TO_TSQUERY('english', $3) AS query) q
+            WHERE
+                e.deleted_at IS NULL
+                AND ($2::UUID IS NULL OR e.pool_id = $2)
+                AND (
+                    q.query IS NULL
+                    OR NUMNODE(q.query) = 0
+                    OR expense_search_vector(e.name, e.description, e.notes) @@ q.query
+                )
+                AND (
+                    CARDINALITY($4::FLOAT8[]) = 0
+                    OR EXISTS (
+                        SELECT 1
+                        FROM UNNEST($4::FLOAT8[], $5::FLOAT8[]) AS amount_range(min, max)
+                        WHERE e.amount >= amount_range.min AND e.amount < amount_range.max
+                    )
+                )
+            ORDER BY rank DESC, e.incurred_on DESC, e.id
+            LIMIT $6
+            OFFSET $7
+            ",
+        )
+        .bind::<Uuid, _>(member_id)
+        .bind::<diesel::sql_types::Nullable<Uuid>, _>(pool_id)
+        .bind::<diesel::sql_types::Nullable<Text>, _>(search.tsquery())
+        .bind::<diesel::sql_types::Array<Double>, _>(amount_mins)
+        .bind::<diesel::sql_types::Array<Double>, _>(amount_maxes)
+        .bind::<BigInt, _>(limit)
+        .bind::<BigInt, _>(offset)
+        .load::<SearchHit>(conn)?;
+
+        let ids: Vec<uuid::Uuid> = hits.iter().map(|hit| hit.id).collect();
+        let mut expenses: HashMap<uuid::Uuid, Self> = expense::table
+            .filter(expense::id.eq_any(&ids))
+            .get_results::<Self>(conn)?
+            .into_iter()
+            .map(|expense| (expense.id, expense))
+            .collect();
+
+        Ok(hits
+            .into_iter()
+            .filter_map(|hit| expenses.remove(&hit.id).map(|expense| (expense, hit.rank)))
+            .collect())
+    }
+
+    /// What the member paid and their share per category, across the live
+    /// expenses they paid for or owe a share of, in one pool or all of theirs.
+    pub fn category_totals_for_member(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+        pool_id: Option<uuid::Uuid>,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> QueryResult<Vec<CategoryTotal>> {
+        diesel::sql_query(
+            "
+            SELECT
+                e.category,
+                COUNT(*) AS expense_count,
+                COALESCE(SUM(e.amount) FILTER (WHERE e.paid_by_member_id = $1), 0) AS paid,
+                COALESCE(SUM(share.amount), 0) AS share
+            FROM expense e
+            JOIN pool_membership pm ON pm.pool_id = e.pool_id AND pm.member_id = $1
+            LEFT JOIN LATERAL (
+                SELECT SUM(eli.amount) AS amount
+                FROM expense_line_item eli
+                WHERE eli.expense_id = e.id AND eli.debtor_member_id = $1
+            ) share ON TRUE
+            WHERE
+                e.deleted_at IS NULL
+                AND (e.paid_by_member_id = $1 OR share.amount IS NOT NULL)
+                AND ($2::UUID IS NULL OR e.pool_id = $2)
+                AND ($3::DATE IS NULL OR e.incurred_on >= $3)
+                AND ($4::DATE IS NULL OR e.incurred_on <= $4)
+            GROUP BY e.category
+            ORDER BY share DESC, paid DESC, e.category
+            ",
+        )
+        .bind::<Uuid, _>(member_id)
+        .bind::<diesel::sql_types::Nullable<Uuid>, _>(pool_id)
+        .bind::<diesel::sql_types::Nullable<diesel::sql_types::Date>, _>(since)
+        .bind::<diesel::sql_types::Nullable<diesel::sql_types::Date>, _>(until)
+        .load::<CategoryTotal>(conn)
+    }
+
+    /// The pool's spending and each member's share per week or month,
+    /// optionally per category, with a zero row for every empty bucket.
+    /// Buckets run through the one containing `until`, or today in
+    /// `timezone`, and start at the one containing `since`, or
+    /// `default_buckets` earlier. Rows are ordered by bucket, then category,
+    /// with the total before the members' shares.
+    #[allow(clippy::too_many_arguments)]
+    pub fn time_series_for_pool(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        interval: TimeSeriesInterval,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+        timezone: &str,
+        by_category: bool,
+        default_buckets: i64,
+    ) -> QueryResult<Vec<TimeSeriesRow>> {
+        diesel::sql_query(
+            "
+            WITH bounds AS (
+                SELECT
+                    COALESCE(
+                        DATE_TRUNC($2, $3::DATE::TIMESTAMP),
+                        finish - ($7 - 1) * ('1 ' || $2)::INTERVAL
+                    ) AS start,
+                    finish
+                FROM (
+                    SELECT DATE_TRUNC(
+                        $2,
+                        COALESCE($4::DATE, (NOW() AT TIME ZONE $5)::DATE)::TIMESTAMP
+                    ) AS finish
+                ) f
+            ), buckets AS (
+                SELECT GENERATE_SERIES(start, finish, ('1 ' || $2)::INTERVAL)::DATE AS bucket
+                FROM bounds
+            ), expense_rows AS (
+                SELECT
+                    DATE_TRUNC($2, e.incurred_on::TIMESTAMP)::DATE AS bucket,
+                    CASE WHEN $6 THEN e.category END AS category,
+                    e.id,
+                    e.amount
+                FROM expense e
+                CROSS JOIN bounds
+                WHERE
+                    e.pool_id = $1
+                    AND e.deleted_at IS NULL
+                    AND e.incurred_on >= bounds.start
+                    AND e.incurred_on < bounds.finish + ('1 ' || $2)::INTERVAL
+                    AND ($3::DATE IS NULL OR e.incurred_on >= $3)
+                    AND ($4::DATE IS NULL OR e.incurred_on <= $4)
+            ), totals AS (
+                SELECT bucket, category, NULL::UUID AS member_id, SUM(amount) AS amount
+                FROM expense_rows
+                GROUP BY bucket, category
+
+                UNION ALL
+
+                SELECT er.bucket, er.category, eli.debtor_member_id, SUM(eli.amount)
+                FROM expense_rows er
+                JOIN expense_line_item eli ON eli.expense_id = er.id
+                GROUP BY er.bucket, er.category, eli.debtor_member_id
+            ), categories AS (
+                SELECT DISTINCT category FROM expense_rows WHERE $6
+                UNION ALL
+                SELECT NULL::expense_category WHERE NOT $6
+            ), members AS (
+                SELECT NULL::UUID AS member_id
+                UNION
+                SELECT member_id FROM pool_membership WHERE pool_id = $1
+                UNION
+                SELECT member_id FROM totals WHERE member_id IS NOT NULL
+            )
+
+            SELECT b.bucket, c.category, m.member_id, COALESCE(t.amount, 0) AS amount
+            FROM buckets b
+            CROSS JOIN categories c
+            CROSS JOIN members m
+            LEFT JOIN totals t
+                ON t.bucket = b.bucket
+                AND t.category IS NOT DISTINCT FROM c.category
+                AND t.member_id IS NOT DISTINCT FROM m.member_id
+            ORDER BY b.bucket, c.category NULLS FIRST, m.member_id NULLS FIRST
+            ",
+        )
+        .bind::<Uuid, _>(pool_id)
+        .bind::<Text, _>(interval.as_sql())
+        .bind::<diesel::sql_types::Nullable<diesel::sql_types::Date>, _>(since)
+        .bind::<diesel::sql_types::Nullable<diesel::sql_types::Date>, _>(until)
+        .bind::<Text, _>(timezone)
+        .bind::<diesel::sql_types::Bool, _>(by_category)
+        .bind::<BigInt, _>(default_buckets)
+        .load::<TimeSeriesRow>(conn)
     }
 
     pub fn list_unpaid_for_balance_computation(
@@ -836,6 +2466,7 @@ impl Expense {
             JOIN expense_line_item eli ON e.id = eli.expense_id AND NOT e.is_settled
             WHERE
                 e.pool_id = $1
+                AND e.deleted_at IS NULL
                 AND eli.debtor_member_id <> e.paid_by_member_id
             GROUP BY eli.debtor_member_id, e.paid_by_member_id
             ",
@@ -897,6 +2528,1244 @@ impl ExpenseLineItem {
     }
 }
 
+impl ExpenseAttachment {
+    pub fn create(
+        conn: &mut PgConnection,
+        new_attachment: &NewExpenseAttachment,
+    ) -> QueryResult<Self> {
+        diesel::insert_into(expense_attachment::table)
+            .values(new_attachment)
+            .get_result(conn)
+    }
+
+    pub fn find(
+        conn: &mut PgConnection,
+        attachment_id: uuid::Uuid,
+        expense_id: uuid::Uuid,
+        pool_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        expense_attachment::table
+            .filter(expense_attachment::id.eq(attachment_id))
+            .filter(expense_attachment::expense_id.eq(expense_id))
+            .filter(expense_attachment::pool_id.eq(pool_id))
+            .first(conn)
+    }
+
+    pub fn find_for_expense(
+        conn: &mut PgConnection,
+        expense_id: uuid::Uuid,
+        pool_id: uuid::Uuid,
+    ) -> QueryResult<Vec<Self>> {
+        expense_attachment::table
+            .filter(expense_attachment::expense_id.eq(expense_id))
+            .filter(expense_attachment::pool_id.eq(pool_id))
+            .order_by(expense_attachment::inserted_at.asc())
+            .get_results(conn)
+    }
+
+    pub fn delete(conn: &mut PgConnection, attachment_id: uuid::Uuid) -> QueryResult<Self> {
+        diesel::delete(expense_attachment::table.find(attachment_id)).get_result(conn)
+    }
+}
+
+impl PoolCategory {
+    /// The built-in category stored alongside this custom category on
+    /// expenses and rules.
+    pub fn builtin_category(&self) -> ExpenseCategory {
+        self.parent_category
+            .clone()
+            .unwrap_or(ExpenseCategory::Miscellaneous)
+    }
+
+    pub fn create(conn: &mut PgConnection, new_category: &NewPoolCategory) -> QueryResult<Self> {
+        diesel::insert_into(pool_category::table)
+            .values(new_category)
+            .get_result(conn)
+    }
+
+    pub fn find(
+        conn: &mut PgConnection,
+        category_id: uuid::Uuid,
+        pool_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        pool_category::table
+            .filter(pool_category::id.eq(category_id))
+            .filter(pool_category::pool_id.eq(pool_id))
+            .first(conn)
+    }
+
+    pub fn list_for_pool(conn: &mut PgConnection, pool_id: uuid::Uuid) -> QueryResult<Vec<Self>> {
+        pool_category::table
+            .filter(pool_category::pool_id.eq(pool_id))
+            .order_by(pool_category::name.asc())
+            .get_results(conn)
+    }
+
+    /// Updates a custom category and keeps the built-in category of its
+    /// expenses and rules in step with its parent.
+    pub fn update(
+        conn: &mut PgConnection,
+        category_id: uuid::Uuid,
+        changeset: &PoolCategoryChangeset,
+    ) -> QueryResult<Self> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let category: Self = diesel::update(pool_category::table.find(category_id))
+                .set(changeset)
+                .get_result(conn)?;
+
+            if changeset.parent_category.is_some() {
+                diesel::update(expense::table.filter(expense::custom_category_id.eq(category.id)))
+                    .set(expense::category.eq(category.builtin_category()))
+                    .execute(conn)?;
+
+                diesel::update(
+                    expense_category_rule::table
+                        .filter(expense_category_rule::custom_category_id.eq(category.id)),
+                )
+                .set(expense_category_rule::category.eq(category.builtin_category()))
+                .execute(conn)?;
+            }
+
+            Ok(category)
+        })
+    }
+
+    pub fn delete(conn: &mut PgConnection, category_id: uuid::Uuid) -> QueryResult<Self> {
+        diesel::delete(pool_category::table.find(category_id)).get_result(conn)
+    }
+}
+
+impl Budget {
+    pub fn create(conn: &mut PgConnection, new_budget: &NewBudget) -> QueryResult<Self> {
+        diesel::insert_into(budget::table)
+            .values(new_budget)
+            .get_result(conn)
+    }
+
+    pub fn find(
+        conn: &mut PgConnection,
+        budget_id: uuid::Uuid,
+        pool_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        budget::table
+            .filter(budget::id.eq(budget_id))
+            .filter(budget::pool_id.eq(pool_id))
+            .first(conn)
+    }
+
+    pub fn list_for_pool(conn: &mut PgConnection, pool_id: uuid::Uuid) -> QueryResult<Vec<Self>> {
+        budget::table
+            .filter(budget::pool_id.eq(pool_id))
+            .order_by((budget::category.asc().nulls_first(), budget::period.asc()))
+            .get_results(conn)
+    }
+
+    pub fn update(
+        conn: &mut PgConnection,
+        budget_id: uuid::Uuid,
+        changeset: &BudgetChangeset,
+    ) -> QueryResult<Self> {
+        diesel::update(budget::table.find(budget_id))
+            .set(changeset)
+            .get_result(conn)
+    }
+
+    pub fn delete(conn: &mut PgConnection, budget_id: uuid::Uuid) -> QueryResult<Self> {
+        diesel::delete(budget::table.find(budget_id)).get_result(conn)
+    }
+
+    /// Reports on the budget for the period containing `date`.
+    pub fn status_on(self, conn: &mut PgConnection, date: NaiveDate) -> QueryResult<BudgetStatus> {
+        let (start, end) = period_bounds(&self.period, date);
+        let spent = self.spent_between(conn, start, end)?;
+
+        Ok(budget_status(self, spent, date))
+    }
+
+    /// What the pool has spent against the budget in `[start, end)`.
+    pub fn spent_between(
+        &self,
+        conn: &mut PgConnection,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> QueryResult<f64> {
+        let mut query = expense::table
+            .filter(expense::pool_id.eq(self.pool_id))
+            .filter(expense::deleted_at.is_null())
+            .filter(expense::incurred_on.ge(start))
+            .filter(expense::incurred_on.lt(end))
+            .into_boxed();
+
+        if let Some(category) = &self.category {
+            query = query.filter(expense::category.eq(category.clone()));
+        }
+
+        query
+            .select(diesel::dsl::sum(expense::amount))
+            .first::<Option<f64>>(conn)
+            .map(|spent| spent.unwrap_or(0.0))
+    }
+}
+
+impl Tag {
+    /// Looks up the pool's tags by name, case-insensitively, creating any
+    /// that don't exist yet. Tags are returned in the order of `names`.
+    pub fn find_or_create(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        names: &[String],
+    ) -> QueryResult<Vec<Self>> {
+        let new_tags: Vec<NewTag> = names
+            .iter()
+            .map(|name| NewTag {
+                pool_id,
+                name: name.clone(),
+            })
+            .collect();
+
+        // Inserting first, rather than looking the tags up and creating the
+        // missing ones, means a tag created concurrently by another request
+        // is simply skipped here instead of failing the unique index.
+        diesel::insert_into(tag::table)
+            .values(&new_tags)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        let lowered: Vec<String> = names.iter().map(|name| name.to_lowercase()).collect();
+
+        let tags: HashMap<String, Self> = tag::table
+            .filter(tag::pool_id.eq(pool_id))
+            .filter(lower(tag::name).eq_any(&lowered))
+            .get_results::<Self>(conn)?
+            .into_iter()
+            .map(|tag| (tag.name.to_lowercase(), tag))
+            .collect();
+
+        lowered
+            .iter()
+            .map(|key| {
+                tags.get(key)
+                    .cloned()
+                    .ok_or(diesel::result::Error::NotFound)
+            })
+            .collect()
+    }
+
+    /// Replaces the expense's tags.
+    pub fn set_for_expense(
+        conn: &mut PgConnection,
+        expense_id: uuid::Uuid,
+        pool_id: uuid::Uuid,
+        names: &[String],
+    ) -> QueryResult<Vec<Self>> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::delete(expense_tag::table.filter(expense_tag::expense_id.eq(expense_id)))
+                .execute(conn)?;
+
+            let tags = Self::find_or_create(conn, pool_id, names)?;
+
+            let links: Vec<NewExpenseTag> = tags
+                .iter()
+                .map(|tag| NewExpenseTag {
+                    expense_id,
+                    tag_id: tag.id,
+                    pool_id,
+                })
+                .collect();
+
+            diesel::insert_into(expense_tag::table)
+                .values(&links)
+                .execute(conn)?;
+
+            Ok(tags)
+        })
+    }
+
+    /// Tag names per expense, alphabetically.
+    pub fn names_for_expenses(
+        conn: &mut PgConnection,
+        expense_ids: &[uuid::Uuid],
+    ) -> QueryResult<HashMap<uuid::Uuid, Vec<String>>> {
+        let rows: Vec<(uuid::Uuid, String)> = expense_tag::table
+            .inner_join(tag::table)
+            .filter(expense_tag::expense_id.eq_any(expense_ids))
+            .order_by(tag::name.asc())
+            .select((expense_tag::expense_id, tag::name))
+            .get_results(conn)?;
+
+        Ok(rows
+            .into_iter()
+            .fold(HashMap::new(), |mut acc, (expense_id, name)| {
+                acc.entry(expense_id).or_insert_with(Vec::new).push(name);
+                acc
+            }))
+    }
+
+    /// The pool's tags, most used first, optionally only those starting
+    /// with `prefix`.
+    pub fn list_for_pool(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        prefix: Option<&str>,
+        limit: i64,
+    ) -> QueryResult<Vec<TagUsage>> {
+        diesel::sql_query(
+            "
+            SELECT
+                t.id,
+                t.name,
+                COUNT(e.id) AS expense_count
+            FROM tag t
+            LEFT JOIN expense_tag et ON et.tag_id = t.id
+            LEFT JOIN expense e ON e.id = et.expense_id AND e.deleted_at IS NULL
+            WHERE
+                t.pool_id = $1
+                AND ($2::TEXT IS NULL OR STARTS_WITH(LOWER(t.name), LOWER($2)))
+            GROUP BY t.id, t.name
+            ORDER BY expense_count DESC, t.name
+            LIMIT $3
+            ",
+        )
+        .bind::<Uuid, _>(pool_id)
+        .bind::<diesel::sql_types::Nullable<Text>, _>(prefix)
+        .bind::<BigInt, _>(limit)
+        .load::<TagUsage>(conn)
+    }
+
+    /// Per-tag totals of the pool's live expenses incurred in the date
+    /// range, with `member_id`'s share of each.
+    pub fn totals_for_pool(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        member_id: uuid::Uuid,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> QueryResult<Vec<TagTotal>> {
+        diesel::sql_query(
+            "
+            SELECT
+                t.id AS tag_id,
+                t.name,
+                COUNT(e.id) AS expense_count,
+                COALESCE(SUM(e.amount), 0) AS total_amount,
+                COALESCE(SUM(share.amount), 0) AS member_share
+            FROM tag t
+            JOIN expense_tag et ON et.tag_id = t.id
+            JOIN expense e ON e.id = et.expense_id AND e.deleted_at IS NULL
+            LEFT JOIN LATERAL (
+                SELECT SUM(eli.amount) AS amount
+                FROM expense_line_item eli
+                WHERE eli.expense_id = e.id AND eli.debtor_member_id = $2
+            ) share ON TRUE
+            WHERE
+                t.pool_id = $1
+                AND ($3::DATE IS NULL OR e.incurred_on >= $3)
+                AND ($4::DATE IS NULL OR e.incurred_on <= $4)
+            GROUP BY t.id, t.name
+            ORDER BY total_amount DESC, t.name
+            ",
+        )
+        .bind::<Uuid, _>(pool_id)
+        .bind::<Uuid, _>(member_id)
+        .bind::<diesel::sql_types::Nullable<diesel::sql_types::Date>, _>(since)
+        .bind::<diesel::sql_types::Nullable<diesel::sql_types::Date>, _>(until)
+        .load::<TagTotal>(conn)
+    }
+}
+
+impl ExpenseEvent {
+    /// Records the event, announces it to open pool streams and queues it
+    /// for the pool's webhooks. Callers run this in the same transaction as
+    /// the change itself.
+    pub fn record(conn: &mut PgConnection, new_event: &NewExpenseEvent) -> QueryResult<Self> {
+        let event: Self = diesel::insert_into(expense_event::table)
+            .values(new_event)
+            .get_result(conn)?;
+
+        // Settle-ups are announced once for the whole settle-up instead.
+        let Some(webhook_event) = WebhookEvent::for_expense_action(&event.action) else {
+            return Ok(event);
+        };
+
+        crate::realtime::notify(
+            conn,
+            &PoolNotification {
+                pool_id: event.pool_id,
+                actor_member_id: event.actor_member_id,
+                action: event.action.clone(),
+                expense_id: Some(event.expense_id),
+            },
+        )?;
+
+        WebhookDelivery::enqueue(
+            conn,
+            event.pool_id,
+            webhook_event,
+            serde_json::json!({
+                "expense_id": event.expense_id,
+                "actor_member_id": event.actor_member_id,
+                "before": event.before_snapshot,
+                "after": event.after_snapshot,
+            }),
+        )?;
+
+        Ok(event)
+    }
+
+    /// Lists a pool's events, newest first. `before` pages backwards through
+    /// the feed by timestamp.
+    pub fn list_for_pool(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        before: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> QueryResult<Vec<Self>> {
+        let mut query = expense_event::table
+            .filter(expense_event::pool_id.eq(pool_id))
+            .into_boxed();
+
+        if let Some(before) = before {
+            query = query.filter(expense_event::inserted_at.lt(before));
+        }
+
+        query
+            .order_by((expense_event::inserted_at.desc(), expense_event::id.desc()))
+            .limit(limit)
+            .get_results(conn)
+    }
+
+    /// Lists the settle-ups of a pool's expenses incurred between the given
+    /// dates, oldest first.
+    pub fn settlements_for_pool(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> QueryResult<Vec<Self>> {
+        let mut expense_ids = expense::table
+            .filter(expense::pool_id.eq(pool_id))
+            .select(expense::id)
+            .into_boxed();
+
+        if let Some(since) = since {
+            expense_ids = expense_ids.filter(expense::incurred_on.ge(since));
+        }
+
+        if let Some(until) = until {
+            expense_ids = expense_ids.filter(expense::incurred_on.le(until));
+        }
+
+        expense_event::table
+            .filter(expense_event::pool_id.eq(pool_id))
+            .filter(expense_event::action.eq(ExpenseEventAction::Settled))
+            .filter(expense_event::expense_id.eq_any(expense_ids))
+            .order_by((expense_event::inserted_at.asc(), expense_event::id.asc()))
+            .get_results(conn)
+    }
+}
+
+impl Webhook {
+    pub fn create(conn: &mut PgConnection, new_webhook: &NewWebhook) -> QueryResult<Self> {
+        diesel::insert_into(webhook::table)
+            .values(new_webhook)
+            .get_result(conn)
+    }
+
+    pub fn find(
+        conn: &mut PgConnection,
+        webhook_id: uuid::Uuid,
+        pool_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        webhook::table
+            .filter(webhook::id.eq(webhook_id))
+            .filter(webhook::pool_id.eq(pool_id))
+            .first(conn)
+    }
+
+    pub fn list_for_pool(conn: &mut PgConnection, pool_id: uuid::Uuid) -> QueryResult<Vec<Self>> {
+        webhook::table
+            .filter(webhook::pool_id.eq(pool_id))
+            .order_by(webhook::inserted_at.asc())
+            .get_results(conn)
+    }
+
+    pub fn update(
+        conn: &mut PgConnection,
+        webhook_id: uuid::Uuid,
+        changeset: &WebhookChangeset,
+    ) -> QueryResult<Self> {
+        diesel::update(webhook::table.find(webhook_id))
+            .set(changeset)
+            .get_result(conn)
+    }
+
+    /// Deletes the webhook along with its delivery log.
+    pub fn delete(conn: &mut PgConnection, webhook_id: uuid::Uuid) -> QueryResult<Self> {
+        diesel::delete(webhook::table.find(webhook_id)).get_result(conn)
+    }
+}
+
+impl WebhookDelivery {
+    /// Queues a delivery of `data` to every active webhook in the pool that
+    /// subscribes to `event`. Nothing is sent here; the delivery worker picks
+    /// the rows up once the surrounding transaction commits.
+    pub fn enqueue(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        event: WebhookEvent,
+        data: serde_json::Value,
+    ) -> QueryResult<usize> {
+        let webhooks: Vec<Webhook> = webhook::table
+            .filter(webhook::pool_id.eq(pool_id))
+            .filter(webhook::is_active.eq(true))
+            .get_results(conn)?;
+
+        let occurred_at = Utc::now();
+        let deliveries: Vec<NewWebhookDelivery> = webhooks
+            .into_iter()
+            .filter(|webhook| webhook.events.contains(&event))
+            .map(|webhook| {
+                let id = uuid::Uuid::new_v4();
+
+                NewWebhookDelivery {
+                    id,
+                    webhook_id: webhook.id,
+                    pool_id,
+                    event,
+                    payload: serde_json::json!({
+                        "id": id,
+                        "event": event,
+                        "pool_id": pool_id,
+                        "occurred_at": occurred_at,
+                        "data": data,
+                    }),
+                }
+            })
+            .collect();
+
+        if deliveries.is_empty() {
+            return Ok(0);
+        }
+
+        diesel::insert_into(webhook_delivery::table)
+            .values(&deliveries)
+            .execute(conn)
+    }
+
+    /// Claims up to `limit` pending deliveries that are due, along with the
+    /// webhook each goes to. Deliveries to paused webhooks wait until they're
+    /// switched back on. Claimed rows are pushed `lease` into the future
+    /// so other workers skip them; if this worker dies before recording an
+    /// attempt, they come due again once the lease runs out.
+    pub fn claim_due(
+        conn: &mut PgConnection,
+        limit: i64,
+        lease: chrono::Duration,
+    ) -> QueryResult<Vec<(Self, Webhook)>> {
+        conn.transaction(|conn| {
+            let now = Utc::now();
+
+            let due: Vec<Self> = webhook_delivery::table
+                .filter(webhook_delivery::status.eq(WebhookDeliveryStatus::Pending))
+                .filter(webhook_delivery::next_attempt_at.le(now))
+                .filter(
+                    webhook_delivery::webhook_id.eq_any(
+                        webhook::table
+                            .filter(webhook::is_active.eq(true))
+                            .select(webhook::id),
+                    ),
+                )
+                .order_by(webhook_delivery::next_attempt_at.asc())
+                .limit(limit)
+                .for_update()
+                .skip_locked()
+                .get_results(conn)?;
+
+            if due.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let ids: Vec<uuid::Uuid> = due.iter().map(|delivery| delivery.id).collect();
+            diesel::update(webhook_delivery::table.filter(webhook_delivery::id.eq_any(&ids)))
+                .set(webhook_delivery::next_attempt_at.eq(now + lease))
+                .execute(conn)?;
+
+            let webhook_ids: Vec<uuid::Uuid> =
+                due.iter().map(|delivery| delivery.webhook_id).collect();
+            let webhooks: HashMap<uuid::Uuid, Webhook> = webhook::table
+                .filter(webhook::id.eq_any(&webhook_ids))
+                .get_results::<Webhook>(conn)?
+                .into_iter()
+                .map(|webhook| (webhook.id, webhook))
+                .collect();
+
+            Ok(due
+                .into_iter()
+                .filter_map(|delivery| {
+                    let webhook = webhooks.get(&delivery.webhook_id)?.clone();
+                    Some((delivery, webhook))
+                })
+                .collect())
+        })
+    }
+
+    /// Records a successful attempt.
+    pub fn mark_delivered(
+        conn: &mut PgConnection,
+        delivery_id: uuid::Uuid,
+        response_status: i32,
+    ) -> QueryResult<Self> {
+        let now = Utc::now();
+
+        diesel::update(webhook_delivery::table.find(delivery_id))
+            .set((
+                webhook_delivery::status.eq(WebhookDeliveryStatus::Delivered),
+                webhook_delivery::attempts.eq(webhook_delivery::attempts + 1),
+                webhook_delivery::last_attempt_at.eq(now),
+                webhook_delivery::last_response_status.eq(response_status),
+                webhook_delivery::last_error.eq(None::<String>),
+                webhook_delivery::delivered_at.eq(now),
+            ))
+            .get_result(conn)
+    }
+
+    /// Records a failed attempt. The delivery is retried at `retry_at`, or
+    /// given up on if there's none.
+    pub fn mark_failed(
+        conn: &mut PgConnection,
+        delivery_id: uuid::Uuid,
+        response_status: Option<i32>,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> QueryResult<Self> {
+        let now = Utc::now();
+        let status = match retry_at {
+            Some(_) => WebhookDeliveryStatus::Pending,
+            None => WebhookDeliveryStatus::Failed,
+        };
+
+        diesel::update(webhook_delivery::table.find(delivery_id))
+            .set((
+                webhook_delivery::status.eq(status),
+                webhook_delivery::attempts.eq(webhook_delivery::attempts + 1),
+                webhook_delivery::last_attempt_at.eq(now),
+                webhook_delivery::last_response_status.eq(response_status),
+                webhook_delivery::last_error.eq(error),
+                webhook_delivery::next_attempt_at.eq(retry_at.unwrap_or(now)),
+            ))
+            .get_result(conn)
+    }
+
+    /// Lists a webhook's deliveries, newest first. `before` pages backwards
+    /// through the log by timestamp.
+    pub fn list_for_webhook(
+        conn: &mut PgConnection,
+        webhook_id: uuid::Uuid,
+        before: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> QueryResult<Vec<Self>> {
+        let mut query = webhook_delivery::table
+            .filter(webhook_delivery::webhook_id.eq(webhook_id))
+            .into_boxed();
+
+        if let Some(before) = before {
+            query = query.filter(webhook_delivery::inserted_at.lt(before));
+        }
+
+        query
+            .order_by((
+                webhook_delivery::inserted_at.desc(),
+                webhook_delivery::id.desc(),
+            ))
+            .limit(limit)
+            .get_results(conn)
+    }
+
+    /// Queues a delivery to be sent again straight away with a fresh set of
+    /// attempts, whatever state it's in.
+    pub fn retry(
+        conn: &mut PgConnection,
+        delivery_id: uuid::Uuid,
+        webhook_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        diesel::update(
+            webhook_delivery::table
+                .filter(webhook_delivery::id.eq(delivery_id))
+                .filter(webhook_delivery::webhook_id.eq(webhook_id)),
+        )
+        .set((
+            webhook_delivery::status.eq(WebhookDeliveryStatus::Pending),
+            webhook_delivery::attempts.eq(0),
+            webhook_delivery::next_attempt_at.eq(Utc::now()),
+            webhook_delivery::delivered_at.eq(None::<DateTime<Utc>>),
+        ))
+        .get_result(conn)
+    }
+}
+
+impl Notification {
+    /// Sends the notifications, skipping any to the member who caused them,
+    /// to placeholder or deleted members, or to members who've turned that
+    /// kind off.
+    pub fn send(
+        conn: &mut PgConnection,
+        notifications: Vec<NewNotification>,
+    ) -> QueryResult<usize> {
+        let member_ids: Vec<uuid::Uuid> = notifications
+            .iter()
+            .map(|notification| notification.member_id)
+            .collect();
+
+        let reachable: std::collections::HashSet<uuid::Uuid> = member::table
+            .filter(member::id.eq_any(&member_ids))
+            .filter(member::is_placeholder.eq(false))
+            .filter(member::deleted_at.is_null())
+            .select(member::id)
+            .get_results::<uuid::Uuid>(conn)?
+            .into_iter()
+            .collect();
+
+        let preferences: HashMap<uuid::Uuid, NotificationPreferences> =
+            notification_preference::table
+                .filter(notification_preference::member_id.eq_any(&member_ids))
+                .select((
+                    notification_preference::member_id,
+                    NotificationPreferences::as_select(),
+                ))
+                .get_results(conn)?
+                .into_iter()
+                .collect();
+
+        let notifications = Self::deliverable(notifications, &reachable, &preferences);
+
+        if notifications.is_empty() {
+            return Ok(0);
+        }
+
+        diesel::insert_into(notification::table)
+            .values(&notifications)
+            .execute(conn)
+    }
+
+    /// The notifications that should go out, given which members can get
+    /// notifications at all and the preferences of those who've set them.
+    pub fn deliverable(
+        notifications: Vec<NewNotification>,
+        reachable: &std::collections::HashSet<uuid::Uuid>,
+        preferences: &HashMap<uuid::Uuid, NotificationPreferences>,
+    ) -> Vec<NewNotification> {
+        notifications
+            .into_iter()
+            .filter(|notification| {
+                reachable.contains(&notification.member_id)
+                    && notification.actor_member_id != Some(notification.member_id)
+                    && preferences
+                        .get(&notification.member_id)
+                        .is_none_or(|preferences| preferences.allows(&notification.kind))
+            })
+            .collect()
+    }
+
+    /// Tells everyone who paid for or has a share of a new expense, along
+    /// with what their share is.
+    pub fn send_expense_added(
+        conn: &mut PgConnection,
+        expense: &ExpenseWithLineItems,
+        actor_member_id: uuid::Uuid,
+    ) -> QueryResult<usize> {
+        let pool_name: String = pool::table
+            .find(expense.pool_id)
+            .select(pool::name)
+            .first(conn)?;
+
+        let mut shares: Vec<(uuid::Uuid, f64)> = vec![(expense.paid_by_member_id, 0.0)];
+
+        for line_item in &expense.line_items {
+            match shares
+                .iter_mut()
+                .find(|(member_id, _)| *member_id == line_item.debtor_member_id)
+            {
+                Some((_, share)) => *share += line_item.amount,
+                None => shares.push((line_item.debtor_member_id, line_item.amount)),
+            }
+        }
+
+        let notifications = shares
+            .into_iter()
+            .map(|(member_id, share)| NewNotification {
+                member_id,
+                kind: NotificationKind::ExpenseAdded,
+                actor_member_id: Some(actor_member_id),
+                pool_id: Some(expense.pool_id),
+                expense_id: Some(expense.id),
+                data: serde_json::json!({
+                    "pool_name": pool_name,
+                    "expense_name": expense.name,
+                    "amount": expense.amount,
+                    "share": share,
+                    "paid_by_member_id": expense.paid_by_member_id,
+                }),
+            })
+            .collect();
+
+        Self::send(conn, notifications)
+    }
+
+    /// Tells a member someone added them to a pool.
+    pub fn send_pool_invitation(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        member_id: uuid::Uuid,
+        actor_member_id: uuid::Uuid,
+    ) -> QueryResult<usize> {
+        let pool_name: String = pool::table.find(pool_id).select(pool::name).first(conn)?;
+
+        Self::send(
+            conn,
+            vec![NewNotification {
+                member_id,
+                kind: NotificationKind::PoolInvitation,
+                actor_member_id: Some(actor_member_id),
+                pool_id: Some(pool_id),
+                expense_id: None,
+                data: serde_json::json!({ "pool_name": pool_name }),
+            }],
+        )
+    }
+
+    /// Lists a member's notifications, newest first. `before` pages
+    /// backwards by timestamp.
+    pub fn list_for_member(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+        unread_only: bool,
+        before: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> QueryResult<Vec<Self>> {
+        let mut query = notification::table
+            .filter(notification::member_id.eq(member_id))
+            .into_boxed();
+
+        if unread_only {
+            query = query.filter(notification::read_at.is_null());
+        }
+
+        if let Some(before) = before {
+            query = query.filter(notification::inserted_at.lt(before));
+        }
+
+        query
+            .order_by((notification::inserted_at.desc(), notification::id.desc()))
+            .limit(limit)
+            .get_results(conn)
+    }
+
+    pub fn unread_count(conn: &mut PgConnection, member_id: uuid::Uuid) -> QueryResult<i64> {
+        notification::table
+            .filter(notification::member_id.eq(member_id))
+            .filter(notification::read_at.is_null())
+            .count()
+            .get_result(conn)
+    }
+
+    /// Marks one of the member's notifications read. Ones that are already
+    /// read keep their original `read_at`.
+    pub fn mark_read(
+        conn: &mut PgConnection,
+        notification_id: uuid::Uuid,
+        member_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        let notification: Self = notification::table
+            .filter(notification::id.eq(notification_id))
+            .filter(notification::member_id.eq(member_id))
+            .first(conn)?;
+
+        if notification.read_at.is_some() {
+            return Ok(notification);
+        }
+
+        diesel::update(notification::table.find(notification_id))
+            .set(notification::read_at.eq(Utc::now()))
+            .get_result(conn)
+    }
+
+    pub fn mark_all_read(conn: &mut PgConnection, member_id: uuid::Uuid) -> QueryResult<usize> {
+        diesel::update(
+            notification::table
+                .filter(notification::member_id.eq(member_id))
+                .filter(notification::read_at.is_null()),
+        )
+        .set(notification::read_at.eq(Utc::now()))
+        .execute(conn)
+    }
+}
+
+impl NotificationPreferences {
+    pub fn for_member(conn: &mut PgConnection, member_id: uuid::Uuid) -> QueryResult<Self> {
+        notification_preference::table
+            .find(member_id)
+            .select(Self::as_select())
+            .first(conn)
+            .optional()
+            .map(Option::unwrap_or_default)
+    }
+
+    pub fn update(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+        changeset: &NotificationPreferencesChangeset,
+    ) -> QueryResult<Self> {
+        let is_empty = changeset.friend_requests.is_none()
+            && changeset.pool_invitations.is_none()
+            && changeset.expenses.is_none()
+            && changeset.settlements.is_none();
+
+        if is_empty {
+            return Self::for_member(conn, member_id);
+        }
+
+        diesel::insert_into(notification_preference::table)
+            .values((notification_preference::member_id.eq(member_id), changeset))
+            .on_conflict(notification_preference::member_id)
+            .do_update()
+            .set(changeset)
+            .returning(Self::as_returning())
+            .get_result(conn)
+    }
+}
+
+impl PoolEmailPreferences {
+    pub fn for_member(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        member_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        pool_email_preference::table
+            .find((pool_id, member_id))
+            .select(Self::as_select())
+            .first(conn)
+            .optional()
+            .map(|preferences| {
+                preferences.unwrap_or(Self {
+                    pool_id,
+                    payment_reminders: true,
+                    weekly_digest: true,
+                })
+            })
+    }
+
+    pub fn list_for_member(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+    ) -> QueryResult<Vec<Self>> {
+        pool_email_preference::table
+            .filter(pool_email_preference::member_id.eq(member_id))
+            .order_by(pool_email_preference::inserted_at.asc())
+            .select(Self::as_select())
+            .get_results(conn)
+    }
+
+    pub fn update(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        member_id: uuid::Uuid,
+        changeset: &PoolEmailPreferencesChangeset,
+    ) -> QueryResult<Self> {
+        if changeset.payment_reminders.is_none() && changeset.weekly_digest.is_none() {
+            return Self::for_member(conn, pool_id, member_id);
+        }
+
+        diesel::insert_into(pool_email_preference::table)
+            .values((
+                pool_email_preference::pool_id.eq(pool_id),
+                pool_email_preference::member_id.eq(member_id),
+                changeset,
+            ))
+            .on_conflict((
+                pool_email_preference::pool_id,
+                pool_email_preference::member_id,
+            ))
+            .do_update()
+            .set(changeset)
+            .returning(Self::as_returning())
+            .get_result(conn)
+    }
+}
+
+impl PaymentReminder {
+    /// Pools anyone might owe money in, plus those with reminders left over
+    /// from before they were settled up.
+    pub fn pools_to_check(conn: &mut PgConnection) -> QueryResult<Vec<uuid::Uuid>> {
+        let mut pool_ids: Vec<uuid::Uuid> = expense::table
+            .filter(expense::is_settled.eq(false))
+            .filter(expense::deleted_at.is_null())
+            .select(expense::pool_id)
+            .distinct()
+            .get_results(conn)?;
+
+        let tracked: Vec<uuid::Uuid> = payment_reminder::table
+            .select(payment_reminder::pool_id)
+            .distinct()
+            .get_results(conn)?;
+
+        for pool_id in tracked {
+            if !pool_ids.contains(&pool_id) {
+                pool_ids.push(pool_id);
+            }
+        }
+
+        Ok(pool_ids)
+    }
+
+    /// Records which members of the pool owe more than the threshold. Ones
+    /// who already did keep their `owing_since`, and anyone not listed has
+    /// paid down enough to stop being reminded.
+    pub fn track(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        owing_member_ids: &[uuid::Uuid],
+        now: DateTime<Utc>,
+    ) -> QueryResult<()> {
+        conn.transaction(|conn| {
+            diesel::delete(
+                payment_reminder::table
+                    .filter(payment_reminder::pool_id.eq(pool_id))
+                    .filter(payment_reminder::member_id.ne_all(owing_member_ids)),
+            )
+            .execute(conn)?;
+
+            let rows: Vec<_> = owing_member_ids
+                .iter()
+                .map(|member_id| {
+                    (
+                        payment_reminder::pool_id.eq(pool_id),
+                        payment_reminder::member_id.eq(*member_id),
+                        payment_reminder::owing_since.eq(now),
+                    )
+                })
+                .collect();
+
+            diesel::insert_into(payment_reminder::table)
+                .values(&rows)
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+
+            Ok(())
+        })
+    }
+
+    /// Marks the pool's reminders that are due as sent and returns them.
+    /// A reminder is due once the member has owed since before `cutoff`
+    /// and hasn't been reminded since then. Claiming in one statement means
+    /// only one replica sends each reminder.
+    pub fn claim_due(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        cutoff: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> QueryResult<Vec<Self>> {
+        let pool_members = pool_membership::table
+            .filter(pool_membership::pool_id.eq(pool_id))
+            .select(pool_membership::member_id);
+
+        let reachable_members = member::table
+            .filter(member::is_placeholder.eq(false))
+            .filter(member::deleted_at.is_null())
+            .select(member::id);
+
+        let opted_out = pool_email_preference::table
+            .filter(pool_email_preference::pool_id.eq(pool_id))
+            .filter(pool_email_preference::payment_reminders.eq(false))
+            .select(pool_email_preference::member_id);
+
+        diesel::update(
+            payment_reminder::table
+                .filter(payment_reminder::pool_id.eq(pool_id))
+                .filter(payment_reminder::owing_since.le(cutoff))
+                .filter(
+                    payment_reminder::last_sent_at
+                        .is_null()
+                        .or(payment_reminder::last_sent_at.le(cutoff)),
+                )
+                .filter(payment_reminder::member_id.eq_any(pool_members))
+                .filter(payment_reminder::member_id.eq_any(reachable_members))
+                .filter(payment_reminder::member_id.ne_all(opted_out)),
+        )
+        .set(payment_reminder::last_sent_at.eq(now))
+        .returning(Self::as_returning())
+        .get_results(conn)
+    }
+
+    /// Undoes a claim whose email couldn't be sent, so the next run tries
+    /// again.
+    pub fn release(
+        conn: &mut PgConnection,
+        pool_id: uuid::Uuid,
+        member_id: uuid::Uuid,
+    ) -> QueryResult<usize> {
+        diesel::update(payment_reminder::table.find((pool_id, member_id)))
+            .set(payment_reminder::last_sent_at.eq(None::<DateTime<Utc>>))
+            .execute(conn)
+    }
+}
+
+impl EmailDigest {
+    /// Members who belong to a pool and haven't had a digest since `cutoff`.
+    pub fn due_member_ids(
+        conn: &mut PgConnection,
+        cutoff: DateTime<Utc>,
+    ) -> QueryResult<Vec<uuid::Uuid>> {
+        let pool_members = pool_membership::table.select(pool_membership::member_id);
+
+        let recently_sent = email_digest::table
+            .filter(email_digest::last_sent_at.gt(cutoff))
+            .select(email_digest::member_id);
+
+        member::table
+            .filter(member::is_placeholder.eq(false))
+            .filter(member::deleted_at.is_null())
+            .filter(member::id.eq_any(pool_members))
+            .filter(member::id.ne_all(recently_sent))
+            .select(member::id)
+            .get_results(conn)
+    }
+
+    /// Marks the member's digest as sent, returning false if another
+    /// replica got there first.
+    pub fn claim(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+        cutoff: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> QueryResult<bool> {
+        let claimed = diesel::sql_query(
+            "
+            INSERT INTO email_digest (member_id, last_sent_at)
+            VALUES ($1, $2)
+            ON CONFLICT (member_id) DO UPDATE
+            SET last_sent_at = EXCLUDED.last_sent_at
+            WHERE email_digest.last_sent_at <= $3
+            ",
+        )
+        .bind::<Uuid, _>(member_id)
+        .bind::<diesel::sql_types::Timestamptz, _>(now)
+        .bind::<diesel::sql_types::Timestamptz, _>(cutoff)
+        .execute(conn)?;
+
+        Ok(claimed > 0)
+    }
+
+    /// Undoes a claim whose email couldn't be sent, so the next run tries
+    /// again.
+    pub fn release(conn: &mut PgConnection, member_id: uuid::Uuid) -> QueryResult<usize> {
+        diesel::delete(email_digest::table.find(member_id)).execute(conn)
+    }
+}
+
+impl PoolActivity {
+    /// What happened since `since` in each of the member's pools that they
+    /// get the digest for. Pools with no activity are left out.
+    pub fn for_member(
+        conn: &mut PgConnection,
+        member_id: uuid::Uuid,
+        since: DateTime<Utc>,
+    ) -> QueryResult<Vec<Self>> {
+        diesel::sql_query(
+            "
+            SELECT
+                p.id AS pool_id,
+                p.name AS pool_name,
+                COUNT(*) FILTER (WHERE ee.action = 'created') AS expenses_added,
+                COALESCE(
+                    SUM((ee.after_snapshot->>'amount')::DOUBLE PRECISION)
+                        FILTER (WHERE ee.action = 'created'),
+                    0
+                ) AS amount_added,
+                COUNT(*) FILTER (WHERE ee.action = 'settled') AS expenses_settled
+            FROM pool_membership pm
+            JOIN pool p ON p.id = pm.pool_id
+            JOIN expense_event ee ON ee.pool_id = p.id AND ee.inserted_at >= $2
+            WHERE
+                pm.member_id = $1
+                AND NOT EXISTS (
+                    SELECT 1
+                    FROM pool_email_preference pep
+                    WHERE
+                        pep.pool_id = p.id
+                        AND pep.member_id = $1
+                        AND NOT pep.weekly_digest
+                )
+            GROUP BY p.id, p.name
+            ORDER BY p.name
+            ",
+        )
+        .bind::<Uuid, _>(member_id)
+        .bind::<diesel::sql_types::Timestamptz, _>(since)
+        .load::<PoolActivity>(conn)
+    }
+}
+
+impl ExpenseComment {
+    pub fn create(conn: &mut PgConnection, new_comment: &NewExpenseComment) -> QueryResult<Self> {
+        diesel::insert_into(expense_comment::table)
+            .values(new_comment)
+            .get_result(conn)
+    }
+
+    pub fn find(
+        conn: &mut PgConnection,
+        comment_id: uuid::Uuid,
+        expense_id: uuid::Uuid,
+        pool_id: uuid::Uuid,
+    ) -> QueryResult<Self> {
+        expense_comment::table
+            .filter(expense_comment::id.eq(comment_id))
+            .filter(expense_comment::expense_id.eq(expense_id))
+            .filter(expense_comment::pool_id.eq(pool_id))
+            .first(conn)
+    }
+
+    pub fn find_for_expense(
+        conn: &mut PgConnection,
+        expense_id: uuid::Uuid,
+        pool_id: uuid::Uuid,
+    ) -> QueryResult<Vec<Self>> {
+        expense_comment::table
+            .filter(expense_comment::expense_id.eq(expense_id))
+            .filter(expense_comment::pool_id.eq(pool_id))
+            .order_by(expense_comment::inserted_at.asc())
+            .get_results(conn)
+    }
+
+    pub fn edit(conn: &mut PgConnection, comment_id: uuid::Uuid, body: &str) -> QueryResult<Self> {
+        diesel::update(
+            expense_comment::table
+                .find(comment_id)
+                .filter(expense_comment::is_deleted.eq(false)),
+        )
+        .set((
+            expense_comment::body.eq(body),
+            expense_comment::is_edited.eq(true),
+            expense_comment::edited_at.eq(diesel::dsl::now),
+        ))
+        .get_result(conn)
+    }
+
+    pub fn soft_delete(conn: &mut PgConnection, comment_id: uuid::Uuid) -> QueryResult<Self> {
+        diesel::update(
+            expense_comment::table
+                .find(comment_id)
+                .filter(expense_comment::is_deleted.eq(false)),
+        )
+        .set((
+            expense_comment::body.eq(""),
+            expense_comment::is_deleted.eq(true),
+            expense_comment::deleted_at.eq(diesel::dsl::now),
+        ))
+        .get_result(conn)
+    }
+}
+
 impl Friendship {
     pub fn create(conn: &mut PgConnection, new_friendship: &NewFriendship) -> QueryResult<Self> {
         diesel::insert_into(friendship::table)
@@ -1001,7 +3870,23 @@ impl Friendship {
 
                 match Self::find(conn, inviting_id, friend_id) {
                     Ok(_) => Ok(None),
-                    Err(_) => Self::create(conn, &new_friendship).map(Some),
+                    Err(_) => conn.transaction(|conn| {
+                        let friendship = Self::create(conn, &new_friendship)?;
+
+                        Notification::send(
+                            conn,
+                            vec![NewNotification {
+                                member_id: friend_id,
+                                kind: NotificationKind::FriendRequest,
+                                actor_member_id: Some(inviting_id),
+                                pool_id: None,
+                                expense_id: None,
+                                data: serde_json::json!({}),
+                            }],
+                        )?;
+
+                        Ok(Some(friendship))
+                    }),
                 }
             }
             Err(e) => Err(e),
@@ -0,0 +1,64 @@
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+pub const MAX_PAGE_LIMIT: i64 = 200;
+
+/// One page of a list. Pass `next_cursor` back as `cursor` to fetch the
+/// next page.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Builds a page from a query for `limit + 1` rows. The extra row is
+    /// dropped; it only signals that there is another page, which starts
+    /// after the last row kept.
+    pub fn from_rows(mut rows: Vec<T>, limit: i64, cursor_for: impl Fn(&T) -> String) -> Self {
+        let limit = limit.max(0) as usize;
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+
+        let next_cursor = rows.last().filter(|_| has_more).map(cursor_for);
+
+        Self {
+            items: rows,
+            next_cursor,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum CursorError {
+    #[error("invalid cursor")]
+    Invalid,
+}
+
+/// Clamps a requested page size, defaulting to `DEFAULT_PAGE_LIMIT`.
+pub fn page_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT)
+}
+
+/// Cursors are opaque to clients: the hex-encoded JSON of the sort key of
+/// the last row on the page.
+pub fn encode_cursor<K: Serialize>(key: &K) -> String {
+    hex::encode(serde_json::to_vec(key).expect("Cursor keys serialize to JSON"))
+}
+
+pub fn decode_cursor<K: DeserializeOwned>(cursor: &str) -> Result<K, CursorError> {
+    let bytes = hex::decode(cursor).map_err(|_| CursorError::Invalid)?;
+    serde_json::from_slice(&bytes).map_err(|_| CursorError::Invalid)
+}
+
+/// Position in a list of expenses ordered by date, then id, both
+/// descending.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpenseCursor {
+    pub incurred_on: NaiveDate,
+    pub id: uuid::Uuid,
+}
@@ -0,0 +1,159 @@
+use std::ffi::{CStr, CString};
+use std::os::fd::RawFd;
+
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::ExpenseEventAction;
+
+/// The Postgres channel pool changes are announced on. Every replica listens
+/// on it, so a change made through one reaches clients connected to any.
+pub const POOL_EVENTS_CHANNEL: &str = "medici_pool_events";
+
+#[derive(Debug, thiserror::Error)]
+pub enum RealtimeError {
+    #[error("failed to connect to the database: {0}")]
+    Connect(String),
+    #[error("failed to listen for notifications: {0}")]
+    Listen(String),
+    #[error("lost the notification connection: {0}")]
+    ConnectionLost(String),
+}
+
+/// A change to a pool's expenses. Kept small, since NOTIFY payloads are
+/// capped at 8000 bytes; clients refetch whatever they need.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PoolNotification {
+    pub pool_id: uuid::Uuid,
+    pub actor_member_id: uuid::Uuid,
+    pub action: ExpenseEventAction,
+    /// Missing for settle-ups, which cover every unsettled expense at once.
+    pub expense_id: Option<uuid::Uuid>,
+}
+
+impl PoolNotification {
+    pub fn parse(payload: &str) -> Option<Self> {
+        serde_json::from_str(payload).ok()
+    }
+}
+
+/// What listeners pass on to the open streams.
+#[derive(Debug, Clone)]
+pub enum PoolFeedMessage {
+    Notification(PoolNotification),
+    /// The listener reconnected and may have missed notifications while it
+    /// was down, so clients should refetch.
+    Reconnected,
+}
+
+/// Announces the change to every replica. Postgres holds the notification
+/// until the surrounding transaction commits and drops it on rollback.
+pub fn notify(conn: &mut PgConnection, notification: &PoolNotification) -> QueryResult<()> {
+    let payload = serde_json::to_string(notification).expect("Notifications serialize");
+
+    diesel::sql_query("SELECT pg_notify($1, $2)")
+        .bind::<Text, _>(POOL_EVENTS_CHANNEL)
+        .bind::<Text, _>(payload)
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// A dedicated libpq connection that LISTENs on a channel. Diesel can't
+/// receive notifications, so this talks to libpq directly.
+pub struct PgListener {
+    conn: *mut pq_sys::PGconn,
+}
+
+// A libpq connection can move between threads as long as it's only used
+// from one at a time, which `&mut self` guarantees.
+unsafe impl Send for PgListener {}
+
+impl PgListener {
+    pub fn connect(database_url: &str, channel: &str) -> Result<Self, RealtimeError> {
+        let conninfo =
+            CString::new(database_url).map_err(|e| RealtimeError::Connect(e.to_string()))?;
+
+        let listener = PgListener {
+            conn: unsafe { pq_sys::PQconnectdb(conninfo.as_ptr()) },
+        };
+
+        if listener.conn.is_null()
+            || unsafe { pq_sys::PQstatus(listener.conn) } != pq_sys::ConnStatusType::CONNECTION_OK
+        {
+            return Err(RealtimeError::Connect(listener.error_message()));
+        }
+
+        let query = CString::new(format!("LISTEN \"{}\"", channel.replace('"', "\"\"")))
+            .map_err(|e| RealtimeError::Listen(e.to_string()))?;
+
+        unsafe {
+            let result = pq_sys::PQexec(listener.conn, query.as_ptr());
+            let status = pq_sys::PQresultStatus(result);
+            pq_sys::PQclear(result);
+
+            if status != pq_sys::ExecStatusType::PGRES_COMMAND_OK {
+                return Err(RealtimeError::Listen(listener.error_message()));
+            }
+        }
+
+        Ok(listener)
+    }
+
+    /// The connection's socket, to wait on for incoming notifications. It's
+    /// closed when the listener is dropped.
+    pub fn socket(&self) -> RawFd {
+        unsafe { pq_sys::PQsocket(self.conn) }
+    }
+
+    /// Reads whatever the server has sent without blocking and returns the
+    /// payloads of any notifications in it.
+    pub fn read_notifications(&mut self) -> Result<Vec<String>, RealtimeError> {
+        if unsafe { pq_sys::PQconsumeInput(self.conn) } == 0 {
+            return Err(RealtimeError::ConnectionLost(self.error_message()));
+        }
+
+        let mut payloads = Vec::new();
+
+        loop {
+            let notify = unsafe { pq_sys::PQnotifies(self.conn) };
+
+            if notify.is_null() {
+                break;
+            }
+
+            unsafe {
+                payloads.push(
+                    CStr::from_ptr((*notify).extra)
+                        .to_string_lossy()
+                        .into_owned(),
+                );
+                pq_sys::PQfreemem(notify.cast());
+            }
+        }
+
+        Ok(payloads)
+    }
+
+    fn error_message(&self) -> String {
+        if self.conn.is_null() {
+            return "out of memory".to_string();
+        }
+
+        unsafe { CStr::from_ptr(pq_sys::PQerrorMessage(self.conn)) }
+            .to_string_lossy()
+            .trim()
+            .to_string()
+    }
+}
+
+impl Drop for PgListener {
+    fn drop(&mut self) {
+        if !self.conn.is_null() {
+            unsafe { pq_sys::PQfinish(self.conn) };
+        }
+    }
+}
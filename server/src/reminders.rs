@@ -0,0 +1,166 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::mail::Email;
+use crate::models::{Balance, Member, PaymentDirection, PoolActivity};
+
+/// Members are emailed their digest at most this often.
+pub fn digest_interval() -> Duration {
+    Duration::days(7)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReminderSettings {
+    /// Members who owe more than this in a pool are reminded to pay.
+    pub threshold: f64,
+    /// How long a member has to owe more than the threshold before the
+    /// first reminder, and how long to wait between reminders after that.
+    pub after: Duration,
+    pub weekly_digest: bool,
+}
+
+impl ReminderSettings {
+    /// Reads `PAYMENT_REMINDER_THRESHOLD` (20 by default),
+    /// `PAYMENT_REMINDER_DAYS` (7 by default) and `WEEKLY_DIGEST_ENABLED`
+    /// (off by default).
+    pub fn from_env() -> Self {
+        let threshold = std::env::var("PAYMENT_REMINDER_THRESHOLD")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(20.0);
+        let days = std::env::var("PAYMENT_REMINDER_DAYS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(7);
+        let weekly_digest = std::env::var("WEEKLY_DIGEST_ENABLED")
+            .map(|value| matches!(value.as_str(), "1" | "true"))
+            .unwrap_or(false);
+
+        Self {
+            threshold,
+            after: Duration::days(days),
+            weekly_digest,
+        }
+    }
+}
+
+/// The total a member owes others, from their simplified balances.
+pub fn amount_owed(balances: &[Balance]) -> f64 {
+    balances
+        .iter()
+        .filter(|balance| balance.direction == PaymentDirection::Outbound)
+        .map(|balance| balance.amount)
+        .sum()
+}
+
+pub fn format_amount(amount: f64) -> String {
+    format!("${:.2}", amount)
+}
+
+/// Someone a reminded member owes money to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Creditor {
+    pub name: String,
+    pub venmo_handle: Option<String>,
+    pub amount: f64,
+}
+
+pub fn payment_reminder_email(
+    member: &Member,
+    pool_name: &str,
+    owing_since: DateTime<Utc>,
+    creditors: &[Creditor],
+    now: DateTime<Utc>,
+) -> Email {
+    let total: f64 = creditors.iter().map(|creditor| creditor.amount).sum();
+    let days = (now - owing_since).num_days();
+
+    let mut body = format!(
+        "Hi {},\n\nYou've owed {} in {} for {} {}. Here's who to pay:\n\n",
+        member.first_name,
+        format_amount(total),
+        pool_name,
+        days,
+        if days == 1 { "day" } else { "days" }
+    );
+
+    for creditor in creditors {
+        body += &format!("- {}: {}", creditor.name, format_amount(creditor.amount));
+
+        if let Some(handle) = &creditor.venmo_handle {
+            body += &format!(" (Venmo @{})", handle.trim_start_matches('@'));
+        }
+
+        body.push('\n');
+    }
+
+    body += &format!(
+        "\nOnce everyone's paid, settle up the pool in Medici to clear the balance.\n\n{}",
+        UNSUBSCRIBE_FOOTER
+    );
+
+    Email {
+        to: member.email.clone(),
+        subject: format!("You owe {} in {}", format_amount(total), pool_name),
+        body,
+    }
+}
+
+/// The digest of the past week in the member's pools, or `None` if nothing
+/// happened in any of them.
+pub fn weekly_digest_email(member: &Member, activity: &[PoolActivity]) -> Option<Email> {
+    let activity: Vec<&PoolActivity> = activity
+        .iter()
+        .filter(|pool| pool.expenses_added > 0 || pool.expenses_settled > 0)
+        .collect();
+
+    if activity.is_empty() {
+        return None;
+    }
+
+    let mut body = format!(
+        "Hi {},\n\nHere's what happened in your pools this week:\n\n",
+        member.first_name
+    );
+
+    for pool in activity {
+        body += &format!("{}\n", pool.pool_name);
+
+        if pool.expenses_added > 0 {
+            body += &format!(
+                "- {} {} added, totalling {}\n",
+                pool.expenses_added,
+                if pool.expenses_added == 1 {
+                    "expense"
+                } else {
+                    "expenses"
+                },
+                format_amount(pool.amount_added)
+            );
+        }
+
+        if pool.expenses_settled > 0 {
+            body += &format!(
+                "- {} {} settled\n",
+                pool.expenses_settled,
+                if pool.expenses_settled == 1 {
+                    "expense"
+                } else {
+                    "expenses"
+                }
+            );
+        }
+
+        body.push('\n');
+    }
+
+    body += UNSUBSCRIBE_FOOTER;
+
+    Some(Email {
+        to: member.email.clone(),
+        subject: "Your week in Medici".to_string(),
+        body,
+    })
+}
+
+const UNSUBSCRIBE_FOOTER: &str =
+    "You can turn these emails off for each pool in the pool's email preferences.\n";
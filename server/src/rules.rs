@@ -0,0 +1,203 @@
+use std::cmp::Reverse;
+
+use regex::RegexBuilder;
+
+use std::collections::HashMap;
+
+use crate::models::{
+    Expense, ExpenseCategory, ExpenseCategoryRule, NewExpenseCategoryRule, RuleMatchType,
+};
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum RuleError {
+    #[error("rule pattern cannot be empty")]
+    EmptyPattern,
+    #[error("invalid regex: {0}")]
+    InvalidRegex(String),
+    #[error("min_amount cannot be greater than max_amount")]
+    InvalidAmountRange,
+}
+
+/// The parts of an expense that rules are evaluated against.
+pub struct RuleSubject<'a> {
+    pub name: &'a str,
+    pub amount: f64,
+    pub pool_id: uuid::Uuid,
+}
+
+/// The conditions shared by stored rules and candidate rules that haven't
+/// been saved yet.
+pub trait RuleConditions {
+    fn pattern(&self) -> &str;
+    fn match_type(&self) -> &RuleMatchType;
+    fn min_amount(&self) -> Option<f64>;
+    fn max_amount(&self) -> Option<f64>;
+    fn pool_id(&self) -> Option<uuid::Uuid>;
+}
+
+impl RuleConditions for ExpenseCategoryRule {
+    fn pattern(&self) -> &str {
+        &self.rule
+    }
+
+    fn match_type(&self) -> &RuleMatchType {
+        &self.match_type
+    }
+
+    fn min_amount(&self) -> Option<f64> {
+        self.min_amount
+    }
+
+    fn max_amount(&self) -> Option<f64> {
+        self.max_amount
+    }
+
+    fn pool_id(&self) -> Option<uuid::Uuid> {
+        self.pool_id
+    }
+}
+
+impl RuleConditions for NewExpenseCategoryRule {
+    fn pattern(&self) -> &str {
+        &self.rule
+    }
+
+    fn match_type(&self) -> &RuleMatchType {
+        &self.match_type
+    }
+
+    fn min_amount(&self) -> Option<f64> {
+        self.min_amount
+    }
+
+    fn max_amount(&self) -> Option<f64> {
+        self.max_amount
+    }
+
+    fn pool_id(&self) -> Option<uuid::Uuid> {
+        self.pool_id
+    }
+}
+
+pub fn validate_rule(rule: &impl RuleConditions) -> Result<(), RuleError> {
+    if rule.pattern().trim().is_empty() {
+        return Err(RuleError::EmptyPattern);
+    }
+
+    if *rule.match_type() == RuleMatchType::Regex {
+        RegexBuilder::new(rule.pattern())
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| RuleError::InvalidRegex(e.to_string()))?;
+    }
+
+    if let (Some(min), Some(max)) = (rule.min_amount(), rule.max_amount())
+        && min > max
+    {
+        return Err(RuleError::InvalidAmountRange);
+    }
+
+    Ok(())
+}
+
+/// Name patterns are matched case-insensitively. Regex rules match anywhere
+/// in the name unless anchored, and regexes that don't compile never match.
+fn pattern_matches(match_type: &RuleMatchType, pattern: &str, name: &str) -> bool {
+    let normalize = |value: &str| value.trim().to_lowercase();
+
+    match match_type {
+        RuleMatchType::Contains => normalize(name).contains(&normalize(pattern)),
+        RuleMatchType::Exact => normalize(name) == normalize(pattern),
+        RuleMatchType::StartsWith => normalize(name).starts_with(&normalize(pattern)),
+        RuleMatchType::Regex => RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map(|regex| regex.is_match(name.trim()))
+            .unwrap_or(false),
+    }
+}
+
+pub fn rule_matches(rule: &impl RuleConditions, subject: &RuleSubject) -> bool {
+    if subject.name.trim().is_empty() {
+        return false;
+    }
+
+    rule.pool_id()
+        .is_none_or(|pool_id| pool_id == subject.pool_id)
+        && rule.min_amount().is_none_or(|min| subject.amount >= min)
+        && rule.max_amount().is_none_or(|max| subject.amount <= max)
+        && pattern_matches(rule.match_type(), rule.pattern(), subject.name)
+}
+
+/// Returns the rule that should categorize the expense. When several rules
+/// match, the highest priority wins, then pool-scoped rules over global ones,
+/// then the oldest rule.
+pub fn find_matching_rule<'a>(
+    rules: &'a [ExpenseCategoryRule],
+    subject: &RuleSubject,
+) -> Option<&'a ExpenseCategoryRule> {
+    rules
+        .iter()
+        .filter(|rule| rule_matches(*rule, subject))
+        .min_by_key(|rule| {
+            (
+                Reverse(rule.priority),
+                rule.pool_id.is_none(),
+                rule.inserted_at,
+            )
+        })
+}
+
+impl<'a> From<&'a Expense> for RuleSubject<'a> {
+    fn from(expense: &'a Expense) -> Self {
+        Self {
+            name: &expense.name,
+            amount: expense.amount,
+            pool_id: expense.pool_id,
+        }
+    }
+}
+
+/// Whether giving the expense this built-in and custom category would change
+/// it.
+pub fn changes_category(
+    category: &ExpenseCategory,
+    custom_category_id: Option<uuid::Uuid>,
+    expense: &Expense,
+) -> bool {
+    *category != expense.category || custom_category_id != expense.custom_category_id
+}
+
+/// Pairs each expense whose category would change with the rule that would
+/// change it. Expenses already in the rule's category are left out.
+pub fn plan_recategorization<'a>(
+    rules: &'a [ExpenseCategoryRule],
+    expenses: &'a [Expense],
+) -> Vec<(&'a Expense, &'a ExpenseCategoryRule)> {
+    expenses
+        .iter()
+        .filter_map(|expense| {
+            find_matching_rule(rules, &expense.into())
+                .filter(|rule| changes_category(&rule.category, rule.custom_category_id, expense))
+                .map(|rule| (expense, rule))
+        })
+        .collect()
+}
+
+/// Counts expenses per category, most common first.
+pub fn count_by_category<'a>(
+    categories: impl IntoIterator<Item = &'a ExpenseCategory>,
+) -> Vec<(ExpenseCategory, usize)> {
+    let mut counts: Vec<(ExpenseCategory, usize)> = categories
+        .into_iter()
+        .fold(HashMap::new(), |mut acc, category| {
+            *acc.entry(category.clone()).or_insert(0) += 1;
+            acc
+        })
+        .into_iter()
+        .collect();
+
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    counts
+}
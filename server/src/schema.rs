@@ -1,21 +1,71 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "budget_period"))]
+    pub struct BudgetPeriod;
+
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "expense_category"))]
     pub struct ExpenseCategory;
 
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "expense_event_action"))]
+    pub struct ExpenseEventAction;
+
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "friendship_status"))]
     pub struct FriendshipStatus;
 
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "notification_kind"))]
+    pub struct NotificationKind;
+
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "pool_role"))]
     pub struct PoolRole;
 
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "rule_match_type"))]
+    pub struct RuleMatchType;
+
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "split_method"))]
     pub struct SplitMethod;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "webhook_delivery_status"))]
+    pub struct WebhookDeliveryStatus;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "webhook_event"))]
+    pub struct WebhookEvent;
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ExpenseCategory;
+    use super::sql_types::BudgetPeriod;
+
+    budget (id) {
+        id -> Uuid,
+        pool_id -> Uuid,
+        category -> Nullable<ExpenseCategory>,
+        amount -> Float8,
+        period -> BudgetPeriod,
+        created_by_member_id -> Uuid,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    email_digest (member_id) {
+        member_id -> Uuid,
+        last_sent_at -> Timestamptz,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
 }
 
 diesel::table! {
@@ -36,19 +86,78 @@ diesel::table! {
         notes -> Nullable<Text>,
         category -> ExpenseCategory,
         split_method -> SplitMethod,
+        incurred_on -> Date,
+        deleted_at -> Nullable<Timestamptz>,
+        custom_category_id -> Nullable<Uuid>,
+        categorized_by_rule -> Bool,
+    }
+}
+
+diesel::table! {
+    expense_attachment (id) {
+        id -> Uuid,
+        expense_id -> Uuid,
+        pool_id -> Uuid,
+        uploaded_by_member_id -> Uuid,
+        file_name -> Text,
+        content_type -> Text,
+        size_bytes -> Int8,
+        storage_key -> Text,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::ExpenseCategory;
+    use super::sql_types::RuleMatchType;
 
-    expense_category_rule (member_id, rule, category) {
+    expense_category_rule (id) {
         member_id -> Uuid,
         rule -> Text,
         category -> ExpenseCategory,
         inserted_at -> Timestamptz,
         updated_at -> Timestamptz,
+        id -> Uuid,
+        match_type -> RuleMatchType,
+        min_amount -> Nullable<Float8>,
+        max_amount -> Nullable<Float8>,
+        pool_id -> Nullable<Uuid>,
+        priority -> Int4,
+        custom_category_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    expense_comment (id) {
+        id -> Uuid,
+        expense_id -> Uuid,
+        pool_id -> Uuid,
+        author_member_id -> Uuid,
+        body -> Text,
+        is_edited -> Bool,
+        is_deleted -> Bool,
+        edited_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ExpenseEventAction;
+
+    expense_event (id) {
+        id -> Uuid,
+        expense_id -> Uuid,
+        pool_id -> Uuid,
+        actor_member_id -> Uuid,
+        action -> ExpenseEventAction,
+        before_snapshot -> Nullable<Jsonb>,
+        after_snapshot -> Nullable<Jsonb>,
+        inserted_at -> Timestamptz,
     }
 }
 
@@ -82,6 +191,10 @@ diesel::table! {
         notes -> Nullable<Text>,
         category -> ExpenseCategory,
         split_method -> SplitMethod,
+        incurred_on -> Date,
+        deleted_at -> Nullable<Timestamptz>,
+        custom_category_id -> Nullable<Uuid>,
+        categorized_by_rule -> Bool,
     }
 }
 
@@ -103,6 +216,19 @@ diesel::table! {
         notes -> Nullable<Text>,
         category -> ExpenseCategory,
         split_method -> SplitMethod,
+        incurred_on -> Date,
+        deleted_at -> Nullable<Timestamptz>,
+        custom_category_id -> Nullable<Uuid>,
+        categorized_by_rule -> Bool,
+    }
+}
+
+diesel::table! {
+    expense_tag (expense_id, tag_id) {
+        expense_id -> Uuid,
+        tag_id -> Uuid,
+        pool_id -> Uuid,
+        inserted_at -> Timestamptz,
     }
 }
 
@@ -129,6 +255,8 @@ diesel::table! {
         updated_at -> Timestamptz,
         bio -> Nullable<Text>,
         venmo_handle -> Nullable<Text>,
+        is_placeholder -> Bool,
+        deleted_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -142,6 +270,47 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::NotificationKind;
+
+    notification (id) {
+        id -> Uuid,
+        member_id -> Uuid,
+        kind -> NotificationKind,
+        actor_member_id -> Nullable<Uuid>,
+        pool_id -> Nullable<Uuid>,
+        expense_id -> Nullable<Uuid>,
+        data -> Jsonb,
+        read_at -> Nullable<Timestamptz>,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    notification_preference (member_id) {
+        member_id -> Uuid,
+        friend_requests -> Bool,
+        pool_invitations -> Bool,
+        expenses -> Bool,
+        settlements -> Bool,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    payment_reminder (pool_id, member_id) {
+        pool_id -> Uuid,
+        member_id -> Uuid,
+        owing_since -> Timestamptz,
+        last_sent_at -> Nullable<Timestamptz>,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     pool (id) {
         id -> Uuid,
@@ -152,6 +321,33 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ExpenseCategory;
+
+    pool_category (id) {
+        id -> Uuid,
+        pool_id -> Uuid,
+        name -> Text,
+        color -> Text,
+        icon -> Nullable<Text>,
+        parent_category -> Nullable<ExpenseCategory>,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    pool_email_preference (pool_id, member_id) {
+        pool_id -> Uuid,
+        member_id -> Uuid,
+        payment_reminders -> Bool,
+        weekly_digest -> Bool,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::PoolRole;
@@ -167,27 +363,119 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    tag (id) {
+        id -> Uuid,
+        pool_id -> Uuid,
+        name -> Text,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::WebhookEvent;
+
+    webhook (id) {
+        id -> Uuid,
+        pool_id -> Uuid,
+        created_by_member_id -> Uuid,
+        url -> Text,
+        secret -> Text,
+        events -> Array<Nullable<WebhookEvent>>,
+        is_active -> Bool,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::WebhookEvent;
+    use super::sql_types::WebhookDeliveryStatus;
+
+    webhook_delivery (id) {
+        id -> Uuid,
+        webhook_id -> Uuid,
+        pool_id -> Uuid,
+        event -> WebhookEvent,
+        payload -> Jsonb,
+        status -> WebhookDeliveryStatus,
+        attempts -> Int4,
+        next_attempt_at -> Timestamptz,
+        last_attempt_at -> Nullable<Timestamptz>,
+        last_response_status -> Nullable<Int4>,
+        last_error -> Nullable<Text>,
+        delivered_at -> Nullable<Timestamptz>,
+        inserted_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::joinable!(budget -> member (created_by_member_id));
+diesel::joinable!(budget -> pool (pool_id));
+diesel::joinable!(email_digest -> member (member_id));
 diesel::joinable!(expense -> member (paid_by_member_id));
 diesel::joinable!(expense -> pool (pool_id));
+diesel::joinable!(expense -> pool_category (custom_category_id));
+diesel::joinable!(expense_attachment -> member (uploaded_by_member_id));
+diesel::joinable!(expense_attachment -> pool (pool_id));
 diesel::joinable!(expense_category_rule -> member (member_id));
+diesel::joinable!(expense_category_rule -> pool (pool_id));
+diesel::joinable!(expense_category_rule -> pool_category (custom_category_id));
+diesel::joinable!(expense_comment -> member (author_member_id));
+diesel::joinable!(expense_comment -> pool (pool_id));
+diesel::joinable!(expense_event -> member (actor_member_id));
+diesel::joinable!(expense_event -> pool (pool_id));
 diesel::joinable!(expense_line_item -> member (debtor_member_id));
 diesel::joinable!(expense_p_is_settled_false -> member (paid_by_member_id));
 diesel::joinable!(expense_p_is_settled_false -> pool (pool_id));
+diesel::joinable!(expense_p_is_settled_false -> pool_category (custom_category_id));
 diesel::joinable!(expense_p_is_settled_true -> member (paid_by_member_id));
 diesel::joinable!(expense_p_is_settled_true -> pool (pool_id));
+diesel::joinable!(expense_p_is_settled_true -> pool_category (custom_category_id));
+diesel::joinable!(expense_tag -> pool (pool_id));
+diesel::joinable!(expense_tag -> tag (tag_id));
 diesel::joinable!(member_password -> member (member_id));
+diesel::joinable!(notification -> pool (pool_id));
+diesel::joinable!(notification_preference -> member (member_id));
+diesel::joinable!(payment_reminder -> member (member_id));
+diesel::joinable!(payment_reminder -> pool (pool_id));
+diesel::joinable!(pool_category -> pool (pool_id));
+diesel::joinable!(pool_email_preference -> member (member_id));
+diesel::joinable!(pool_email_preference -> pool (pool_id));
 diesel::joinable!(pool_membership -> member (member_id));
 diesel::joinable!(pool_membership -> pool (pool_id));
+diesel::joinable!(tag -> pool (pool_id));
+diesel::joinable!(webhook -> member (created_by_member_id));
+diesel::joinable!(webhook -> pool (pool_id));
+diesel::joinable!(webhook_delivery -> pool (pool_id));
+diesel::joinable!(webhook_delivery -> webhook (webhook_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    budget,
+    email_digest,
     expense,
+    expense_attachment,
     expense_category_rule,
+    expense_comment,
+    expense_event,
     expense_line_item,
     expense_p_is_settled_false,
     expense_p_is_settled_true,
+    expense_tag,
     friendship,
     member,
     member_password,
+    notification,
+    notification_preference,
+    payment_reminder,
     pool,
+    pool_category,
+    pool_email_preference,
     pool_membership,
+    tag,
+    webhook,
+    webhook_delivery,
 );
@@ -0,0 +1,86 @@
+/// An amount mentioned in a search, matched as `min <= amount < max`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmountRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ParsedSearch {
+    /// Lowercased words to look for in names, descriptions and notes.
+    pub terms: Vec<String>,
+    pub amounts: Vec<AmountRange>,
+}
+
+impl ParsedSearch {
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.amounts.is_empty()
+    }
+
+    /// A `to_tsquery` expression matching any term, or words starting with
+    /// it. Terms are alphanumeric, so they can't inject query operators.
+    pub fn tsquery(&self) -> Option<String> {
+        (!self.terms.is_empty()).then(|| {
+            self.terms
+                .iter()
+                .map(|term| format!("{term}:*"))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        })
+    }
+}
+
+/// Parses an amount like `$84`, `84.50` or `$1,200`. Bare integers aren't
+/// amounts, since they're as likely to be part of a name.
+fn parse_amount(token: &str) -> Option<AmountRange> {
+    let token = token.trim_end_matches(['.', ',', '?', '!']);
+    let (has_dollar_sign, number) = match token.strip_prefix('$') {
+        Some(number) => (true, number),
+        None => (false, token),
+    };
+    let number = number.replace(',', "");
+
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+
+    let value: f64 = number.parse().ok()?;
+
+    match number.split_once('.') {
+        Some((_, cents)) if !cents.is_empty() => Some(AmountRange {
+            min: value - 0.005,
+            max: value + 0.005,
+        }),
+        // "$84" matches anything from $84.00 up to $84.99.
+        _ if has_dollar_sign => Some(AmountRange {
+            min: value,
+            max: value + 1.0,
+        }),
+        _ => None,
+    }
+}
+
+/// Splits a search like "that $84 thing at Costco" into amounts and words.
+pub fn parse_search_query(query: &str) -> ParsedSearch {
+    let mut parsed = ParsedSearch::default();
+
+    for token in query.split_whitespace() {
+        if let Some(amount) = parse_amount(token) {
+            parsed.amounts.push(amount);
+            continue;
+        }
+
+        for term in token
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|term| !term.is_empty())
+        {
+            let term = term.to_lowercase();
+
+            if !parsed.terms.contains(&term) {
+                parsed.terms.push(term);
+            }
+        }
+    }
+
+    parsed
+}
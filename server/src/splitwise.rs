@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::import::{
+    ImportError, ImportLineItem, ImportRow, ImportRowError, MAX_IMPORT_ROWS, csv_reader,
+    record_line,
+};
+use crate::models::{ExpenseCategory, SplitMethod};
+
+/// The columns every Splitwise group export starts with. Each column after
+/// these belongs to one member of the group.
+pub const SPLITWISE_COLUMNS: [&str; 5] = ["Date", "Description", "Category", "Cost", "Currency"];
+
+const TOTAL_BALANCE_DESCRIPTION: &str = "Total balance";
+const PAYMENT_CATEGORY: &str = "Payment";
+const BALANCE_TOLERANCE: f64 = 0.005;
+
+/// One row of a Splitwise export. `balances` holds each member's net
+/// change for the row, in the order of the export's member columns: what
+/// they paid minus their share.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitwiseExpense {
+    pub row: usize,
+    pub incurred_on: NaiveDate,
+    pub description: String,
+    pub category: String,
+    pub cost: f64,
+    pub currency: String,
+    pub balances: Vec<f64>,
+}
+
+#[derive(Debug, Default)]
+pub struct SplitwiseExport {
+    pub members: Vec<String>,
+    pub expenses: Vec<SplitwiseExpense>,
+    /// Payments between members. A settlement here settles every open
+    /// expense rather than a set amount, so these aren't imported.
+    pub payments: Vec<SplitwiseExpense>,
+    /// The export's closing "Total balance" row, if it has one.
+    pub totals: Option<Vec<f64>>,
+    pub errors: Vec<ImportRowError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SplitwiseBalanceCheck {
+    pub name: String,
+    pub member_id: uuid::Uuid,
+    /// The member's balance according to the export's totals, leaving out
+    /// payments.
+    pub expected: f64,
+    /// The member's balance from the expenses that would be imported.
+    pub imported: f64,
+    pub matches: bool,
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .replace(',', "")
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+}
+
+/// Maps Splitwise's categories onto built-in categories. Unknown
+/// categories, including "General", become miscellaneous.
+pub fn map_splitwise_category(category: &str) -> ExpenseCategory {
+    match category.trim().to_lowercase().as_str() {
+        "groceries" => ExpenseCategory::Groceries,
+        "dining out" | "liquor" | "food and drink" => ExpenseCategory::FoodDining,
+        "rent" | "mortgage" | "home" => ExpenseCategory::HousingRent,
+        "household supplies" | "furniture" | "maintenance" | "cleaning" => {
+            ExpenseCategory::HomeHouseholdSupplies
+        }
+        "electricity" | "heat/gas" | "water" | "trash" | "tv/phone/internet" | "utilities" => {
+            ExpenseCategory::Utilities
+        }
+        "pets" => ExpenseCategory::Pets,
+        "services" => ExpenseCategory::ProfessionalServices,
+        "electronics" | "clothing" => ExpenseCategory::Shopping,
+        "car" | "gas/fuel" | "parking" | "bus/train" | "taxi" | "bicycle" | "transportation" => {
+            ExpenseCategory::Transportation
+        }
+        "hotel" | "plane" => ExpenseCategory::Travel,
+        "games" | "movies" | "music" | "entertainment" => ExpenseCategory::Entertainment,
+        "sports" => ExpenseCategory::Fitness,
+        "medical expenses" => ExpenseCategory::Healthcare,
+        "taxes" => ExpenseCategory::Taxes,
+        "insurance" => ExpenseCategory::Insurance,
+        "education" => ExpenseCategory::Education,
+        "childcare" => ExpenseCategory::Childcare,
+        "gifts" => ExpenseCategory::Gifts,
+        _ => ExpenseCategory::Miscellaneous,
+    }
+}
+
+/// Reads a Splitwise group export. Rows that can't be read are reported
+/// rather than stopping the import.
+pub fn parse_splitwise_export(data: &str) -> Result<SplitwiseExport, ImportError> {
+    let data = data.trim_start_matches('\u{feff}');
+    let mut reader = csv_reader(data);
+
+    let headers = reader
+        .headers()
+        .map_err(|e| ImportError::Csv(e.to_string()))?
+        .clone();
+
+    let is_splitwise_export = headers.len() > SPLITWISE_COLUMNS.len()
+        && SPLITWISE_COLUMNS
+            .iter()
+            .zip(headers.iter())
+            .all(|(expected, header)| expected.eq_ignore_ascii_case(header));
+
+    if !is_splitwise_export {
+        return Err(ImportError::Csv(format!(
+            "expected a Splitwise export with the columns {} followed by one column per member",
+            SPLITWISE_COLUMNS.join(", ")
+        )));
+    }
+
+    let mut export = SplitwiseExport {
+        members: headers
+            .iter()
+            .skip(SPLITWISE_COLUMNS.len())
+            .map(str::to_string)
+            .collect(),
+        ..Default::default()
+    };
+
+    for record in reader.records() {
+        let record = record.map_err(|e| ImportError::Csv(e.to_string()))?;
+
+        if record.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+
+        if export.expenses.len() + export.payments.len() + export.errors.len() >= MAX_IMPORT_ROWS {
+            return Err(ImportError::TooManyRows);
+        }
+
+        let row = record_line(data, &record);
+
+        let field = |index: usize| record.get(index).unwrap_or_default();
+        let error = |column: Option<&str>, message: String| ImportRowError {
+            row,
+            column: column.map(str::to_string),
+            message,
+        };
+
+        let balances: Option<Vec<f64>> = (0..export.members.len())
+            .map(|index| {
+                let value = field(SPLITWISE_COLUMNS.len() + index);
+                if value.is_empty() {
+                    Some(0.0)
+                } else {
+                    parse_number(value)
+                }
+            })
+            .collect();
+
+        let Some(balances) = balances else {
+            export
+                .errors
+                .push(error(None, "member balances must be numbers".to_string()));
+            continue;
+        };
+
+        if field(1).eq_ignore_ascii_case(TOTAL_BALANCE_DESCRIPTION) {
+            export.totals = Some(balances);
+            continue;
+        }
+
+        let Ok(incurred_on) = NaiveDate::parse_from_str(field(0), "%Y-%m-%d") else {
+            export.errors.push(error(
+                Some("Date"),
+                format!("\"{}\" is not a date", field(0)),
+            ));
+            continue;
+        };
+
+        let cost = match parse_number(field(3)) {
+            Some(cost) if cost > 0.0 => round_cents(cost),
+            _ => {
+                export.errors.push(error(
+                    Some("Cost"),
+                    format!("\"{}\" is not a positive amount", field(3)),
+                ));
+                continue;
+            }
+        };
+
+        let net: f64 = balances.iter().sum();
+        if net.abs() > 0.01 {
+            export.errors.push(error(
+                None,
+                format!("member balances add up to {}, not 0", round_cents(net)),
+            ));
+            continue;
+        }
+
+        let currency = field(4);
+        let expense = SplitwiseExpense {
+            row,
+            incurred_on,
+            description: field(1).to_string(),
+            category: field(2).to_string(),
+            cost,
+            currency: currency.to_string(),
+            balances,
+        };
+
+        if expense.category.eq_ignore_ascii_case(PAYMENT_CATEGORY) {
+            export.payments.push(expense);
+            continue;
+        }
+
+        if let Some(first) = export.expenses.first()
+            && !first.currency.eq_ignore_ascii_case(currency)
+        {
+            export.errors.push(error(
+                Some("Currency"),
+                format!(
+                    "expenses in {currency} can't be imported alongside expenses in {}",
+                    first.currency
+                ),
+            ));
+            continue;
+        }
+
+        export.expenses.push(expense);
+    }
+
+    if export.expenses.is_empty() && export.payments.is_empty() && export.errors.is_empty() {
+        return Err(ImportError::NoRows);
+    }
+
+    Ok(export)
+}
+
+/// Recreates a Splitwise row as expenses. A row with one payer becomes one
+/// expense whose line items are everyone's exact share, including the
+/// payer's own. Splitwise also allows several payers, which a pool expense
+/// can't express, so those rows become one expense per payer, each owed a
+/// proportional part of what the others owe. The export only has net
+/// balances, so the payers' own shares are split in the same proportion,
+/// which keeps the expenses adding up to the row's cost. Balances come out
+/// the same either way.
+pub fn splitwise_expense_rows(
+    expense: &SplitwiseExpense,
+    member_ids: &[uuid::Uuid],
+) -> Vec<ImportRow> {
+    let category = map_splitwise_category(&expense.category);
+
+    let balances: Vec<(uuid::Uuid, f64)> = member_ids
+        .iter()
+        .copied()
+        .zip(expense.balances.iter().copied())
+        .filter(|(_, balance)| balance.abs() >= BALANCE_TOLERANCE)
+        .collect();
+
+    let creditors: Vec<(uuid::Uuid, f64)> = balances
+        .iter()
+        .copied()
+        .filter(|(_, balance)| *balance > 0.0)
+        .collect();
+    let debtors: Vec<(uuid::Uuid, f64)> = balances
+        .iter()
+        .copied()
+        .filter(|(_, balance)| *balance < 0.0)
+        .collect();
+
+    let credited: f64 = creditors.iter().map(|(_, balance)| balance).sum();
+
+    let row =
+        |paid_by_member_id: uuid::Uuid, amount: f64, line_items: Vec<ImportLineItem>| ImportRow {
+            row: expense.row,
+            incurred_on: expense.incurred_on,
+            name: expense.description.clone(),
+            amount: round_cents(amount),
+            paid_by_member_id,
+            category: Some((category.clone(), None)),
+            split_method: SplitMethod::Amount,
+            line_items,
+        };
+
+    match creditors.as_slice() {
+        [] => Vec::new(),
+        [(payer_id, payer_balance)] => {
+            let mut line_items: Vec<ImportLineItem> = debtors
+                .iter()
+                .map(|(member_id, balance)| ImportLineItem {
+                    debtor_member_id: *member_id,
+                    amount: round_cents(-balance),
+                })
+                .collect();
+
+            // Payments come out with no share for the payer, since the
+            // payer's balance is the whole cost.
+            let payer_share = round_cents(expense.cost - payer_balance);
+            if payer_share >= BALANCE_TOLERANCE {
+                line_items.insert(
+                    0,
+                    ImportLineItem {
+                        debtor_member_id: *payer_id,
+                        amount: payer_share,
+                    },
+                );
+            }
+
+            vec![row(*payer_id, expense.cost, line_items)]
+        }
+        _ => {
+            let mut remaining = expense.cost;
+
+            creditors
+                .iter()
+                .enumerate()
+                .map(|(index, (payer_id, payer_balance))| {
+                    let portion = payer_balance / credited;
+
+                    // The last payer takes what's left, so rounding can't
+                    // leave the expenses a cent off the cost.
+                    let amount = if index == creditors.len() - 1 {
+                        round_cents(remaining)
+                    } else {
+                        round_cents(expense.cost * portion)
+                    };
+                    remaining -= amount;
+
+                    let mut line_items: Vec<ImportLineItem> = debtors
+                        .iter()
+                        .map(|(member_id, balance)| ImportLineItem {
+                            debtor_member_id: *member_id,
+                            amount: round_cents(-balance * portion),
+                        })
+                        .collect();
+
+                    let owed: f64 = line_items.iter().map(|item| item.amount).sum();
+                    let payer_share = round_cents(amount - owed);
+                    if payer_share >= BALANCE_TOLERANCE {
+                        line_items.insert(
+                            0,
+                            ImportLineItem {
+                                debtor_member_id: *payer_id,
+                                amount: payer_share,
+                            },
+                        );
+                    }
+
+                    row(*payer_id, amount, line_items)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Each member's balance across the rows: what others owe them on the
+/// expenses they paid, minus what they owe on everyone else's.
+pub fn imported_balances(rows: &[ImportRow], member_ids: &[uuid::Uuid]) -> Vec<f64> {
+    member_ids
+        .iter()
+        .map(|member_id| {
+            let balance: f64 = rows
+                .iter()
+                .flat_map(|row| {
+                    row.line_items
+                        .iter()
+                        .filter(move |item| item.debtor_member_id != row.paid_by_member_id)
+                        .map(move |item| {
+                            if row.paid_by_member_id == *member_id {
+                                item.amount
+                            } else if item.debtor_member_id == *member_id {
+                                -item.amount
+                            } else {
+                                0.0
+                            }
+                        })
+                })
+                .sum();
+
+            round_cents(balance)
+        })
+        .collect()
+}
+
+/// Compares imported balances with the export's totals, or with the sum of
+/// its rows when it has no totals row. Payments aren't imported, so they're
+/// taken back out of the totals.
+pub fn check_balances(
+    export: &SplitwiseExport,
+    rows: &[ImportRow],
+    member_ids: &[uuid::Uuid],
+) -> Vec<SplitwiseBalanceCheck> {
+    let sum = |expenses: &[SplitwiseExpense], index: usize| -> f64 {
+        expenses.iter().map(|expense| expense.balances[index]).sum()
+    };
+
+    let expected: Vec<f64> = (0..export.members.len())
+        .map(|index| match &export.totals {
+            Some(totals) => totals[index] - sum(&export.payments, index),
+            None => sum(&export.expenses, index),
+        })
+        .collect();
+
+    export
+        .members
+        .iter()
+        .zip(member_ids)
+        .zip(expected)
+        .zip(imported_balances(rows, member_ids))
+        .map(|(((name, member_id), expected), imported)| {
+            let expected = round_cents(expected);
+
+            SplitwiseBalanceCheck {
+                name: name.clone(),
+                member_id: *member_id,
+                expected,
+                imported,
+                matches: (expected - imported).abs() <= 0.01 + BALANCE_TOLERANCE,
+            }
+        })
+        .collect()
+}
+
+/// The first two export members mapped to the same pool member, if any.
+/// Their balances would be merged into one, so the mapping is rejected.
+pub fn find_duplicate_mapping<'a>(
+    mappings: impl IntoIterator<Item = (&'a str, uuid::Uuid)>,
+) -> Option<(&'a str, &'a str)> {
+    let mut seen: HashMap<uuid::Uuid, &'a str> = HashMap::new();
+
+    for (name, member_id) in mappings {
+        if let Some(first) = seen.insert(member_id, name) {
+            return Some((first, name));
+        }
+    }
+
+    None
+}
@@ -0,0 +1,347 @@
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("blob not found: {0}")]
+    NotFound(String),
+    #[error("invalid blob key: {0}")]
+    InvalidKey(String),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("http error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("unexpected response from object store: {0}")]
+    UnexpectedResponse(String),
+}
+
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, content_type: &str, bytes: Vec<u8>) -> Result<(), StorageError>;
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+}
+
+/// Builds the blob store configured by `BLOB_STORE` (`local` or `s3`).
+pub fn blob_store_from_env() -> Box<dyn BlobStore> {
+    let backend = std::env::var("BLOB_STORE").unwrap_or("local".to_string());
+
+    match backend.as_str() {
+        "s3" => Box::new(S3BlobStore::from_env()),
+        _ => {
+            let root =
+                std::env::var("BLOB_STORE_LOCAL_PATH").unwrap_or("./attachments".to_string());
+            Box::new(LocalBlobStore::new(root))
+        }
+    }
+}
+
+/// The content type of a file, judged from its leading bytes rather than
+/// whatever the uploader claimed. Only the image and PDF formats attachments
+/// may be are recognized.
+pub fn sniff_content_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some("image/jpeg");
+    }
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some("image/png");
+    }
+
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+
+    if bytes.starts_with(b"%PDF-") {
+        return Some("application/pdf");
+    }
+
+    // HEIF files open with an `ftyp` box naming the file's major brand.
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        return match &bytes[8..12] {
+            b"heic" | b"heix" | b"heim" | b"heis" | b"hevc" | b"hevx" | b"hevm" | b"hevs" => {
+                Some("image/heic")
+            }
+            b"mif1" | b"msf1" | b"heif" => Some("image/heif"),
+            _ => None,
+        };
+    }
+
+    None
+}
+
+fn validate_key(key: &str) -> Result<(), StorageError> {
+    let is_valid = !key.is_empty()
+        && Path::new(key)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidKey(key.to_string()))
+    }
+}
+
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf, StorageError> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(
+        &self,
+        key: &str,
+        _content_type: &str,
+        bytes: Vec<u8>,
+    ) -> Result<(), StorageError> {
+        let path = self.path_for(key)?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        tokio::fs::write(path, bytes).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let path = self.path_for(key)?;
+
+        match tokio::fs::read(path).await {
+            Ok(bytes) => Ok(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(StorageError::NotFound(key.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let path = self.path_for(key)?;
+
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+/// An S3-compatible store (AWS S3, MinIO, R2, ...) using path-style addressing
+/// and SigV4-signed requests.
+pub struct S3BlobStore {
+    client: reqwest::Client,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    credentials: S3Credentials,
+}
+
+impl S3BlobStore {
+    pub fn new(endpoint: &str, bucket: &str, region: &str, credentials: S3Credentials) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: bucket.to_string(),
+            region: region.to_string(),
+            credentials,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let endpoint = std::env::var("S3_ENDPOINT").expect("S3_ENDPOINT must be set");
+        let bucket = std::env::var("S3_BUCKET").expect("S3_BUCKET must be set");
+        let region = std::env::var("S3_REGION").unwrap_or("us-east-1".to_string());
+        let credentials = S3Credentials {
+            access_key_id: std::env::var("S3_ACCESS_KEY_ID").expect("S3_ACCESS_KEY_ID must be set"),
+            secret_access_key: std::env::var("S3_SECRET_ACCESS_KEY")
+                .expect("S3_SECRET_ACCESS_KEY must be set"),
+        };
+
+        Self::new(&endpoint, &bucket, &region, credentials)
+    }
+
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        key: &str,
+        content_type: Option<&str>,
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response, StorageError> {
+        validate_key(key)?;
+
+        let url = reqwest::Url::parse(&format!("{}/{}/{}", self.endpoint, self.bucket, key))
+            .map_err(|e| StorageError::UnexpectedResponse(e.to_string()))?;
+
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let now = Utc::now();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        let mut headers = vec![
+            ("host".to_string(), host),
+            ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+            (
+                "x-amz-date".to_string(),
+                now.format("%Y%m%dT%H%M%SZ").to_string(),
+            ),
+        ];
+
+        if let Some(content_type) = content_type {
+            headers.push(("content-type".to_string(), content_type.to_string()));
+        }
+
+        let authorization = sign_v4(
+            method.as_str(),
+            url.path(),
+            &headers,
+            &payload_hash,
+            &self.credentials,
+            &self.region,
+            now,
+        );
+
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("authorization", authorization)
+            .body(body);
+
+        for (name, value) in headers.into_iter().filter(|(name, _)| name != "host") {
+            request = request.header(name, value);
+        }
+
+        Ok(request.send().await?)
+    }
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, content_type: &str, bytes: Vec<u8>) -> Result<(), StorageError> {
+        let response = self
+            .send(reqwest::Method::PUT, key, Some(content_type), bytes)
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(StorageError::UnexpectedResponse(response.text().await?))
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let response = self
+            .send(reqwest::Method::GET, key, None, Vec::new())
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(response.bytes().await?.to_vec()),
+            reqwest::StatusCode::NOT_FOUND => Err(StorageError::NotFound(key.to_string())),
+            _ => Err(StorageError::UnexpectedResponse(response.text().await?)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let response = self
+            .send(reqwest::Method::DELETE, key, None, Vec::new())
+            .await?;
+
+        if response.status().is_success() || response.status() == reqwest::StatusCode::NOT_FOUND {
+            Ok(())
+        } else {
+            Err(StorageError::UnexpectedResponse(response.text().await?))
+        }
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Computes the SigV4 `Authorization` header for an S3 request. `headers` must
+/// contain every header to be signed, including `host`.
+pub fn sign_v4(
+    method: &str,
+    path: &str,
+    headers: &[(String, String)],
+    payload_hash: &str,
+    credentials: &S3Credentials,
+    region: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    let mut sorted_headers: Vec<(String, String)> = headers
+        .iter()
+        .map(|(name, value)| (name.to_lowercase(), value.trim().to_string()))
+        .collect();
+    sorted_headers.sort();
+
+    let canonical_headers: String = sorted_headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value))
+        .collect();
+
+    let signed_headers = sorted_headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<&str>>()
+        .join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n\n{}\n{}\n{}",
+        method, path, canonical_headers, signed_headers, payload_hash
+    );
+
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let signing_key = [region, "s3", "aws4_request"].iter().fold(
+        hmac_sha256(
+            format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+            &date,
+        ),
+        |key, part| hmac_sha256(&key, part),
+    );
+
+    let signature = hex::encode(hmac_sha256(&signing_key, &string_to_sign));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key_id, scope, signed_headers, signature
+    )
+}
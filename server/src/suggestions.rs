@@ -0,0 +1,238 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::models::{
+    CategoryNameCount, Expense, ExpenseCategory, ExpenseCategoryRule, NewExpenseCategoryRule,
+    RuleMatchType,
+};
+use crate::rules::rule_matches;
+
+/// Splits an expense name into lowercase alphanumeric tokens, dropping
+/// single characters and pure numbers (store numbers, dates) that carry no
+/// signal about the category.
+pub fn tokenize(name: &str) -> Vec<String> {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| token.chars().count() > 1 && !token.chars().all(|c| c.is_ascii_digit()))
+        .map(str::to_string)
+        .collect()
+}
+
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[derive(Debug, Serialize, ToSchema, PartialEq)]
+pub struct CategorySuggestion {
+    pub category: ExpenseCategory,
+    /// Share of the probability mass, between 0 and 1.
+    pub score: f64,
+}
+
+/// A naive Bayes classifier over expense-name tokens, trained on past
+/// expenses. Observations carry a weight so a member's own history can count
+/// for more than the rest of the pool's.
+#[derive(Default)]
+pub struct CategorySuggester {
+    token_weights: HashMap<ExpenseCategory, HashMap<String, f64>>,
+    token_totals: HashMap<ExpenseCategory, f64>,
+    category_weights: HashMap<ExpenseCategory, f64>,
+    exact_names: HashMap<String, HashMap<ExpenseCategory, f64>>,
+    vocabulary: HashSet<String>,
+}
+
+impl CategorySuggester {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trains on how expense names were categorized. A member's own
+    /// categorizations count double compared to the rest of the pool's.
+    pub fn from_history(history: &[CategoryNameCount]) -> Self {
+        let mut suggester = Self::new();
+
+        for entry in history {
+            let weight = if entry.is_own { 2.0 } else { 1.0 };
+
+            suggester.observe(
+                &entry.name,
+                &entry.category,
+                weight * entry.occurrences as f64,
+            );
+        }
+
+        suggester
+    }
+
+    pub fn observe(&mut self, name: &str, category: &ExpenseCategory, weight: f64) {
+        let tokens = tokenize(name);
+
+        if tokens.is_empty() {
+            return;
+        }
+
+        *self.category_weights.entry(category.clone()).or_default() += weight;
+
+        *self
+            .exact_names
+            .entry(normalize_name(name))
+            .or_default()
+            .entry(category.clone())
+            .or_default() += weight;
+
+        let token_weights = self.token_weights.entry(category.clone()).or_default();
+
+        for token in tokens {
+            *token_weights.entry(token.clone()).or_default() += weight;
+            *self.token_totals.entry(category.clone()).or_default() += weight;
+            self.vocabulary.insert(token);
+        }
+    }
+
+    /// Ranks categories for a new expense name, best first. Returns nothing
+    /// when none of the name's tokens have been seen before.
+    pub fn suggest(&self, name: &str, limit: usize) -> Vec<CategorySuggestion> {
+        let tokens: Vec<String> = tokenize(name)
+            .into_iter()
+            .filter(|token| self.vocabulary.contains(token))
+            .collect();
+
+        if tokens.is_empty() {
+            return Vec::new();
+        }
+
+        let total_weight: f64 = self.category_weights.values().sum();
+        let vocabulary_size = self.vocabulary.len() as f64;
+
+        let log_likelihoods: Vec<(ExpenseCategory, f64)> = self
+            .category_weights
+            .iter()
+            .map(|(category, weight)| {
+                let token_weights = &self.token_weights[category];
+                let token_total = self.token_totals[category];
+
+                let log_likelihood = (weight / total_weight).ln()
+                    + tokens
+                        .iter()
+                        .map(|token| {
+                            let count = token_weights.get(token).copied().unwrap_or(0.0);
+                            ((count + 1.0) / (token_total + vocabulary_size)).ln()
+                        })
+                        .sum::<f64>();
+
+                (category.clone(), log_likelihood)
+            })
+            .collect();
+
+        let max = log_likelihoods
+            .iter()
+            .map(|(_, l)| *l)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let normalizer: f64 = log_likelihoods.iter().map(|(_, l)| (l - max).exp()).sum();
+
+        let mut scores: HashMap<ExpenseCategory, f64> = log_likelihoods
+            .into_iter()
+            .map(|(category, l)| (category, (l - max).exp() / normalizer))
+            .collect();
+
+        // Blend in how this exact name was categorized before, if ever.
+        if let Some(exact) = self.exact_names.get(&normalize_name(name)) {
+            let exact_total: f64 = exact.values().sum();
+
+            for score in scores.values_mut() {
+                *score *= 0.5;
+            }
+
+            for (category, weight) in exact {
+                *scores.entry(category.clone()).or_default() += 0.5 * weight / exact_total;
+            }
+        }
+
+        let mut suggestions: Vec<CategorySuggestion> = scores
+            .into_iter()
+            .map(|(category, score)| CategorySuggestion { category, score })
+            .collect();
+
+        suggestions.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.category.cmp(&b.category))
+        });
+        suggestions.truncate(limit);
+
+        suggestions
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RuleProposal {
+    pub rule: NewExpenseCategoryRule,
+    /// How many past expenses with this name were given the category.
+    pub occurrences: usize,
+}
+
+/// Proposes an exact-match rule for every expense name the member has
+/// categorized the same way at least `min_occurrences` times, unless one of
+/// their rules already covers it.
+pub fn propose_rules(
+    history: &[Expense],
+    existing_rules: &[ExpenseCategoryRule],
+    min_occurrences: usize,
+) -> Vec<RuleProposal> {
+    let mut by_name: HashMap<String, Vec<&Expense>> = HashMap::new();
+
+    for expense in history {
+        by_name
+            .entry(normalize_name(&expense.name))
+            .or_default()
+            .push(expense);
+    }
+
+    let mut proposals: Vec<RuleProposal> = by_name
+        .into_values()
+        .filter_map(|expenses| {
+            let first = expenses[0];
+            let category = &first.category;
+
+            let is_consistent = expenses.len() >= min_occurrences
+                && expenses.iter().all(|e| {
+                    e.category == *category && e.custom_category_id == first.custom_category_id
+                });
+
+            let is_covered = expenses.iter().any(|expense| {
+                existing_rules
+                    .iter()
+                    .any(|rule| rule_matches(rule, &(*expense).into()))
+            });
+
+            (is_consistent && !is_covered).then(|| RuleProposal {
+                occurrences: expenses.len(),
+                rule: NewExpenseCategoryRule {
+                    rule: first.name.trim().to_string(),
+                    category: category.clone(),
+                    match_type: RuleMatchType::Exact,
+                    min_amount: None,
+                    max_amount: None,
+                    // Custom categories belong to one pool, so the rule
+                    // must be scoped to it.
+                    pool_id: first.custom_category_id.map(|_| first.pool_id),
+                    priority: 0,
+                    custom_category_id: first.custom_category_id,
+                },
+            })
+        })
+        .collect();
+
+    proposals.sort_by(|a, b| {
+        b.occurrences
+            .cmp(&a.occurrences)
+            .then_with(|| a.rule.rule.cmp(&b.rule.rule))
+    });
+
+    proposals
+}
@@ -0,0 +1,62 @@
+use std::collections::HashSet;
+
+pub const TAG_MAX_CHARS: usize = 50;
+pub const TAGS_PER_EXPENSE_MAX: usize = 20;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum TagError {
+    #[error("tags cannot be empty")]
+    EmptyTag,
+    #[error("tags cannot exceed {TAG_MAX_CHARS} characters")]
+    TagTooLong,
+    #[error("an expense cannot have more than {TAGS_PER_EXPENSE_MAX} tags")]
+    TooManyTags,
+}
+
+/// Trims a tag and collapses runs of whitespace, so "  bachelor   party"
+/// and "bachelor party" are the same tag.
+pub fn normalize_tag(tag: &str) -> Result<String, TagError> {
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if tag.is_empty() {
+        return Err(TagError::EmptyTag);
+    }
+
+    if tag.chars().count() > TAG_MAX_CHARS {
+        return Err(TagError::TagTooLong);
+    }
+
+    Ok(tag)
+}
+
+/// Normalizes an expense's tags, dropping case-insensitive duplicates and
+/// keeping the first spelling of each.
+pub fn normalize_tags(tags: &[String]) -> Result<Vec<String>, TagError> {
+    let mut seen = HashSet::new();
+    let mut normalized = Vec::new();
+
+    for tag in tags {
+        let tag = normalize_tag(tag)?;
+
+        if seen.insert(tag.to_lowercase()) {
+            normalized.push(tag);
+        }
+    }
+
+    if normalized.len() > TAGS_PER_EXPENSE_MAX {
+        return Err(TagError::TooManyTags);
+    }
+
+    Ok(normalized)
+}
+
+/// Parses a comma-separated tag filter, skipping blank entries.
+pub fn parse_tag_filter(filter: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+
+    filter
+        .split(',')
+        .filter_map(|tag| normalize_tag(tag).ok())
+        .filter(|tag| seen.insert(tag.to_lowercase()))
+        .collect()
+}
@@ -0,0 +1,447 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use diesel::pg::PgConnection;
+use diesel::{Connection, QueryResult};
+use once_cell::sync::Lazy;
+use opentelemetry::KeyValue;
+use opentelemetry::trace::{Span, SpanKind, Tracer};
+use server::compute_balances_for_member;
+use server::mail::Email;
+use server::models::{
+    EmailDigest, Expense, Member, PaymentDirection, PaymentReminder, Pool, PoolActivity,
+    WebhookDelivery,
+};
+use server::realtime::{POOL_EVENTS_CHANNEL, PgListener, PoolFeedMessage, PoolNotification};
+use server::reminders::{
+    Creditor, ReminderSettings, amount_owed, digest_interval, payment_reminder_email,
+    weekly_digest_email,
+};
+use server::webhooks::{DeliveryOutcome, deliver, retry_delay, webhook_client};
+
+use crate::handlers::{
+    BLOB_STORE, MAILER, POOL_EVENTS, WEBHOOK_ALLOWED_HOSTS, get_db_connection, get_tracer,
+};
+
+const PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+const WEBHOOK_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+const WEBHOOK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+const WEBHOOK_BATCH_SIZE: i64 = 50;
+const POOL_EVENTS_RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+const EMAIL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+fn trash_retention_days() -> i64 {
+    std::env::var("EXPENSE_TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(30)
+}
+
+/// Periodically removes expenses that have sat in a pool's trash for longer
+/// than `EXPENSE_TRASH_RETENTION_DAYS`.
+pub async fn purge_deleted_expenses_task() {
+    let mut interval = tokio::time::interval(PURGE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = purge_deleted_expenses().await {
+            tracing::error!("Failed to purge deleted expenses: {}", e);
+        }
+    }
+}
+
+async fn purge_deleted_expenses() -> Result<(), anyhow::Error> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("purge_deleted_expenses")
+        .with_kind(SpanKind::Internal)
+        .start(tracer);
+
+    let cutoff = Utc::now() - Duration::days(trash_retention_days());
+
+    let mut conn = get_db_connection().await?;
+
+    let (purged, attachments) =
+        tokio::task::spawn_blocking(move || Expense::purge_deleted_before(&mut conn, cutoff))
+            .await??;
+
+    for attachment in attachments {
+        if let Err(e) = BLOB_STORE.delete(&attachment.storage_key).await {
+            tracing::error!(
+                "Failed to delete attachment blob {}: {}",
+                attachment.storage_key,
+                e
+            );
+        }
+    }
+
+    span.set_attribute(KeyValue::new("purged_expenses", purged as i64));
+    span.end();
+
+    if purged > 0 {
+        tracing::info!("Purged {} deleted expenses", purged);
+    }
+
+    Ok(())
+}
+
+/// Sends queued webhook deliveries as they come due. Safe to run on every
+/// replica, since each claims its own batch.
+pub async fn deliver_webhooks_task() {
+    let client = webhook_client(WEBHOOK_TIMEOUT, &WEBHOOK_ALLOWED_HOSTS)
+        .expect("Failed to build webhook HTTP client");
+    let mut interval = tokio::time::interval(WEBHOOK_POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = deliver_webhooks(&client).await {
+            tracing::error!("Failed to deliver webhooks: {}", e);
+        }
+    }
+}
+
+async fn deliver_webhooks(client: &reqwest::Client) -> Result<(), anyhow::Error> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("deliver_webhooks")
+        .with_kind(SpanKind::Internal)
+        .start(tracer);
+
+    // Long enough for a whole batch to time out one request after another.
+    let lease = Duration::from_std(WEBHOOK_TIMEOUT * (WEBHOOK_BATCH_SIZE as u32 + 1))?;
+
+    let mut conn = get_db_connection().await?;
+
+    let due = tokio::task::spawn_blocking(move || {
+        WebhookDelivery::claim_due(&mut conn, WEBHOOK_BATCH_SIZE, lease)
+    })
+    .await??;
+
+    let mut delivered = 0;
+
+    for (delivery, webhook) in &due {
+        let outcome = deliver(
+            client,
+            &WEBHOOK_ALLOWED_HOSTS,
+            &webhook.url,
+            &webhook.secret,
+            delivery.id,
+            delivery.event,
+            &delivery.payload,
+        )
+        .await;
+
+        let delivery_id = delivery.id;
+        let attempts = delivery.attempts + 1;
+        let mut conn = get_db_connection().await?;
+
+        match outcome {
+            DeliveryOutcome::Delivered { status } => {
+                delivered += 1;
+                tokio::task::spawn_blocking(move || {
+                    WebhookDelivery::mark_delivered(&mut conn, delivery_id, status as i32)
+                })
+                .await??;
+            }
+            DeliveryOutcome::Failed { status, error } => {
+                tracing::warn!("Webhook delivery {} failed: {}", delivery_id, error);
+                tokio::task::spawn_blocking(move || {
+                    WebhookDelivery::mark_failed(
+                        &mut conn,
+                        delivery_id,
+                        status.map(|status| status as i32),
+                        &error,
+                        retry_delay(attempts).map(|delay| Utc::now() + delay),
+                    )
+                })
+                .await??;
+            }
+        }
+    }
+
+    span.set_attribute(KeyValue::new("claimed_deliveries", due.len() as i64));
+    span.set_attribute(KeyValue::new("delivered", delivered as i64));
+    span.end();
+
+    Ok(())
+}
+
+/// Listens for pool changes made through any replica and passes them on to
+/// the event streams open on this one, reconnecting if the connection drops.
+pub async fn listen_for_pool_events_task() {
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let mut has_connected = false;
+
+    loop {
+        if let Err(e) = listen_for_pool_events(&database_url, &mut has_connected).await {
+            tracing::error!("Pool event listener failed: {}", e);
+        }
+
+        tokio::time::sleep(POOL_EVENTS_RECONNECT_DELAY).await;
+    }
+}
+
+async fn listen_for_pool_events(
+    database_url: &str,
+    has_connected: &mut bool,
+) -> Result<(), anyhow::Error> {
+    let database_url = database_url.to_string();
+    let mut listener = tokio::task::spawn_blocking(move || {
+        PgListener::connect(&database_url, POOL_EVENTS_CHANNEL)
+    })
+    .await??;
+
+    // Declared after the listener so it's dropped, and stops watching the
+    // socket, before the listener closes it.
+    let socket = tokio::io::unix::AsyncFd::new(listener.socket())?;
+
+    if *has_connected {
+        let _ = POOL_EVENTS.send(PoolFeedMessage::Reconnected);
+    }
+
+    *has_connected = true;
+
+    loop {
+        // Clearing readiness before reading means anything arriving
+        // mid-read wakes the next iteration instead of being missed.
+        socket.readable().await?.clear_ready();
+
+        for payload in listener.read_notifications()? {
+            match PoolNotification::parse(&payload) {
+                Some(notification) => {
+                    let _ = POOL_EVENTS.send(PoolFeedMessage::Notification(notification));
+                }
+                None => tracing::warn!("Ignoring malformed pool notification: {}", payload),
+            }
+        }
+    }
+}
+
+/// Emails payment reminders to members who've owed more than the threshold
+/// for too long, and weekly digests if they're enabled. Safe to run on every
+/// replica, since each email is claimed before it's sent.
+pub async fn send_emails_task() {
+    let settings = ReminderSettings::from_env();
+    // Set the mailer up now, so a missing MAILER is warned about at startup
+    // rather than when the first reminder comes due.
+    Lazy::force(&MAILER);
+    let mut interval = tokio::time::interval(EMAIL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = send_payment_reminders(&settings).await {
+            tracing::error!("Failed to send payment reminders: {}", e);
+        }
+
+        if settings.weekly_digest
+            && let Err(e) = send_weekly_digests().await
+        {
+            tracing::error!("Failed to send weekly digests: {}", e);
+        }
+    }
+}
+
+async fn send_payment_reminders(settings: &ReminderSettings) -> Result<(), anyhow::Error> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("send_payment_reminders")
+        .with_kind(SpanKind::Internal)
+        .start(tracer);
+
+    let settings = settings.clone();
+    let mut conn = get_db_connection().await?;
+
+    let reminders = tokio::task::spawn_blocking(move || {
+        claim_payment_reminders(&mut conn, &settings, Utc::now())
+    })
+    .await??;
+
+    let mut sent = 0;
+
+    for (pool_id, member_id, email) in &reminders {
+        if let Err(e) = MAILER.send(email).await {
+            tracing::error!("Failed to send payment reminder to {}: {}", member_id, e);
+
+            let (pool_id, member_id) = (*pool_id, *member_id);
+            let mut conn = get_db_connection().await?;
+
+            tokio::task::spawn_blocking(move || {
+                PaymentReminder::release(&mut conn, pool_id, member_id)
+            })
+            .await??;
+        } else {
+            sent += 1;
+        }
+    }
+
+    span.set_attribute(KeyValue::new("claimed_reminders", reminders.len() as i64));
+    span.set_attribute(KeyValue::new("sent", sent as i64));
+    span.end();
+
+    Ok(())
+}
+
+/// Brings every pool's reminder tracking up to date with its balances, then
+/// claims the reminders that are due and writes their emails. Each pool is
+/// handled in its own transaction, so a pool that fails part way releases
+/// its claims without holding up the others.
+fn claim_payment_reminders(
+    conn: &mut PgConnection,
+    settings: &ReminderSettings,
+    now: DateTime<Utc>,
+) -> QueryResult<Vec<(uuid::Uuid, uuid::Uuid, Email)>> {
+    let mut reminders = Vec::new();
+
+    for pool_id in PaymentReminder::pools_to_check(conn)? {
+        match conn.transaction(|conn| claim_pool_payment_reminders(conn, pool_id, settings, now)) {
+            Ok(pool_reminders) => reminders.extend(pool_reminders),
+            Err(e) => tracing::error!(
+                "Failed to claim payment reminders for pool {}: {}",
+                pool_id,
+                e
+            ),
+        }
+    }
+
+    Ok(reminders)
+}
+
+fn claim_pool_payment_reminders(
+    conn: &mut PgConnection,
+    pool_id: uuid::Uuid,
+    settings: &ReminderSettings,
+    now: DateTime<Utc>,
+) -> QueryResult<Vec<(uuid::Uuid, uuid::Uuid, Email)>> {
+    let mut reminders = Vec::new();
+
+    let debts = Expense::list_unpaid_for_balance_computation(conn, pool_id)?;
+    let debtor_ids: HashSet<uuid::Uuid> = debts.iter().map(|debt| debt.from_member_id).collect();
+
+    let mut owing = HashMap::new();
+
+    for member_id in debtor_ids {
+        let balances = compute_balances_for_member(member_id, debts.clone());
+
+        if amount_owed(&balances) > settings.threshold {
+            owing.insert(member_id, balances);
+        }
+    }
+
+    let owing_member_ids: Vec<uuid::Uuid> = owing.keys().copied().collect();
+    PaymentReminder::track(conn, pool_id, &owing_member_ids, now)?;
+
+    let due = PaymentReminder::claim_due(conn, pool_id, now - settings.after, now)?;
+
+    if due.is_empty() {
+        return Ok(reminders);
+    }
+
+    let pool = Pool::find(conn, pool_id)?;
+
+    for reminder in due {
+        let Some(balances) = owing.get(&reminder.member_id) else {
+            continue;
+        };
+
+        let member = Member::find(conn, reminder.member_id)?;
+        let owed: Vec<_> = balances
+            .iter()
+            .filter(|balance| balance.direction == PaymentDirection::Outbound)
+            .collect();
+        let creditor_ids: Vec<uuid::Uuid> = owed.iter().map(|balance| balance.member_id).collect();
+        let creditors: HashMap<uuid::Uuid, Member> = Member::find_many(conn, &creditor_ids)?
+            .into_iter()
+            .map(|creditor| (creditor.id, creditor))
+            .collect();
+
+        let creditors: Vec<Creditor> = owed
+            .iter()
+            .filter_map(|balance| {
+                creditors.get(&balance.member_id).map(|creditor| Creditor {
+                    name: format!("{} {}", creditor.first_name, creditor.last_name),
+                    venmo_handle: creditor.venmo_handle.clone(),
+                    amount: balance.amount,
+                })
+            })
+            .collect();
+
+        reminders.push((
+            pool_id,
+            member.id,
+            payment_reminder_email(&member, &pool.name, reminder.owing_since, &creditors, now),
+        ));
+    }
+
+    Ok(reminders)
+}
+
+async fn send_weekly_digests() -> Result<(), anyhow::Error> {
+    let tracer = get_tracer();
+
+    let mut span = tracer
+        .span_builder("send_weekly_digests")
+        .with_kind(SpanKind::Internal)
+        .start(tracer);
+
+    let mut conn = get_db_connection().await?;
+
+    let digests =
+        tokio::task::spawn_blocking(move || claim_weekly_digests(&mut conn, Utc::now())).await??;
+
+    let mut sent = 0;
+
+    for (member_id, email) in &digests {
+        if let Err(e) = MAILER.send(email).await {
+            tracing::error!("Failed to send weekly digest to {}: {}", member_id, e);
+
+            let member_id = *member_id;
+            let mut conn = get_db_connection().await?;
+
+            tokio::task::spawn_blocking(move || EmailDigest::release(&mut conn, member_id))
+                .await??;
+        } else {
+            sent += 1;
+        }
+    }
+
+    span.set_attribute(KeyValue::new("claimed_digests", digests.len() as i64));
+    span.set_attribute(KeyValue::new("sent", sent as i64));
+    span.end();
+
+    Ok(())
+}
+
+/// Writes the digests of members who are due one and had activity in their
+/// pools, claiming each. Members with nothing to report are checked again on
+/// the next run.
+fn claim_weekly_digests(
+    conn: &mut PgConnection,
+    now: DateTime<Utc>,
+) -> QueryResult<Vec<(uuid::Uuid, Email)>> {
+    let cutoff = now - digest_interval();
+    let mut digests = Vec::new();
+
+    for member_id in EmailDigest::due_member_ids(conn, cutoff)? {
+        let activity = PoolActivity::for_member(conn, member_id, cutoff)?;
+
+        if activity.is_empty() {
+            continue;
+        }
+
+        let member = Member::find(conn, member_id)?;
+
+        if let Some(email) = weekly_digest_email(&member, &activity)
+            && EmailDigest::claim(conn, member_id, cutoff, now)?
+        {
+            digests.push((member_id, email));
+        }
+    }
+
+    Ok(digests)
+}
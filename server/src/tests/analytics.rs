@@ -0,0 +1,197 @@
+use crate::analytics::{MemberShare, TimeSeriesInterval, group_time_series};
+use crate::models::{Expense, ExpenseCategory, TimeSeriesRow};
+use crate::schema::expense;
+use crate::tests::db;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn row(
+    bucket: NaiveDate,
+    category: Option<ExpenseCategory>,
+    member_id: Option<Uuid>,
+    amount: f64,
+) -> TimeSeriesRow {
+    TimeSeriesRow {
+        bucket,
+        category,
+        member_id,
+        amount,
+    }
+}
+
+#[test]
+fn test_bucket_count() {
+    let month = TimeSeriesInterval::Month;
+    assert_eq!(month.bucket_count(date(2024, 1, 31), date(2024, 2, 1)), 2);
+    assert_eq!(
+        month.bucket_count(date(2023, 12, 1), date(2024, 11, 30)),
+        12
+    );
+    assert_eq!(month.bucket_count(date(2024, 2, 1), date(2024, 1, 1)), 0);
+
+    let week = TimeSeriesInterval::Week;
+    // 2024-01-07 is a Sunday and 2024-01-08 the following Monday.
+    assert_eq!(week.bucket_count(date(2024, 1, 7), date(2024, 1, 8)), 2);
+    assert_eq!(week.bucket_count(date(2024, 1, 1), date(2024, 1, 7)), 1);
+}
+
+#[test]
+fn test_group_time_series_splits_totals_and_shares() {
+    let alice = Uuid::from_u128(1);
+    let bob = Uuid::from_u128(2);
+    let groceries = Some(ExpenseCategory::Groceries);
+    let travel = Some(ExpenseCategory::Travel);
+
+    let points = group_time_series(vec![
+        row(date(2024, 1, 1), groceries.clone(), None, 30.0),
+        row(date(2024, 1, 1), groceries.clone(), Some(alice), 10.0),
+        row(date(2024, 1, 1), groceries.clone(), Some(bob), 20.0),
+        row(date(2024, 1, 1), travel.clone(), None, 0.0),
+        row(date(2024, 1, 1), travel.clone(), Some(alice), 0.0),
+        row(date(2024, 1, 1), travel.clone(), Some(bob), 0.0),
+        row(date(2024, 2, 1), groceries.clone(), None, 0.0),
+        row(date(2024, 2, 1), groceries.clone(), Some(alice), 0.0),
+        row(date(2024, 2, 1), groceries.clone(), Some(bob), 0.0),
+    ]);
+
+    assert_eq!(points.len(), 3);
+    assert_eq!(points[0].category, groceries);
+    assert_eq!(points[0].total, 30.0);
+    assert_eq!(
+        points[0].shares,
+        vec![
+            MemberShare {
+                member_id: alice,
+                share: 10.0
+            },
+            MemberShare {
+                member_id: bob,
+                share: 20.0
+            },
+        ]
+    );
+    assert_eq!(points[1].category, travel);
+    assert_eq!(points[1].total, 0.0);
+    assert_eq!(points[2].bucket, date(2024, 2, 1));
+}
+
+#[test]
+fn test_category_totals_split_what_was_paid_from_the_members_share() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let bob = db::member(&mut conn, "Bob");
+    let carol = db::member(&mut conn, "Carol");
+    let home = db::pool(&mut conn, &[&alice, &bob]);
+    let trip = db::pool(&mut conn, &[&alice, &carol]);
+    let other = db::pool(&mut conn, &[&bob, &carol]);
+
+    db::expense(
+        &mut conn,
+        &home,
+        &alice,
+        "Dinner",
+        Some(date(2024, 1, 10)),
+        &[(&alice, 10.0), (&bob, 20.0)],
+    );
+    let (groceries, _) = db::expense(
+        &mut conn,
+        &home,
+        &bob,
+        "Groceries",
+        Some(date(2024, 2, 10)),
+        &[(&alice, 15.0), (&bob, 25.0)],
+    );
+    diesel::update(expense::table.filter(expense::id.eq(groceries.id)))
+        .set(expense::category.eq(ExpenseCategory::Groceries))
+        .execute(&mut conn)
+        .unwrap();
+
+    // Neither paid for nor owed by Alice.
+    db::expense(
+        &mut conn,
+        &home,
+        &bob,
+        "Lunch",
+        Some(date(2024, 1, 12)),
+        &[(&bob, 50.0)],
+    );
+    db::expense(
+        &mut conn,
+        &other,
+        &bob,
+        "Lunch",
+        Some(date(2024, 1, 12)),
+        &[(&alice, 5.0), (&carol, 5.0)],
+    );
+
+    let (trashed, _) = db::expense(
+        &mut conn,
+        &home,
+        &alice,
+        "Takeout",
+        Some(date(2024, 1, 15)),
+        &[(&alice, 50.0), (&bob, 50.0)],
+    );
+    Expense::soft_delete(&mut conn, trashed.id, home.id, alice.id).unwrap();
+
+    db::expense(
+        &mut conn,
+        &trip,
+        &carol,
+        "Tapas",
+        Some(date(2024, 3, 1)),
+        &[(&alice, 6.0), (&carol, 6.0)],
+    );
+
+    let mut totals = |pool_id, since, until| {
+        Expense::category_totals_for_member(&mut conn, alice.id, pool_id, since, until)
+            .unwrap()
+            .into_iter()
+            .map(|total| (total.category, total.expense_count, total.paid, total.share))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        totals(Some(home.id), None, None),
+        vec![
+            (ExpenseCategory::Groceries, 1, 0.0, 15.0),
+            (ExpenseCategory::FoodDining, 1, 30.0, 10.0),
+        ]
+    );
+    assert_eq!(
+        totals(None, None, None),
+        vec![
+            (ExpenseCategory::FoodDining, 2, 30.0, 16.0),
+            (ExpenseCategory::Groceries, 1, 0.0, 15.0),
+        ]
+    );
+    assert_eq!(
+        totals(None, Some(date(2024, 2, 1)), None),
+        vec![
+            (ExpenseCategory::Groceries, 1, 0.0, 15.0),
+            (ExpenseCategory::FoodDining, 1, 0.0, 6.0),
+        ]
+    );
+    assert_eq!(
+        totals(None, None, Some(date(2024, 1, 31))),
+        vec![(ExpenseCategory::FoodDining, 1, 30.0, 10.0)]
+    );
+    assert!(totals(Some(other.id), None, None).is_empty());
+
+    // Bob's view of the same dinner is the other side of the split.
+    let bob_totals =
+        Expense::category_totals_for_member(&mut conn, bob.id, Some(home.id), None, None)
+            .unwrap()
+            .into_iter()
+            .find(|total| total.category == ExpenseCategory::FoodDining)
+            .unwrap();
+    assert_eq!(
+        (bob_totals.expense_count, bob_totals.paid, bob_totals.share),
+        (2, 50.0, 70.0)
+    );
+}
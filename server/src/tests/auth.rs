@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+use crate::auth::{ActiveMembers, AuthError, ensure_active, issue_token, verify_token};
+use crate::models::Member;
+use crate::tests::db;
+
+const SECRET: &[u8] = b"test-secret";
+
+#[test]
+fn test_tokens_are_verified_with_their_secret() {
+    let member_id = Uuid::from_u128(1);
+    let token = issue_token(member_id, SECRET).unwrap();
+
+    assert_eq!(verify_token(&token, SECRET), Ok(member_id));
+    assert_eq!(
+        verify_token(&token, b"other-secret"),
+        Err(AuthError::InvalidToken)
+    );
+    assert_eq!(
+        verify_token("not a token", SECRET),
+        Err(AuthError::InvalidToken)
+    );
+}
+
+#[test]
+fn test_token_issued_before_deletion_is_rejected() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let token = issue_token(alice.id, SECRET).unwrap();
+
+    assert_eq!(ensure_active(&mut conn, alice.id), Ok(()));
+
+    Member::anonymize(&mut conn, alice.id).unwrap();
+
+    // The token itself is still valid; only the account check catches it.
+    let member_id = verify_token(&token, SECRET).unwrap();
+    assert_eq!(ensure_active(&mut conn, member_id), Err(AuthError::Deleted));
+    assert_eq!(
+        ensure_active(&mut conn, Uuid::new_v4()),
+        Err(AuthError::Deleted)
+    );
+}
+
+#[test]
+fn test_active_members_expire_and_can_be_forgotten() {
+    let active = ActiveMembers::new(Duration::from_secs(60));
+    let alice = Uuid::from_u128(1);
+    let bob = Uuid::from_u128(2);
+    let start = Instant::now();
+
+    assert!(!active.contains(alice, start));
+
+    active.insert(alice, start);
+    active.insert(bob, start);
+    assert!(active.contains(alice, start + Duration::from_secs(59)));
+    assert!(!active.contains(alice, start + Duration::from_secs(60)));
+
+    active.forget(bob);
+    assert!(!active.contains(bob, start));
+    assert!(active.contains(alice, start));
+}
@@ -0,0 +1,122 @@
+use chrono::Utc;
+
+use crate::backup::{
+    BACKUP_FORMAT, BACKUP_VERSION, BackupError, BackupHeader, BackupTable, known_schema_versions,
+    quote_ident, restore_order,
+};
+
+fn names(tables: &[&str]) -> Vec<String> {
+    tables.iter().map(|table| table.to_string()).collect()
+}
+
+fn foreign_keys(keys: &[(&str, &str)]) -> Vec<(String, String)> {
+    keys.iter()
+        .map(|(table, references)| (table.to_string(), references.to_string()))
+        .collect()
+}
+
+fn header(version: u32, schema_version: &str) -> BackupHeader {
+    BackupHeader {
+        format: BACKUP_FORMAT.to_string(),
+        version,
+        schema_version: schema_version.to_string(),
+        created_at: Utc::now(),
+        tables: vec![BackupTable {
+            name: "member".to_string(),
+            rows: 2,
+        }],
+    }
+}
+
+#[test]
+fn test_restore_order_puts_referenced_tables_first() {
+    let order = restore_order(
+        &names(&[
+            "expense_line_item",
+            "expense",
+            "pool",
+            "member",
+            "friendship",
+        ]),
+        &foreign_keys(&[
+            ("expense_line_item", "expense"),
+            ("expense_line_item", "member"),
+            ("expense", "pool"),
+            ("expense", "member"),
+            ("friendship", "member"),
+            ("friendship", "friendship"),
+            ("expense", "expense_p_is_settled_true"),
+        ]),
+    )
+    .unwrap();
+
+    assert_eq!(
+        order,
+        names(&[
+            "member",
+            "pool",
+            "expense",
+            "friendship",
+            "expense_line_item"
+        ])
+    );
+}
+
+#[test]
+fn test_restore_order_rejects_cycles() {
+    let result = restore_order(
+        &names(&["a", "b", "c"]),
+        &foreign_keys(&[("a", "b"), ("b", "a")]),
+    );
+
+    assert!(matches!(result, Err(BackupError::ForeignKeyCycle(tables)) if tables == "a, b"));
+}
+
+#[test]
+fn test_backup_header_round_trip() {
+    let header = header(BACKUP_VERSION, "20261018213317");
+    let line = serde_json::to_string(&header).unwrap();
+
+    assert_eq!(BackupHeader::parse(&line).unwrap(), header);
+
+    assert!(matches!(
+        BackupHeader::parse(&line.replace(BACKUP_FORMAT, "pg_dump")),
+        Err(BackupError::Malformed(_))
+    ));
+    assert!(matches!(
+        BackupHeader::parse("{\"table\":\"member\",\"row\":{}}"),
+        Err(BackupError::Malformed(_))
+    ));
+}
+
+#[test]
+fn test_backup_header_validation() {
+    let known = known_schema_versions().unwrap();
+    let latest = known.last().unwrap().clone();
+
+    assert!(header(BACKUP_VERSION, &latest).validate(&known).is_ok());
+    assert!(header(BACKUP_VERSION, &known[1]).validate(&known).is_ok());
+    assert!(matches!(
+        header(BACKUP_VERSION + 1, &latest).validate(&known),
+        Err(BackupError::UnsupportedVersion(_))
+    ));
+    assert!(matches!(
+        header(BACKUP_VERSION, "29991231000000").validate(&known),
+        Err(BackupError::UnknownSchemaVersion(_))
+    ));
+}
+
+#[test]
+fn test_known_schema_versions_are_sorted() {
+    let known = known_schema_versions().unwrap();
+
+    assert_eq!(known.first().map(String::as_str), Some("00000000000000"));
+    assert!(known.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(known.contains(&"20261018213317".to_string()));
+}
+
+#[test]
+fn test_quote_ident() {
+    assert_eq!(quote_ident("member"), "\"member\"");
+    assert_eq!(quote_ident("odd\"name"), "\"odd\"\"name\"");
+}
@@ -0,0 +1,112 @@
+use crate::budgets::{budget_covers, budget_status, period_bounds, was_pushed_over};
+use crate::models::{Budget, BudgetPeriod, ExpenseCategory};
+use chrono::{NaiveDate, Utc};
+use uuid::Uuid;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn budget(category: Option<ExpenseCategory>, amount: f64, period: BudgetPeriod) -> Budget {
+    Budget {
+        id: Uuid::new_v4(),
+        pool_id: Uuid::new_v4(),
+        category,
+        amount,
+        period,
+        created_by_member_id: Uuid::new_v4(),
+        inserted_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn test_period_bounds() {
+    // 2024-02-14 is a Wednesday in a leap year.
+    let day = date(2024, 2, 14);
+
+    assert_eq!(
+        period_bounds(&BudgetPeriod::Weekly, day),
+        (date(2024, 2, 12), date(2024, 2, 19))
+    );
+    assert_eq!(
+        period_bounds(&BudgetPeriod::Monthly, day),
+        (date(2024, 2, 1), date(2024, 3, 1))
+    );
+    assert_eq!(
+        period_bounds(&BudgetPeriod::Monthly, date(2024, 12, 31)),
+        (date(2024, 12, 1), date(2025, 1, 1))
+    );
+    assert_eq!(
+        period_bounds(&BudgetPeriod::Yearly, day),
+        (date(2024, 1, 1), date(2025, 1, 1))
+    );
+}
+
+#[test]
+fn test_budget_status_projects_current_pace() {
+    let food = budget(
+        Some(ExpenseCategory::FoodDining),
+        600.0,
+        BudgetPeriod::Monthly,
+    );
+
+    // $250 over the first 10 days of a 30-day month is on pace for $750.
+    let status = budget_status(food, 250.0, date(2024, 4, 10));
+
+    assert_eq!(status.period_start, date(2024, 4, 1));
+    assert_eq!(status.period_end, date(2024, 4, 30));
+    assert_eq!(status.remaining, 350.0);
+    assert_eq!(status.projected, 750.0);
+    assert!(!status.is_exceeded);
+    assert!(status.is_projected_to_exceed);
+
+    let over = budget_status(
+        budget(None, 100.0, BudgetPeriod::Weekly),
+        120.0,
+        date(2024, 4, 10),
+    );
+    assert_eq!(over.remaining, -20.0);
+    assert!(over.is_exceeded);
+}
+
+#[test]
+fn test_budget_covers() {
+    let food = budget(
+        Some(ExpenseCategory::FoodDining),
+        600.0,
+        BudgetPeriod::Monthly,
+    );
+    let everything = budget(None, 2000.0, BudgetPeriod::Monthly);
+
+    assert!(budget_covers(&food, &ExpenseCategory::FoodDining));
+    assert!(!budget_covers(&food, &ExpenseCategory::Groceries));
+    assert!(budget_covers(&everything, &ExpenseCategory::Groceries));
+}
+
+#[test]
+fn test_only_the_expense_that_crosses_the_budget_pushes_it_over() {
+    let weekly = || budget(None, 100.0, BudgetPeriod::Weekly);
+    let today = date(2024, 4, 10);
+
+    // $90 spent, then a $30 expense takes it to $120.
+    assert!(was_pushed_over(
+        &budget_status(weekly(), 120.0, today),
+        30.0
+    ));
+    // Landing exactly on the budget isn't over it.
+    assert!(!was_pushed_over(
+        &budget_status(weekly(), 100.0, today),
+        10.0
+    ));
+    // Already at $120, so another $5 doesn't push it over again.
+    assert!(!was_pushed_over(
+        &budget_status(weekly(), 125.0, today),
+        5.0
+    ));
+    // Exactly at the budget before the expense still counts as crossing it.
+    assert!(was_pushed_over(
+        &budget_status(weekly(), 110.0, today),
+        10.0
+    ));
+}
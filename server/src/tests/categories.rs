@@ -0,0 +1,74 @@
+use crate::categories::{
+    CategoryError, validate_category_color, validate_category_icon, validate_category_name,
+};
+use crate::models::{ExpenseCategory, PoolCategory};
+use chrono::Utc;
+use uuid::Uuid;
+
+fn pool_category(parent_category: Option<ExpenseCategory>) -> PoolCategory {
+    PoolCategory {
+        id: Uuid::new_v4(),
+        pool_id: Uuid::new_v4(),
+        name: "Ski trip".to_string(),
+        color: "#1f8a70".to_string(),
+        icon: None,
+        parent_category,
+        inserted_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn test_validate_category_name() {
+    assert_eq!(
+        validate_category_name("  Ski trip "),
+        Ok("Ski trip".to_string())
+    );
+    assert_eq!(validate_category_name("   "), Err(CategoryError::EmptyName));
+    assert_eq!(
+        validate_category_name(&"a".repeat(51)),
+        Err(CategoryError::NameTooLong)
+    );
+}
+
+#[test]
+fn test_validate_category_color() {
+    assert_eq!(
+        validate_category_color("#1F8A70"),
+        Ok("#1f8a70".to_string())
+    );
+    assert_eq!(
+        validate_category_color("1f8a70"),
+        Err(CategoryError::InvalidColor)
+    );
+    assert_eq!(
+        validate_category_color("#1f8a7"),
+        Err(CategoryError::InvalidColor)
+    );
+    assert_eq!(
+        validate_category_color("#1g8a70"),
+        Err(CategoryError::InvalidColor)
+    );
+}
+
+#[test]
+fn test_validate_category_icon() {
+    assert_eq!(validate_category_icon(" ⛷ "), Ok(Some("⛷".to_string())));
+    assert_eq!(validate_category_icon(""), Ok(None));
+    assert_eq!(
+        validate_category_icon(&"x".repeat(33)),
+        Err(CategoryError::IconTooLong)
+    );
+}
+
+#[test]
+fn test_builtin_category_falls_back_to_miscellaneous() {
+    assert_eq!(
+        pool_category(Some(ExpenseCategory::Travel)).builtin_category(),
+        ExpenseCategory::Travel
+    );
+    assert_eq!(
+        pool_category(None).builtin_category(),
+        ExpenseCategory::Miscellaneous
+    );
+}
@@ -0,0 +1,116 @@
+use diesel::result::Error;
+
+use crate::comments::{
+    CommentError, EXPENSE_COMMENT_MAX_CHARS, ensure_author, normalize_notes, validate_comment_body,
+};
+use crate::models::{Expense, ExpenseChangeset, ExpenseComment, NewExpenseComment};
+use crate::tests::db;
+
+#[test]
+fn test_validate_comment_body() {
+    assert_eq!(
+        validate_comment_body("  Paid you back  "),
+        Ok("Paid you back".to_string())
+    );
+    assert_eq!(validate_comment_body(" \n "), Err(CommentError::EmptyBody));
+    assert_eq!(
+        validate_comment_body(&"é".repeat(EXPENSE_COMMENT_MAX_CHARS)),
+        Ok("é".repeat(EXPENSE_COMMENT_MAX_CHARS))
+    );
+    assert_eq!(
+        validate_comment_body(&"a".repeat(EXPENSE_COMMENT_MAX_CHARS + 1)),
+        Err(CommentError::BodyTooLong)
+    );
+}
+
+#[test]
+fn test_normalize_notes() {
+    assert_eq!(
+        normalize_notes(Some(" Split the tip ")),
+        Some("Split the tip".to_string())
+    );
+    assert_eq!(normalize_notes(Some("  ")), None);
+    assert_eq!(normalize_notes(None), None);
+}
+
+#[test]
+fn test_only_the_author_can_modify_a_live_comment() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let bob = db::member(&mut conn, "Bob");
+    let pool = db::pool(&mut conn, &[&alice, &bob]);
+    let (expense, _) = db::expense(&mut conn, &pool, &alice, "Dinner", None, &[(&bob, 10.0)]);
+
+    let comment = ExpenseComment::create(
+        &mut conn,
+        &NewExpenseComment {
+            expense_id: expense.id,
+            pool_id: pool.id,
+            author_member_id: alice.id,
+            body: "Venmo me".to_string(),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(ensure_author(&comment, alice.id), Ok(()));
+    assert_eq!(
+        ensure_author(&comment, bob.id),
+        Err(CommentError::NotAuthor)
+    );
+
+    let deleted = ExpenseComment::soft_delete(&mut conn, comment.id).unwrap();
+
+    assert!(deleted.is_deleted);
+    assert_eq!(deleted.body, "");
+    assert_eq!(
+        ensure_author(&deleted, alice.id),
+        Err(CommentError::NotFound)
+    );
+
+    // A delete racing the ownership check leaves nothing to edit or delete.
+    assert!(matches!(
+        ExpenseComment::edit(&mut conn, comment.id, "Too late"),
+        Err(Error::NotFound)
+    ));
+    assert!(matches!(
+        ExpenseComment::soft_delete(&mut conn, comment.id),
+        Err(Error::NotFound)
+    ));
+}
+
+#[test]
+fn test_notes_can_be_cleared() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let pool = db::pool(&mut conn, &[&alice]);
+    let (expense, _) = db::expense(&mut conn, &pool, &alice, "Dinner", None, &[(&alice, 10.0)]);
+
+    let changeset = |notes| ExpenseChangeset {
+        name: Some("Dinner".to_string()),
+        amount: None,
+        is_settled: None,
+        description: None,
+        notes,
+        category: None,
+        split_method: None,
+        incurred_on: None,
+        custom_category_id: None,
+        categorized_by_rule: None,
+    };
+
+    let expense = Expense::update(
+        &mut conn,
+        &expense.id,
+        pool.id,
+        &changeset(Some(Some("Split the tip".to_string()))),
+    )
+    .unwrap();
+    assert_eq!(expense.notes.as_deref(), Some("Split the tip"));
+
+    // Leaving notes out of an update keeps them.
+    let expense = Expense::update(&mut conn, &expense.id, pool.id, &changeset(None)).unwrap();
+    assert_eq!(expense.notes.as_deref(), Some("Split the tip"));
+
+    let expense = Expense::update(&mut conn, &expense.id, pool.id, &changeset(Some(None))).unwrap();
+    assert_eq!(expense.notes, None);
+}
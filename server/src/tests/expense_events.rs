@@ -0,0 +1,146 @@
+use crate::models::{
+    Expense, ExpenseCategory, ExpenseEvent, ExpenseEventAction, ExpenseLineItem,
+    ExpenseWithLineItems, NewExpenseEvent, SplitMethod,
+};
+use crate::schema::{expense_event, member, pool};
+use crate::tests::db;
+use chrono::{NaiveDate, TimeZone, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+fn expense_snapshot(amount: f64, is_settled: bool) -> ExpenseWithLineItems {
+    let now = Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+
+    let expense = Expense {
+        id: Uuid::from_u128(1),
+        name: "Groceries".to_string(),
+        amount,
+        is_settled,
+        inserted_at: now,
+        updated_at: now,
+        pool_id: Uuid::from_u128(2),
+        paid_by_member_id: Uuid::from_u128(3),
+        description: None,
+        notes: None,
+        category: ExpenseCategory::Groceries,
+        split_method: SplitMethod::Default,
+        incurred_on: NaiveDate::from_ymd_opt(2026, 10, 17).unwrap(),
+        deleted_at: None,
+        custom_category_id: None,
+        categorized_by_rule: false,
+    };
+
+    let line_items = vec![ExpenseLineItem {
+        id: Uuid::from_u128(4),
+        expense_id: expense.id,
+        is_settled,
+        amount: amount / 2.0,
+        inserted_at: now,
+        updated_at: now,
+        debtor_member_id: Uuid::from_u128(5),
+    }];
+
+    expense.with_line_items(line_items)
+}
+
+#[test]
+fn test_created_event_has_only_after_snapshot() {
+    let after = expense_snapshot(40.0, false);
+    let event = NewExpenseEvent::new(
+        ExpenseEventAction::Created,
+        Uuid::from_u128(3),
+        None,
+        Some(&after),
+    );
+
+    assert_eq!(event.expense_id, after.id);
+    assert_eq!(event.pool_id, after.pool_id);
+    assert!(event.before_snapshot.is_none());
+
+    let snapshot = event.after_snapshot.unwrap();
+    assert_eq!(snapshot["amount"], 40.0);
+    assert_eq!(snapshot["line_items"][0]["amount"], 20.0);
+}
+
+#[test]
+fn test_deleted_event_takes_ids_from_before_snapshot() {
+    let before = expense_snapshot(40.0, false);
+    let event = NewExpenseEvent::new(
+        ExpenseEventAction::Deleted,
+        Uuid::from_u128(9),
+        Some(&before),
+        None,
+    );
+
+    assert_eq!(event.expense_id, before.id);
+    assert_eq!(event.pool_id, before.pool_id);
+    assert_eq!(event.actor_member_id, Uuid::from_u128(9));
+    assert!(event.after_snapshot.is_none());
+}
+
+#[test]
+fn test_settled_event_keeps_both_snapshots() {
+    let before = expense_snapshot(40.0, false);
+    let after = expense_snapshot(60.0, true);
+    let event = NewExpenseEvent::new(
+        ExpenseEventAction::Settled,
+        Uuid::from_u128(3),
+        Some(&before),
+        Some(&after),
+    );
+
+    assert_eq!(event.before_snapshot.unwrap()["is_settled"], false);
+    assert_eq!(event.after_snapshot.unwrap()["is_settled"], true);
+}
+
+#[test]
+fn test_events_cannot_be_updated_or_deleted() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let pool = db::pool(&mut conn, &[&alice]);
+    let (expense, _) = db::expense(&mut conn, &pool, &alice, "Dinner", None, &[(&alice, 10.0)]);
+    let snapshot = Expense::snapshot(&mut conn, expense.id).unwrap();
+
+    let event = ExpenseEvent::record(
+        &mut conn,
+        &NewExpenseEvent::new(ExpenseEventAction::Created, alice.id, None, Some(&snapshot)),
+    )
+    .unwrap();
+
+    // Each attempt runs in a savepoint so the test transaction survives the error.
+    let update = conn.transaction(|conn| {
+        diesel::update(expense_event::table.find(event.id))
+            .set(expense_event::action.eq(ExpenseEventAction::Deleted))
+            .execute(conn)
+    });
+    let delete =
+        conn.transaction(|conn| diesel::delete(expense_event::table.find(event.id)).execute(conn));
+    let delete_actor =
+        conn.transaction(|conn| diesel::delete(member::table.find(alice.id)).execute(conn));
+
+    assert!(update.unwrap_err().to_string().contains("append-only"));
+    assert!(delete.unwrap_err().to_string().contains("append-only"));
+    assert!(delete_actor.is_err());
+    assert_eq!(
+        expense_event::table
+            .find(event.id)
+            .select(expense_event::action)
+            .first::<ExpenseEventAction>(&mut conn)
+            .unwrap(),
+        ExpenseEventAction::Created
+    );
+
+    // Events still go with their pool.
+    diesel::delete(pool::table.find(pool.id))
+        .execute(&mut conn)
+        .unwrap();
+
+    assert_eq!(
+        expense_event::table
+            .find(event.id)
+            .count()
+            .get_result::<i64>(&mut conn)
+            .unwrap(),
+        0
+    );
+}
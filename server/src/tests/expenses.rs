@@ -0,0 +1,231 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::models::{Expense, ExpenseChangeset, ExpenseEvent, ExpenseEventAction};
+use crate::schema::{expense, expense_event};
+use crate::tests::db;
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+}
+
+fn recent_names(
+    conn: &mut PgConnection,
+    pool_id: Uuid,
+    member_id: Uuid,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+) -> Vec<String> {
+    Expense::get_recent_for_member_in_pool(
+        conn,
+        pool_id,
+        member_id,
+        50,
+        None,
+        None,
+        &[],
+        None,
+        false,
+        since,
+        until,
+        None,
+    )
+    .unwrap()
+    .into_iter()
+    .map(|(expense, _)| expense.name)
+    .collect()
+}
+
+#[test]
+fn test_incurred_on_defaults_to_today() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let pool = db::pool(&mut conn, &[&alice]);
+
+    let (expense, _) = db::expense(&mut conn, &pool, &alice, "Dinner", None, &[(&alice, 10.0)]);
+    let today: NaiveDate =
+        diesel::select(diesel::dsl::sql::<diesel::sql_types::Date>("CURRENT_DATE"))
+            .get_result(&mut conn)
+            .unwrap();
+
+    assert_eq!(expense.incurred_on, today);
+}
+
+#[test]
+fn test_recent_expenses_are_filtered_and_sorted_by_incurred_on() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let bob = db::member(&mut conn, "Bob");
+    let pool = db::pool(&mut conn, &[&alice, &bob]);
+
+    // Added out of order, so sorting by when they were added would differ.
+    for (name, incurred_on) in [
+        ("Groceries", date(9, 15)),
+        ("Rent", date(10, 1)),
+        ("Old dinner", date(9, 1)),
+        ("Future trip", date(10, 20)),
+    ] {
+        db::expense(
+            &mut conn,
+            &pool,
+            &alice,
+            name,
+            Some(incurred_on),
+            &[(&alice, 10.0), (&bob, 10.0)],
+        );
+    }
+
+    assert_eq!(
+        recent_names(&mut conn, pool.id, bob.id, None, None),
+        vec!["Future trip", "Rent", "Groceries", "Old dinner"]
+    );
+    assert_eq!(
+        recent_names(
+            &mut conn,
+            pool.id,
+            bob.id,
+            Some(date(9, 10)),
+            Some(date(10, 1))
+        ),
+        vec!["Rent", "Groceries"]
+    );
+}
+
+fn actions(conn: &mut PgConnection, expense_id: Uuid) -> Vec<ExpenseEventAction> {
+    // Every event in a test transaction has the same `inserted_at`, so these
+    // come back in no particular order.
+    expense_event::table
+        .filter(expense_event::expense_id.eq(expense_id))
+        .select(expense_event::action)
+        .load(conn)
+        .unwrap()
+}
+
+#[test]
+fn test_trashed_expenses_can_be_restored() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let bob = db::member(&mut conn, "Bob");
+    let pool = db::pool(&mut conn, &[&alice, &bob]);
+    let (expense, _) = db::expense(
+        &mut conn,
+        &pool,
+        &alice,
+        "Dinner",
+        None,
+        &[(&alice, 10.0), (&bob, 10.0)],
+    );
+
+    let trashed = Expense::soft_delete(&mut conn, expense.id, pool.id, bob.id).unwrap();
+
+    assert!(trashed.deleted_at.is_some());
+    assert!(recent_names(&mut conn, pool.id, bob.id, None, None).is_empty());
+    assert_eq!(
+        Expense::list_deleted_for_pool(&mut conn, pool.id)
+            .unwrap()
+            .into_iter()
+            .map(|expense| expense.id)
+            .collect::<Vec<_>>(),
+        vec![expense.id]
+    );
+    assert!(Expense::soft_delete(&mut conn, expense.id, pool.id, bob.id).is_err());
+
+    let restored = Expense::restore(&mut conn, expense.id, pool.id, alice.id).unwrap();
+
+    assert_eq!(restored.deleted_at, None);
+    assert_eq!(
+        recent_names(&mut conn, pool.id, bob.id, None, None),
+        vec!["Dinner"]
+    );
+    assert!(Expense::restore(&mut conn, expense.id, pool.id, alice.id).is_err());
+
+    // Both moves are in the event log, with who made them.
+    let actions = actions(&mut conn, expense.id);
+    assert_eq!(actions.len(), 2);
+    assert!(actions.contains(&ExpenseEventAction::Deleted));
+    assert!(actions.contains(&ExpenseEventAction::Restored));
+    let restored_event: ExpenseEvent = expense_event::table
+        .filter(expense_event::expense_id.eq(expense.id))
+        .filter(expense_event::action.eq(ExpenseEventAction::Restored))
+        .first(&mut conn)
+        .unwrap();
+    assert_eq!(restored_event.actor_member_id, alice.id);
+    assert!(restored_event.before_snapshot.unwrap()["deleted_at"].is_string());
+    assert!(restored_event.after_snapshot.unwrap()["deleted_at"].is_null());
+}
+
+#[test]
+fn test_purge_only_removes_expenses_trashed_before_the_cutoff() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let pool = db::pool(&mut conn, &[&alice]);
+    let now = Utc::now();
+
+    let mut trash = |name: &str, deleted_at: Option<DateTime<Utc>>| {
+        let (expense, _) = db::expense(&mut conn, &pool, &alice, name, None, &[(&alice, 10.0)]);
+
+        if let Some(deleted_at) = deleted_at {
+            Expense::soft_delete(&mut conn, expense.id, pool.id, alice.id).unwrap();
+            diesel::update(expense::table.filter(expense::id.eq(expense.id)))
+                .set(expense::deleted_at.eq(deleted_at))
+                .execute(&mut conn)
+                .unwrap();
+        }
+
+        expense.id
+    };
+
+    let expired = trash("Expired", Some(now - Duration::days(31)));
+    let recent = trash("Recent", Some(now - Duration::days(29)));
+    let live = trash("Live", None);
+
+    let (purged, _) = Expense::purge_deleted_before(&mut conn, now - Duration::days(30)).unwrap();
+
+    let remaining: Vec<Uuid> = expense::table
+        .filter(expense::id.eq_any([expired, recent, live]))
+        .select(expense::id)
+        .load(&mut conn)
+        .unwrap();
+
+    assert_eq!(purged, 1);
+    assert!(!remaining.contains(&expired));
+    assert!(remaining.contains(&recent));
+    assert!(remaining.contains(&live));
+    // The event log outlives the purge.
+    assert_eq!(
+        actions(&mut conn, expired),
+        vec![ExpenseEventAction::Deleted]
+    );
+}
+
+#[test]
+fn test_updates_only_apply_within_the_expenses_pool() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let home = db::pool(&mut conn, &[&alice]);
+    let trip = db::pool(&mut conn, &[&alice]);
+    let (dinner, _) = db::expense(&mut conn, &home, &alice, "Dinner", None, &[(&alice, 10.0)]);
+
+    let changeset = ExpenseChangeset {
+        name: Some("Lunch".to_string()),
+        amount: None,
+        is_settled: None,
+        description: None,
+        notes: None,
+        category: None,
+        split_method: None,
+        incurred_on: None,
+        custom_category_id: None,
+        categorized_by_rule: None,
+    };
+
+    assert!(matches!(
+        Expense::update(&mut conn, &dinner.id, trip.id, &changeset),
+        Err(diesel::result::Error::NotFound)
+    ));
+
+    let updated = Expense::update(&mut conn, &dinner.id, home.id, &changeset).unwrap();
+    assert_eq!(updated.name, "Lunch");
+}
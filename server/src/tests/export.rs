@@ -0,0 +1,188 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use uuid::Uuid;
+
+use crate::export::{
+    EXPORT_VERSION, ExportError, ExportFormat, ExportSettlement, ExportWriter, member_accounts,
+    validate_currency,
+};
+use crate::models::{
+    ExpenseCategory, ExpenseLineItem, ExpenseWithLineItems, Member, Pool, SplitMethod,
+};
+
+fn member(n: u128, first_name: &str, last_name: &str) -> Member {
+    let now = Utc::now();
+
+    Member {
+        id: Uuid::from_u128(n),
+        first_name: first_name.to_string(),
+        last_name: last_name.to_string(),
+        email: format!("{}@example.com", first_name.to_lowercase()),
+        inserted_at: now,
+        updated_at: now,
+        bio: None,
+        venmo_handle: None,
+        is_placeholder: false,
+        deleted_at: None,
+    }
+}
+
+fn pool() -> Pool {
+    let now = Utc::now();
+
+    Pool {
+        id: Uuid::from_u128(100),
+        name: "Home".to_string(),
+        description: None,
+        inserted_at: now,
+        updated_at: now,
+    }
+}
+
+fn expense(n: u128, name: &str, is_settled: bool, shares: &[(u128, f64)]) -> ExpenseWithLineItems {
+    let now = Utc::now();
+    let amount = shares.iter().map(|(_, share)| share).sum();
+
+    ExpenseWithLineItems {
+        id: Uuid::from_u128(n),
+        name: name.to_string(),
+        amount,
+        is_settled,
+        inserted_at: now,
+        updated_at: now,
+        pool_id: Uuid::from_u128(100),
+        paid_by_member_id: Uuid::from_u128(1),
+        description: Some("with \"quotes\"".to_string()),
+        notes: None,
+        category: ExpenseCategory::FoodDining,
+        split_method: SplitMethod::Amount,
+        incurred_on: NaiveDate::from_ymd_opt(2026, 9, 1).unwrap(),
+        deleted_at: None,
+        custom_category_id: None,
+        categorized_by_rule: false,
+        line_items: shares
+            .iter()
+            .map(|(member_n, share)| ExpenseLineItem {
+                id: Uuid::new_v4(),
+                expense_id: Uuid::from_u128(n),
+                is_settled,
+                amount: *share,
+                inserted_at: now,
+                updated_at: now,
+                debtor_member_id: Uuid::from_u128(*member_n),
+            })
+            .collect(),
+    }
+}
+
+fn export(format: ExportFormat, settlements: &[ExportSettlement]) -> String {
+    let members = vec![member(1, "Alice", "Smith"), member(2, "Bob", "Jones")];
+    let exported_at = Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+
+    let mut writer = ExportWriter::new(format, "USD", &members, &[]);
+
+    let mut output = writer.begin(&pool(), &members, &[], None, None, exported_at);
+    output += &writer.expense(&expense(10, "Dinner", true, &[(1, 30.0), (2, 20.0)]));
+    output += &writer.expense(&expense(11, "Groceries", false, &[(2, 12.5)]));
+    output += &writer.finish(settlements);
+
+    output
+}
+
+#[test]
+fn test_validate_currency() {
+    assert_eq!(validate_currency(" USD "), Ok("USD".to_string()));
+    assert_eq!(validate_currency("VACHR2"), Ok("VACHR2".to_string()));
+    assert_eq!(validate_currency("usd"), Err(ExportError::InvalidCurrency));
+    assert_eq!(validate_currency("$"), Err(ExportError::InvalidCurrency));
+    assert_eq!(validate_currency(""), Err(ExportError::InvalidCurrency));
+}
+
+#[test]
+fn test_member_accounts_are_unique() {
+    let members = vec![
+        member(1, "Mary-Jane", "O'Neil"),
+        member(2, "Sam", "Lee"),
+        member(3, "Sam", "Lee"),
+        member(4, "", ""),
+    ];
+
+    let hledger = member_accounts(ExportFormat::Hledger, &members);
+    assert_eq!(hledger[&Uuid::from_u128(1)], "mary-jane-o-neil");
+    assert_ne!(hledger[&Uuid::from_u128(2)], hledger[&Uuid::from_u128(3)]);
+    assert!(hledger[&Uuid::from_u128(2)].starts_with("sam-lee-"));
+    assert!(hledger[&Uuid::from_u128(4)].starts_with("member-"));
+
+    let beancount = member_accounts(ExportFormat::Beancount, &members);
+    assert_eq!(beancount[&Uuid::from_u128(1)], "MaryJaneONeil");
+    assert!(beancount[&Uuid::from_u128(2)].starts_with("SamLee"));
+    assert_ne!(
+        beancount[&Uuid::from_u128(2)],
+        beancount[&Uuid::from_u128(3)]
+    );
+}
+
+#[test]
+fn test_csv_export_has_a_row_per_line_item() {
+    let output = export(ExportFormat::Csv, &[]);
+    let lines: Vec<&str> = output.lines().collect();
+
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("expense_id,date,name"));
+    assert!(lines[1].contains(
+        ",Dinner,\"with \"\"quotes\"\"\",,food_dining,,50.0,Alice Smith,true,Alice Smith,30.0"
+    ));
+    assert!(lines[2].ends_with("Bob Jones,20.0"));
+}
+
+#[test]
+fn test_json_export_is_a_versioned_document() {
+    let settlement = ExportSettlement {
+        expense_id: Uuid::from_u128(10),
+        settled_by_member_id: Uuid::from_u128(1),
+        settled_at: Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap(),
+    };
+
+    let document: serde_json::Value =
+        serde_json::from_str(&export(ExportFormat::Json, &[settlement])).unwrap();
+
+    assert_eq!(document["version"], EXPORT_VERSION);
+    assert_eq!(document["pool"]["name"], "Home");
+    assert_eq!(document["members"].as_array().unwrap().len(), 2);
+    assert_eq!(document["expenses"].as_array().unwrap().len(), 2);
+    assert_eq!(document["expenses"][0]["line_items"][1]["amount"], 20.0);
+    assert_eq!(
+        document["settlements"][0]["expense_id"],
+        Uuid::from_u128(10).to_string()
+    );
+}
+
+#[test]
+fn test_hledger_export() {
+    let output = export(ExportFormat::Hledger, &[]);
+
+    assert!(output.contains(&format!(
+        "2026-09-01 * Dinner  ; expense_id:{}\n    ; with \"quotes\"\n    expenses:food_dining:alice-smith  30.00 USD\n    expenses:food_dining:bob-jones  20.00 USD\n    assets:pool:alice-smith\n",
+        Uuid::from_u128(10)
+    )));
+    assert!(output.contains("2026-09-01 ! Groceries"));
+}
+
+#[test]
+fn test_beancount_export_opens_accounts_once() {
+    let output = export(ExportFormat::Beancount, &[]);
+
+    assert!(output.contains("option \"operating_currency\" \"USD\""));
+    assert_eq!(
+        output
+            .matches("open Expenses:FoodDining:BobJones USD")
+            .count(),
+        1
+    );
+    assert!(output.contains("2026-09-01 * \"Dinner\"\n"));
+    assert!(output.contains("    description: \"with \\\"quotes\\\"\"\n"));
+    assert!(output.contains("2026-09-01 ! \"Groceries\"\n"));
+    assert!(
+        output
+            .contains("    Expenses:FoodDining:BobJones  12.50 USD\n    Assets:Pool:AliceSmith\n")
+    );
+}
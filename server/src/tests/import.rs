@@ -0,0 +1,266 @@
+use chrono::{NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::categories::parse_builtin_category;
+use crate::import::{
+    ColumnMapping, ImportError, ImportLineItem, ImportMember, csv_reader, default_split,
+    parse_amount, parse_split, plan_import, record_line, resolve_member,
+};
+use crate::models::{ExpenseCategory, PoolCategory, SplitMethod};
+
+fn member(n: u128, first_name: &str, last_name: &str, percentage: f64) -> ImportMember {
+    ImportMember {
+        id: Uuid::from_u128(n),
+        first_name: first_name.to_string(),
+        last_name: last_name.to_string(),
+        email: format!("{}@example.com", first_name.to_lowercase()),
+        default_split_percentage: percentage,
+    }
+}
+
+fn members() -> Vec<ImportMember> {
+    vec![
+        member(1, "Alice", "Smith", 60.0),
+        member(2, "Bob", "Jones", 40.0),
+    ]
+}
+
+fn mapping() -> ColumnMapping {
+    ColumnMapping {
+        date: "Date".to_string(),
+        name: "Description".to_string(),
+        amount: "Cost".to_string(),
+        payer: "Paid by".to_string(),
+        category: Some("Category".to_string()),
+        split: Some("Split".to_string()),
+        date_format: None,
+    }
+}
+
+fn line_item(n: u128, amount: f64) -> ImportLineItem {
+    ImportLineItem {
+        debtor_member_id: Uuid::from_u128(n),
+        amount,
+    }
+}
+
+#[test]
+fn test_parse_builtin_category_accepts_common_spellings() {
+    for value in [
+        "food_dining",
+        "FoodDining",
+        "Food & Dining",
+        " food dining ",
+    ] {
+        assert_eq!(
+            parse_builtin_category(value),
+            Some(ExpenseCategory::FoodDining)
+        );
+    }
+
+    assert_eq!(
+        parse_builtin_category("GROCERIES"),
+        Some(ExpenseCategory::Groceries)
+    );
+    assert_eq!(parse_builtin_category("wine"), None);
+}
+
+#[test]
+fn test_parse_amount() {
+    assert_eq!(parse_amount("$1,204.505"), Ok(1204.51));
+    assert_eq!(parse_amount(" 84 "), Ok(84.0));
+    assert!(parse_amount("0").is_err());
+    assert!(parse_amount("-3").is_err());
+    assert!(parse_amount("lots").is_err());
+}
+
+#[test]
+fn test_resolve_member_by_email_full_name_and_first_name() {
+    let members = members();
+
+    assert_eq!(
+        resolve_member(&members, "ALICE@example.com"),
+        Ok(Uuid::from_u128(1))
+    );
+    assert_eq!(
+        resolve_member(&members, "bob  jones"),
+        Ok(Uuid::from_u128(2))
+    );
+    assert_eq!(resolve_member(&members, "bob"), Ok(Uuid::from_u128(2)));
+    assert!(resolve_member(&members, "carol").is_err());
+
+    let ambiguous = vec![
+        member(1, "Alice", "Smith", 50.0),
+        member(2, "Alice", "Brown", 50.0),
+    ];
+    assert!(resolve_member(&ambiguous, "alice").is_err());
+    assert_eq!(
+        resolve_member(&ambiguous, "Alice Brown"),
+        Ok(Uuid::from_u128(2))
+    );
+}
+
+#[test]
+fn test_default_split_uses_default_percentages_or_splits_evenly() {
+    assert_eq!(
+        default_split(10.0, &members()),
+        vec![line_item(1, 6.0), line_item(2, 4.0)]
+    );
+
+    let unset = vec![
+        member(1, "Alice", "Smith", 0.0),
+        member(2, "Bob", "Jones", 0.0),
+        member(3, "Carol", "White", 0.0),
+    ];
+    assert_eq!(
+        default_split(10.0, &unset),
+        vec![line_item(1, 3.34), line_item(2, 3.33), line_item(3, 3.33)]
+    );
+}
+
+#[test]
+fn test_parse_split() {
+    let members = members();
+
+    assert_eq!(
+        parse_split("alice=25%; bob=75%", 20.0, &members),
+        Ok((
+            SplitMethod::Percentage,
+            vec![line_item(1, 5.0), line_item(2, 15.0)]
+        ))
+    );
+    assert_eq!(
+        parse_split("alice=12.50;bob=$7.50", 20.0, &members),
+        Ok((
+            SplitMethod::Amount,
+            vec![line_item(1, 12.5), line_item(2, 7.5)]
+        ))
+    );
+    assert_eq!(
+        parse_split("", 20.0, &members),
+        Ok((
+            SplitMethod::Default,
+            vec![line_item(1, 12.0), line_item(2, 8.0)]
+        ))
+    );
+
+    assert!(parse_split("alice=50%; bob=40%", 20.0, &members).is_err());
+    assert!(parse_split("alice=10; bob=5", 20.0, &members).is_err());
+    assert!(parse_split("alice=50%; bob=10", 20.0, &members).is_err());
+    assert!(parse_split("alice=10; alice=10", 20.0, &members).is_err());
+    assert!(parse_split("alice", 20.0, &members).is_err());
+}
+
+#[test]
+fn test_plan_import_collects_rows_and_errors() {
+    let now = Utc::now();
+    let custom_categories = vec![PoolCategory {
+        id: Uuid::from_u128(10),
+        pool_id: Uuid::from_u128(20),
+        name: "Wine".to_string(),
+        color: "#7b1e3a".to_string(),
+        icon: None,
+        parent_category: Some(ExpenseCategory::FoodDining),
+        inserted_at: now,
+        updated_at: now,
+    }];
+
+    let csv = "\u{feff}Date,Description,Cost,Paid by,Category,Split\n\
+               2026-09-01,Costco,\"$1,000.00\",alice@example.com,groceries,\n\
+               2026-09-02,\"Dinner, with wine\",84,Bob Jones,wine,alice=50%;bob=50%\n\
+               ,,,,,\n\
+               2026-09-03,Gas,30,bob,,alice=20\n\
+               09/04/2026,,abc,carol,wine-ish,\n";
+
+    let plan = plan_import(csv, &mapping(), &members(), &custom_categories).unwrap();
+
+    assert_eq!(plan.rows.len(), 2);
+
+    let costco = &plan.rows[0];
+    assert_eq!(costco.row, 2);
+    assert_eq!(
+        costco.incurred_on,
+        NaiveDate::from_ymd_opt(2026, 9, 1).unwrap()
+    );
+    assert_eq!(costco.amount, 1000.0);
+    assert_eq!(costco.paid_by_member_id, Uuid::from_u128(1));
+    assert_eq!(costco.category, Some((ExpenseCategory::Groceries, None)));
+    assert_eq!(costco.split_method, SplitMethod::Default);
+
+    let dinner = &plan.rows[1];
+    assert_eq!(dinner.name, "Dinner, with wine");
+    assert_eq!(
+        dinner.category,
+        Some((ExpenseCategory::FoodDining, Some(Uuid::from_u128(10))))
+    );
+    assert_eq!(
+        dinner.line_items,
+        vec![line_item(1, 42.0), line_item(2, 42.0)]
+    );
+
+    let gas_errors: Vec<_> = plan.errors.iter().filter(|e| e.row == 5).collect();
+    assert_eq!(gas_errors.len(), 1);
+    assert_eq!(gas_errors[0].column.as_deref(), Some("Split"));
+
+    let bad_columns: Vec<_> = plan
+        .errors
+        .iter()
+        .filter(|e| e.row == 6)
+        .filter_map(|e| e.column.as_deref())
+        .collect();
+    assert_eq!(
+        bad_columns,
+        vec!["Date", "Description", "Cost", "Paid by", "Category"]
+    );
+}
+
+#[test]
+fn test_plan_import_rejects_unmapped_columns_and_empty_files() {
+    let mut missing_split = mapping();
+    missing_split.split = Some("Shares".to_string());
+
+    assert_eq!(
+        plan_import(
+            "Date,Description,Cost,Paid by,Category\n",
+            &missing_split,
+            &members(),
+            &[]
+        )
+        .unwrap_err(),
+        ImportError::MissingColumn("Shares".to_string())
+    );
+
+    let mut no_optional_columns = mapping();
+    no_optional_columns.category = None;
+    no_optional_columns.split = None;
+
+    assert_eq!(
+        plan_import(
+            "date,description,cost,paid by\n",
+            &no_optional_columns,
+            &members(),
+            &[]
+        )
+        .unwrap_err(),
+        ImportError::NoRows
+    );
+}
+
+#[test]
+fn test_record_line_skips_blank_lines_and_counts_quoted_newlines() {
+    let csv = "Date,Description\r\n\
+               2026-09-01,Costco\r\n\
+               \r\n\
+               \r\n\
+               2026-09-02,\"Dinner\nwith wine\"\n\
+               2026-09-03,Gas\n\
+               \n\
+               2026-09-04,Lunch";
+
+    let lines: Vec<usize> = csv_reader(csv)
+        .records()
+        .map(|record| record_line(csv, &record.unwrap()))
+        .collect();
+
+    assert_eq!(lines, vec![2, 5, 7, 9]);
+}
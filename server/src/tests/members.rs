@@ -0,0 +1,192 @@
+use diesel::prelude::*;
+
+use crate::compute_balances_for_member;
+use crate::models::{
+    Expense, ExpenseCategory, ExpenseCategoryRule, ExpenseComment, Friendship, FriendshipStatus,
+    Member, MemberChangeset, MemberPassword, NewExpenseCategoryRule, NewExpenseComment,
+    NewFriendship, NewMemberPassword, RuleMatchType,
+};
+use crate::schema::{expense_line_item, member_password};
+use crate::tests::db;
+
+fn rule(name: &str) -> NewExpenseCategoryRule {
+    NewExpenseCategoryRule {
+        rule: name.to_string(),
+        category: ExpenseCategory::Groceries,
+        match_type: RuleMatchType::Contains,
+        min_amount: None,
+        max_amount: None,
+        pool_id: None,
+        priority: 0,
+        custom_category_id: None,
+    }
+}
+
+#[test]
+fn test_deleting_a_member_clears_their_details_but_keeps_balances() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let bob = db::member(&mut conn, "Bob");
+    let pool = db::pool(&mut conn, &[&alice, &bob]);
+
+    Member::update(
+        &mut conn,
+        alice.id,
+        &MemberChangeset {
+            first_name: None,
+            last_name: None,
+            email: None,
+            bio: Some("Hi".to_string()),
+            venmo_handle: Some("@alice".to_string()),
+        },
+    )
+    .unwrap();
+    MemberPassword::create(
+        &mut conn,
+        &NewMemberPassword {
+            member_id: alice.id,
+            password_hash: "hash".to_string(),
+        },
+    )
+    .unwrap();
+    ExpenseCategoryRule::create(&mut conn, &alice.id, &rule("market")).unwrap();
+
+    db::expense(
+        &mut conn,
+        &pool,
+        &alice,
+        "Dinner",
+        None,
+        &[(&alice, 20.0), (&bob, 30.0)],
+    );
+    db::expense(
+        &mut conn,
+        &pool,
+        &bob,
+        "Lunch",
+        None,
+        &[(&alice, 10.0), (&bob, 10.0)],
+    );
+
+    let line_items = |conn: &mut PgConnection| {
+        expense_line_item::table
+            .select((
+                expense_line_item::expense_id,
+                expense_line_item::debtor_member_id,
+                expense_line_item::amount,
+            ))
+            .filter(expense_line_item::debtor_member_id.eq_any([alice.id, bob.id]))
+            .order_by(expense_line_item::id)
+            .load::<(uuid::Uuid, uuid::Uuid, f64)>(conn)
+            .unwrap()
+    };
+    let balances = |conn: &mut PgConnection| {
+        compute_balances_for_member(
+            bob.id,
+            Expense::list_unpaid_for_balance_computation(conn, pool.id).unwrap(),
+        )
+    };
+
+    let line_items_before = line_items(&mut conn);
+    let balances_before = balances(&mut conn);
+    assert_eq!(balances_before.len(), 1);
+    assert_eq!(balances_before[0].amount, 20.0);
+
+    let deleted = Member::anonymize(&mut conn, alice.id).unwrap();
+
+    assert_eq!(deleted.first_name, "Deleted");
+    assert_eq!(deleted.last_name, "member");
+    assert_eq!(
+        deleted.email,
+        format!("deleted+{}@medici.invalid", alice.id)
+    );
+    assert_eq!(deleted.bio, None);
+    assert_eq!(deleted.venmo_handle, None);
+    assert!(deleted.deleted_at.is_some());
+    assert!(Member::is_deleted(&mut conn, alice.id).unwrap());
+
+    let passwords: i64 = member_password::table
+        .filter(member_password::member_id.eq(alice.id))
+        .count()
+        .get_result(&mut conn)
+        .unwrap();
+    assert_eq!(passwords, 0);
+    assert!(
+        ExpenseCategoryRule::find_for_member(&mut conn, alice.id)
+            .unwrap()
+            .is_empty()
+    );
+
+    assert_eq!(line_items(&mut conn), line_items_before);
+    let balances_after = balances(&mut conn);
+    assert_eq!(balances_after.len(), 1);
+    assert_eq!(balances_after[0].member_id, alice.id);
+    assert_eq!(balances_after[0].amount, balances_before[0].amount);
+    assert_eq!(balances_after[0].direction, balances_before[0].direction);
+}
+
+#[test]
+fn test_export_data_gathers_what_the_member_is_part_of() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let bob = db::member(&mut conn, "Bob");
+    let carol = db::member(&mut conn, "Carol");
+    let pool = db::pool(&mut conn, &[&alice, &bob, &carol]);
+
+    Friendship::create(
+        &mut conn,
+        &NewFriendship {
+            inviting_member_id: bob.id,
+            friend_member_id: alice.id,
+            status: FriendshipStatus::Accepted,
+        },
+    )
+    .unwrap();
+    ExpenseCategoryRule::create(&mut conn, &alice.id, &rule("market")).unwrap();
+    ExpenseCategoryRule::create(&mut conn, &bob.id, &rule("bakery")).unwrap();
+
+    let (paid, _) = db::expense(&mut conn, &pool, &alice, "Dinner", None, &[(&bob, 30.0)]);
+    let (owed, _) = db::expense(
+        &mut conn,
+        &pool,
+        &bob,
+        "Lunch",
+        None,
+        &[(&alice, 10.0), (&carol, 10.0)],
+    );
+    db::expense(&mut conn, &pool, &bob, "Coffee", None, &[(&carol, 4.0)]);
+
+    ExpenseComment::create(
+        &mut conn,
+        &NewExpenseComment {
+            expense_id: owed.id,
+            pool_id: pool.id,
+            author_member_id: alice.id,
+            body: "Thanks!".to_string(),
+        },
+    )
+    .unwrap();
+
+    let export = Member::export_data(&mut conn, alice.id).unwrap();
+
+    assert_eq!(export.member.id, alice.id);
+    assert_eq!(export.friendships.len(), 1);
+    assert_eq!(
+        export.pools.iter().map(|pool| pool.id).collect::<Vec<_>>(),
+        vec![pool.id]
+    );
+    assert_eq!(export.pool_memberships.len(), 1);
+    assert_eq!(export.comments.len(), 1);
+    assert_eq!(export.category_rules.len(), 1);
+    assert_eq!(export.category_rules[0].rule, "market");
+
+    let mut expense_ids: Vec<uuid::Uuid> = export.expenses.iter().map(|e| e.id).collect();
+    expense_ids.sort();
+    let mut expected = vec![paid.id, owed.id];
+    expected.sort();
+    assert_eq!(expense_ids, expected);
+
+    // Shares come with the rest of the split.
+    let lunch = export.expenses.iter().find(|e| e.id == owed.id).unwrap();
+    assert_eq!(lunch.line_items.len(), 2);
+}
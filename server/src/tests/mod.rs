@@ -1,3 +1,27 @@
+mod analytics;
+mod auth;
+mod backup;
+mod budgets;
+mod categories;
+mod comments;
+mod db;
+mod expense_events;
+mod expenses;
+mod export;
+mod import;
+mod members;
+mod notifications;
+mod pagination;
+mod realtime;
+mod reminders;
+mod rules;
+mod search;
+mod splitwise;
+mod storage;
+mod suggestions;
+mod tags;
+mod webhooks;
+
 use crate::compute_balances_for_member;
 use crate::models::{Balance, DebtPair, PaymentDirection};
 use std::collections::HashSet;
@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::models::{
+    Expense, Member, NewNotification, NewPoolMembership, Notification, NotificationKind,
+    NotificationPreferences, NotificationPreferencesChangeset, PoolMembership, PoolRole,
+};
+use crate::schema::notification;
+use crate::tests::db;
+
+fn new_notification(member_id: Uuid, kind: NotificationKind, actor: Uuid) -> NewNotification {
+    NewNotification {
+        member_id,
+        kind,
+        actor_member_id: Some(actor),
+        pool_id: None,
+        expense_id: None,
+        data: serde_json::json!({}),
+    }
+}
+
+fn recipients(notifications: &[NewNotification]) -> Vec<Uuid> {
+    notifications.iter().map(|n| n.member_id).collect()
+}
+
+#[test]
+fn test_default_preferences_allow_everything() {
+    let preferences = NotificationPreferences::default();
+
+    assert!(preferences.allows(&NotificationKind::FriendRequest));
+    assert!(preferences.allows(&NotificationKind::PoolInvitation));
+    assert!(preferences.allows(&NotificationKind::ExpenseAdded));
+    assert!(preferences.allows(&NotificationKind::Settlement));
+}
+
+#[test]
+fn test_preferences_turn_off_one_kind() {
+    let preferences = NotificationPreferences {
+        expenses: false,
+        ..NotificationPreferences::default()
+    };
+
+    assert!(!preferences.allows(&NotificationKind::ExpenseAdded));
+    assert!(preferences.allows(&NotificationKind::Settlement));
+    assert!(preferences.allows(&NotificationKind::FriendRequest));
+}
+
+#[test]
+fn test_deliverable_skips_the_actor_and_unreachable_members() {
+    let alice = Uuid::from_u128(1);
+    let bob = Uuid::from_u128(2);
+    let placeholder = Uuid::from_u128(3);
+
+    let notifications = vec![
+        new_notification(alice, NotificationKind::ExpenseAdded, alice),
+        new_notification(bob, NotificationKind::ExpenseAdded, alice),
+        new_notification(placeholder, NotificationKind::ExpenseAdded, alice),
+    ];
+    let reachable = HashSet::from([alice, bob]);
+
+    let deliverable = Notification::deliverable(notifications, &reachable, &HashMap::new());
+
+    assert_eq!(recipients(&deliverable), vec![bob]);
+}
+
+#[test]
+fn test_deliverable_applies_each_members_preferences() {
+    let alice = Uuid::from_u128(1);
+    let bob = Uuid::from_u128(2);
+    let carol = Uuid::from_u128(3);
+    let actor = Uuid::from_u128(4);
+
+    let notifications = vec![
+        new_notification(bob, NotificationKind::ExpenseAdded, actor),
+        new_notification(bob, NotificationKind::Settlement, actor),
+        new_notification(carol, NotificationKind::ExpenseAdded, actor),
+        new_notification(alice, NotificationKind::ExpenseAdded, actor),
+    ];
+    let reachable = HashSet::from([alice, bob, carol]);
+    let preferences = HashMap::from([
+        (
+            bob,
+            NotificationPreferences {
+                expenses: false,
+                ..NotificationPreferences::default()
+            },
+        ),
+        (carol, NotificationPreferences::default()),
+    ]);
+
+    let deliverable = Notification::deliverable(notifications, &reachable, &preferences);
+
+    assert_eq!(recipients(&deliverable), vec![bob, carol, alice]);
+    assert_eq!(deliverable[0].kind, NotificationKind::Settlement);
+}
+
+#[test]
+fn test_expense_added_goes_to_everyone_in_the_split_but_the_actor() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let bob = db::member(&mut conn, "Bob");
+    let carol = db::member(&mut conn, "Carol");
+    let dave = db::member(&mut conn, "Dave");
+    let erin = db::member(&mut conn, "Erin");
+    let outsider = db::member(&mut conn, "Outsider");
+    let placeholder =
+        Member::create_placeholder(&mut conn, Uuid::new_v4(), "Pat", "Holder").unwrap();
+    let pool = db::pool(&mut conn, &[&alice, &bob, &carol, &dave, &erin]);
+
+    PoolMembership::create(
+        &mut conn,
+        &NewPoolMembership {
+            pool_id: pool.id,
+            member_id: placeholder.id,
+            role: PoolRole::PARTICIPANT,
+            default_split_percentage: 0.0,
+        },
+    )
+    .unwrap();
+    NotificationPreferences::update(
+        &mut conn,
+        dave.id,
+        &NotificationPreferencesChangeset {
+            friend_requests: None,
+            pool_invitations: None,
+            expenses: Some(false),
+            settlements: None,
+        },
+    )
+    .unwrap();
+    Member::anonymize(&mut conn, erin.id).unwrap();
+
+    // Bob pays, and Alice adds it on his behalf.
+    let (expense, line_items) = db::expense(
+        &mut conn,
+        &pool,
+        &bob,
+        "Groceries",
+        None,
+        &[
+            (&alice, 10.0),
+            (&carol, 20.0),
+            (&carol, 5.0),
+            (&dave, 10.0),
+            (&erin, 10.0),
+            (&placeholder, 10.0),
+        ],
+    );
+    let expense = Expense::with_line_items(expense, line_items);
+
+    let sent = Notification::send_expense_added(&mut conn, &expense, alice.id).unwrap();
+    assert_eq!(sent, 2);
+
+    let mut received: Vec<(Uuid, f64)> = notification::table
+        .filter(notification::expense_id.eq(expense.id))
+        .select((notification::member_id, notification::data))
+        .load::<(Uuid, serde_json::Value)>(&mut conn)
+        .unwrap()
+        .into_iter()
+        .map(|(member_id, data)| (member_id, data["share"].as_f64().unwrap()))
+        .collect();
+    received.sort_by_key(|(member_id, _)| *member_id);
+
+    let mut expected = vec![(bob.id, 0.0), (carol.id, 25.0)];
+    expected.sort_by_key(|(member_id, _)| *member_id);
+    assert_eq!(received, expected);
+    assert!(
+        !received
+            .iter()
+            .any(|(member_id, _)| *member_id == outsider.id)
+    );
+}
+
+#[test]
+fn test_marking_read_only_touches_the_callers_notifications() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let bob = db::member(&mut conn, "Bob");
+    let carol = db::member(&mut conn, "Carol");
+
+    Notification::send(
+        &mut conn,
+        vec![
+            new_notification(alice.id, NotificationKind::FriendRequest, carol.id),
+            new_notification(alice.id, NotificationKind::PoolInvitation, carol.id),
+            new_notification(bob.id, NotificationKind::FriendRequest, carol.id),
+        ],
+    )
+    .unwrap();
+
+    let bobs = Notification::list_for_member(&mut conn, bob.id, false, None, 10).unwrap();
+    let alices = Notification::list_for_member(&mut conn, alice.id, false, None, 10).unwrap();
+
+    assert_eq!(
+        Notification::mark_read(&mut conn, bobs[0].id, alice.id).unwrap_err(),
+        diesel::result::Error::NotFound
+    );
+    assert_eq!(Notification::unread_count(&mut conn, bob.id).unwrap(), 1);
+
+    let read = Notification::mark_read(&mut conn, alices[0].id, alice.id).unwrap();
+    assert!(read.read_at.is_some());
+    assert_eq!(Notification::unread_count(&mut conn, alice.id).unwrap(), 1);
+
+    assert_eq!(Notification::mark_all_read(&mut conn, alice.id).unwrap(), 1);
+    assert_eq!(Notification::unread_count(&mut conn, alice.id).unwrap(), 0);
+    assert_eq!(Notification::unread_count(&mut conn, bob.id).unwrap(), 1);
+}
@@ -0,0 +1,48 @@
+use crate::pagination::{
+    CursorError, ExpenseCursor, MAX_PAGE_LIMIT, Page, decode_cursor, encode_cursor, page_limit,
+};
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+#[test]
+fn test_expense_cursor_round_trips() {
+    let cursor = ExpenseCursor {
+        incurred_on: NaiveDate::from_ymd_opt(2024, 3, 9).unwrap(),
+        id: Uuid::new_v4(),
+    };
+
+    let encoded = encode_cursor(&cursor);
+
+    assert!(encoded.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(decode_cursor::<ExpenseCursor>(&encoded), Ok(cursor));
+}
+
+#[test]
+fn test_decode_cursor_rejects_garbage() {
+    assert_eq!(
+        decode_cursor::<ExpenseCursor>("not hex"),
+        Err(CursorError::Invalid)
+    );
+    assert_eq!(
+        decode_cursor::<ExpenseCursor>(&hex::encode("{\"id\": 1}")),
+        Err(CursorError::Invalid)
+    );
+}
+
+#[test]
+fn test_page_from_rows_only_sets_cursor_when_more_rows_exist() {
+    let page = Page::from_rows(vec![1, 2, 3], 2, |n| n.to_string());
+    assert_eq!(page.items, vec![1, 2]);
+    assert_eq!(page.next_cursor.as_deref(), Some("2"));
+
+    let last_page = Page::from_rows(vec![1, 2], 2, |n| n.to_string());
+    assert_eq!(last_page.items, vec![1, 2]);
+    assert_eq!(last_page.next_cursor, None);
+}
+
+#[test]
+fn test_page_limit_defaults_and_clamps() {
+    assert_eq!(page_limit(None), 50);
+    assert_eq!(page_limit(Some(0)), 1);
+    assert_eq!(page_limit(Some(10_000)), MAX_PAGE_LIMIT);
+}
@@ -0,0 +1,196 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::{Duration, TimeZone, Utc};
+use uuid::Uuid;
+
+use crate::mail::{Email, LogMailer, MailError, Mailer, SmtpMailer};
+use crate::models::{Balance, Member, PaymentDirection, PoolActivity};
+use crate::reminders::{
+    Creditor, amount_owed, format_amount, payment_reminder_email, weekly_digest_email,
+};
+
+fn member() -> Member {
+    let now = Utc::now();
+
+    Member {
+        id: Uuid::from_u128(1),
+        first_name: "Alice".to_string(),
+        last_name: "Smith".to_string(),
+        email: "alice@example.com".to_string(),
+        inserted_at: now,
+        updated_at: now,
+        bio: None,
+        venmo_handle: None,
+        is_placeholder: false,
+        deleted_at: None,
+    }
+}
+
+fn activity(
+    name: &str,
+    expenses_added: i64,
+    amount_added: f64,
+    expenses_settled: i64,
+) -> PoolActivity {
+    PoolActivity {
+        pool_id: Uuid::new_v4(),
+        pool_name: name.to_string(),
+        expenses_added,
+        amount_added,
+        expenses_settled,
+    }
+}
+
+#[test]
+fn test_amount_owed_only_counts_outbound_balances() {
+    let balances = vec![
+        Balance {
+            member_id: Uuid::from_u128(2),
+            amount: 12.5,
+            direction: PaymentDirection::Outbound,
+        },
+        Balance {
+            member_id: Uuid::from_u128(3),
+            amount: 40.0,
+            direction: PaymentDirection::Inbound,
+        },
+        Balance {
+            member_id: Uuid::from_u128(4),
+            amount: 7.5,
+            direction: PaymentDirection::Outbound,
+        },
+    ];
+
+    assert_eq!(amount_owed(&balances), 20.0);
+    assert_eq!(amount_owed(&[]), 0.0);
+    assert_eq!(format_amount(1234.5), "$1234.50");
+}
+
+#[test]
+fn test_payment_reminder_email_lists_creditors() {
+    let now = Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+    let creditors = vec![
+        Creditor {
+            name: "Bob Jones".to_string(),
+            venmo_handle: Some("@bob-jones".to_string()),
+            amount: 30.0,
+        },
+        Creditor {
+            name: "Carol White".to_string(),
+            venmo_handle: None,
+            amount: 12.25,
+        },
+    ];
+
+    let email = payment_reminder_email(&member(), "Home", now - Duration::days(8), &creditors, now);
+
+    assert_eq!(email.to, "alice@example.com");
+    assert_eq!(email.subject, "You owe $42.25 in Home");
+    assert!(
+        email
+            .body
+            .contains("You've owed $42.25 in Home for 8 days.")
+    );
+    assert!(
+        email
+            .body
+            .contains("- Bob Jones: $30.00 (Venmo @bob-jones)\n")
+    );
+    assert!(email.body.contains("- Carol White: $12.25\n"));
+}
+
+#[test]
+fn test_weekly_digest_skips_quiet_pools() {
+    assert_eq!(weekly_digest_email(&member(), &[]), None);
+    assert_eq!(
+        weekly_digest_email(&member(), &[activity("Home", 0, 0.0, 0)]),
+        None
+    );
+
+    let email = weekly_digest_email(
+        &member(),
+        &[
+            activity("Home", 3, 120.0, 0),
+            activity("Quiet", 0, 0.0, 0),
+            activity("Trip", 1, 40.0, 2),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(email.subject, "Your week in Medici");
+    assert!(
+        email
+            .body
+            .contains("Home\n- 3 expenses added, totalling $120.00\n\n")
+    );
+    assert!(
+        email
+            .body
+            .contains("Trip\n- 1 expense added, totalling $40.00\n- 2 expenses settled\n\n")
+    );
+    assert!(!email.body.contains("Quiet"));
+}
+
+/// Keeps what it's asked to send, so tests can check what would have gone
+/// out.
+#[derive(Default)]
+struct RecordingMailer {
+    sent: Mutex<Vec<Email>>,
+}
+
+#[async_trait]
+impl Mailer for RecordingMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        self.sent.lock().unwrap().push(email.clone());
+
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_mailers_send_through_the_trait() {
+    let email = Email {
+        to: "alice@example.com".to_string(),
+        subject: "Hello".to_string(),
+        body: "Hi Alice".to_string(),
+    };
+
+    let recording = RecordingMailer::default();
+    let mailers: [&dyn Mailer; 2] = [&LogMailer, &recording];
+
+    for mailer in mailers {
+        mailer.send(&email).await.unwrap();
+    }
+
+    assert_eq!(*recording.sent.lock().unwrap(), vec![email]);
+}
+
+#[tokio::test]
+async fn test_smtp_mailer_builds_messages() {
+    let mailer = SmtpMailer::new("smtp://localhost:2525", "Medici <medici@example.com>").unwrap();
+
+    let message = mailer
+        .message(&Email {
+            to: "alice@example.com".to_string(),
+            subject: "You owe $20.00 in Home".to_string(),
+            body: "Hi Alice".to_string(),
+        })
+        .unwrap();
+    let formatted = String::from_utf8(message.formatted()).unwrap();
+
+    assert!(formatted.contains("From: Medici <medici@example.com>\r\n"));
+    assert!(formatted.contains("To: alice@example.com\r\n"));
+    assert!(formatted.contains("Subject: You owe $20.00 in Home\r\n"));
+    assert!(formatted.ends_with("Hi Alice"));
+
+    assert!(
+        mailer
+            .message(&Email {
+                to: "not an address".to_string(),
+                subject: "Hello".to_string(),
+                body: String::new(),
+            })
+            .is_err()
+    );
+}
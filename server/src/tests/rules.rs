@@ -0,0 +1,372 @@
+use crate::models::{
+    Expense, ExpenseCategory, ExpenseCategoryRule, NewExpenseCategoryRule, RuleMatchType,
+    SplitMethod,
+};
+use crate::rules::{
+    RuleError, RuleSubject, changes_category, count_by_category, find_matching_rule,
+    plan_recategorization, validate_rule,
+};
+use crate::schema::expense_category_rule;
+use crate::tests::db;
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+const POOL: Uuid = Uuid::from_u128(10);
+
+fn rule(
+    pattern: &str,
+    match_type: RuleMatchType,
+    category: ExpenseCategory,
+) -> ExpenseCategoryRule {
+    let inserted_at = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+    ExpenseCategoryRule {
+        member_id: Uuid::from_u128(1),
+        rule: pattern.to_string(),
+        category,
+        inserted_at,
+        updated_at: inserted_at,
+        id: Uuid::new_v4(),
+        match_type,
+        min_amount: None,
+        max_amount: None,
+        pool_id: None,
+        priority: 0,
+        custom_category_id: None,
+    }
+}
+
+fn subject(name: &str, amount: f64) -> RuleSubject<'_> {
+    RuleSubject {
+        name,
+        amount,
+        pool_id: POOL,
+    }
+}
+
+fn category_for(rules: &[ExpenseCategoryRule], name: &str, amount: f64) -> Option<ExpenseCategory> {
+    find_matching_rule(rules, &subject(name, amount)).map(|rule| rule.category.clone())
+}
+
+#[test]
+fn test_match_types() {
+    let contains = vec![rule(
+        "trader joe",
+        RuleMatchType::Contains,
+        ExpenseCategory::Groceries,
+    )];
+    let exact = vec![rule(
+        "Netflix",
+        RuleMatchType::Exact,
+        ExpenseCategory::Subscriptions,
+    )];
+    let starts_with = vec![rule(
+        "uber",
+        RuleMatchType::StartsWith,
+        ExpenseCategory::Transportation,
+    )];
+    let regex = vec![rule(
+        r"^pg&e\b",
+        RuleMatchType::Regex,
+        ExpenseCategory::Utilities,
+    )];
+
+    assert_eq!(
+        category_for(&contains, "TRADER JOE'S #123", 20.0),
+        Some(ExpenseCategory::Groceries)
+    );
+    assert_eq!(
+        category_for(&exact, " netflix ", 15.0),
+        Some(ExpenseCategory::Subscriptions)
+    );
+    assert_eq!(category_for(&exact, "Netflix gift card", 15.0), None);
+    assert_eq!(
+        category_for(&starts_with, "Uber to airport", 30.0),
+        Some(ExpenseCategory::Transportation)
+    );
+    assert_eq!(category_for(&starts_with, "Lunch after uber", 30.0), None);
+    assert_eq!(
+        category_for(&regex, "PG&E October", 90.0),
+        Some(ExpenseCategory::Utilities)
+    );
+    assert_eq!(category_for(&regex, "My PG&E", 90.0), None);
+}
+
+#[test]
+fn test_amount_range_is_inclusive() {
+    let mut coffee = rule(
+        "starbucks",
+        RuleMatchType::Contains,
+        ExpenseCategory::FoodDining,
+    );
+    coffee.min_amount = Some(1.0);
+    coffee.max_amount = Some(10.0);
+    let rules = vec![coffee];
+
+    assert!(category_for(&rules, "Starbucks", 10.0).is_some());
+    assert!(category_for(&rules, "Starbucks", 1.0).is_some());
+    assert!(category_for(&rules, "Starbucks", 10.01).is_none());
+    assert!(category_for(&rules, "Starbucks", 0.5).is_none());
+}
+
+#[test]
+fn test_pool_scoped_rules_only_apply_in_their_pool() {
+    let mut scoped = rule(
+        "rent",
+        RuleMatchType::Contains,
+        ExpenseCategory::HousingRent,
+    );
+    scoped.pool_id = Some(Uuid::from_u128(99));
+    let rules = vec![scoped];
+
+    assert!(category_for(&rules, "Rent", 1000.0).is_none());
+
+    let elsewhere = RuleSubject {
+        name: "Rent",
+        amount: 1000.0,
+        pool_id: Uuid::from_u128(99),
+    };
+    assert!(find_matching_rule(&rules, &elsewhere).is_some());
+}
+
+#[test]
+fn test_precedence_priority_then_pool_scope_then_age() {
+    let generic = rule(
+        "uber",
+        RuleMatchType::Contains,
+        ExpenseCategory::Transportation,
+    );
+    let mut eats = rule(
+        "uber eats",
+        RuleMatchType::Contains,
+        ExpenseCategory::FoodDining,
+    );
+    eats.priority = 10;
+
+    assert_eq!(
+        category_for(&[generic.clone(), eats.clone()], "Uber Eats", 25.0),
+        Some(ExpenseCategory::FoodDining)
+    );
+
+    let mut travel = rule("uber", RuleMatchType::Contains, ExpenseCategory::Travel);
+    travel.pool_id = Some(POOL);
+    assert_eq!(
+        category_for(&[generic.clone(), travel], "Uber", 25.0),
+        Some(ExpenseCategory::Travel)
+    );
+
+    let mut newer = rule(
+        "uber",
+        RuleMatchType::Contains,
+        ExpenseCategory::BusinessExpenses,
+    );
+    newer.inserted_at = generic.inserted_at + Duration::days(1);
+    assert_eq!(
+        category_for(&[newer, generic], "Uber", 25.0),
+        Some(ExpenseCategory::Transportation)
+    );
+}
+
+#[test]
+fn test_invalid_regex_and_blank_names_never_match() {
+    let rules = vec![
+        rule("(unclosed", RuleMatchType::Regex, ExpenseCategory::Shopping),
+        rule("", RuleMatchType::Contains, ExpenseCategory::Miscellaneous),
+    ];
+
+    assert!(
+        category_for(&rules, "(unclosed", 5.0).is_some_and(|c| c == ExpenseCategory::Miscellaneous)
+    );
+    assert!(category_for(&rules, "   ", 5.0).is_none());
+    assert!(category_for(&[], "Anything", 5.0).is_none());
+}
+
+#[test]
+fn test_rules_without_a_match_type_are_contains_rules() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+
+    let new_rule: NewExpenseCategoryRule =
+        serde_json::from_value(serde_json::json!({"rule": "uber", "category": "Transportation"}))
+            .unwrap();
+    let created = ExpenseCategoryRule::create(&mut conn, &alice.id, &new_rule).unwrap();
+
+    assert_eq!(created.match_type, RuleMatchType::Contains);
+    assert!(category_for(&[created], "UBER EATS", 20.0).is_some());
+
+    let defaulted: RuleMatchType = diesel::insert_into(expense_category_rule::table)
+        .values((
+            expense_category_rule::member_id.eq(alice.id),
+            expense_category_rule::rule.eq("lyft"),
+            expense_category_rule::category.eq(ExpenseCategory::Transportation),
+        ))
+        .returning(expense_category_rule::match_type)
+        .get_result(&mut conn)
+        .unwrap();
+
+    assert_eq!(defaulted, RuleMatchType::Contains);
+
+    // What the web client sends, since its rules support regex syntax.
+    let new_rule: NewExpenseCategoryRule = serde_json::from_value(serde_json::json!({
+        "rule": "^uber( eats)?$",
+        "category": "Transportation",
+        "match_type": "Regex",
+    }))
+    .unwrap();
+    let created = ExpenseCategoryRule::create(&mut conn, &alice.id, &new_rule).unwrap();
+
+    assert_eq!(created.match_type, RuleMatchType::Regex);
+    assert!(category_for(&[created], "UBER EATS", 20.0).is_some());
+}
+
+#[test]
+fn test_validate_rule() {
+    let candidate = |pattern: &str, match_type, min_amount, max_amount| NewExpenseCategoryRule {
+        rule: pattern.to_string(),
+        category: ExpenseCategory::Shopping,
+        match_type,
+        min_amount,
+        max_amount,
+        pool_id: None,
+        priority: 0,
+        custom_category_id: None,
+    };
+
+    assert_eq!(
+        validate_rule(&candidate("amazon", RuleMatchType::Contains, None, None)),
+        Ok(())
+    );
+    assert_eq!(
+        validate_rule(&candidate("  ", RuleMatchType::Contains, None, None)),
+        Err(RuleError::EmptyPattern)
+    );
+    assert!(matches!(
+        validate_rule(&candidate("(amazon", RuleMatchType::Regex, None, None)),
+        Err(RuleError::InvalidRegex(_))
+    ));
+    assert_eq!(
+        validate_rule(&candidate("(amazon", RuleMatchType::Contains, None, None)),
+        Ok(())
+    );
+    assert_eq!(
+        validate_rule(&candidate(
+            "amazon",
+            RuleMatchType::Contains,
+            Some(20.0),
+            Some(10.0)
+        )),
+        Err(RuleError::InvalidAmountRange)
+    );
+}
+
+fn expense(name: &str, category: ExpenseCategory) -> Expense {
+    let now = Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap();
+
+    Expense {
+        id: Uuid::new_v4(),
+        name: name.to_string(),
+        amount: 12.0,
+        is_settled: false,
+        inserted_at: now,
+        updated_at: now,
+        pool_id: POOL,
+        paid_by_member_id: Uuid::from_u128(1),
+        description: None,
+        notes: None,
+        category,
+        split_method: SplitMethod::Default,
+        incurred_on: NaiveDate::from_ymd_opt(2026, 2, 1).unwrap(),
+        deleted_at: None,
+        custom_category_id: None,
+        categorized_by_rule: false,
+    }
+}
+
+#[test]
+fn test_plan_recategorization_skips_unmatched_and_unchanged_expenses() {
+    let rules = vec![
+        rule(
+            "trader joe",
+            RuleMatchType::Contains,
+            ExpenseCategory::Groceries,
+        ),
+        rule(
+            "uber",
+            RuleMatchType::StartsWith,
+            ExpenseCategory::Transportation,
+        ),
+    ];
+    let expenses = vec![
+        expense("Trader Joe's", ExpenseCategory::Miscellaneous),
+        expense("Trader Joe's", ExpenseCategory::Groceries),
+        expense("Uber", ExpenseCategory::FoodDining),
+        expense("Dentist", ExpenseCategory::Miscellaneous),
+    ];
+
+    let plan = plan_recategorization(&rules, &expenses);
+
+    let planned: Vec<(&str, ExpenseCategory)> = plan
+        .iter()
+        .map(|(expense, rule)| (expense.name.as_str(), rule.category.clone()))
+        .collect();
+
+    assert_eq!(
+        planned,
+        vec![
+            ("Trader Joe's", ExpenseCategory::Groceries),
+            ("Uber", ExpenseCategory::Transportation),
+        ]
+    );
+}
+
+#[test]
+fn test_plan_recategorization_moves_expenses_into_custom_category() {
+    let ski_trip = Uuid::from_u128(7);
+
+    let mut lift_rule = rule(
+        "lift ticket",
+        RuleMatchType::Contains,
+        ExpenseCategory::Travel,
+    );
+    lift_rule.pool_id = Some(POOL);
+    lift_rule.custom_category_id = Some(ski_trip);
+
+    let mut already_custom = expense("Lift ticket", ExpenseCategory::Travel);
+    already_custom.custom_category_id = Some(ski_trip);
+
+    let expenses = vec![
+        expense("Lift ticket", ExpenseCategory::Travel),
+        already_custom,
+    ];
+
+    let rules = [lift_rule];
+    let plan = plan_recategorization(&rules, &expenses);
+
+    assert_eq!(plan.len(), 1);
+    assert_eq!(plan[0].0.id, expenses[0].id);
+    assert!(!changes_category(
+        &ExpenseCategory::Travel,
+        Some(ski_trip),
+        &expenses[1]
+    ));
+}
+
+#[test]
+fn test_count_by_category_orders_by_count_then_category() {
+    let categories = [
+        ExpenseCategory::Transportation,
+        ExpenseCategory::Groceries,
+        ExpenseCategory::Transportation,
+        ExpenseCategory::FoodDining,
+    ];
+
+    assert_eq!(
+        count_by_category(&categories),
+        vec![
+            (ExpenseCategory::Transportation, 2),
+            (ExpenseCategory::FoodDining, 1),
+            (ExpenseCategory::Groceries, 1),
+        ]
+    );
+}
@@ -0,0 +1,49 @@
+use crate::search::{AmountRange, parse_search_query};
+
+#[test]
+fn test_parse_search_query_splits_amounts_and_terms() {
+    let parsed = parse_search_query("that $84 thing at Costco");
+
+    assert_eq!(parsed.terms, vec!["that", "thing", "at", "costco"]);
+    assert_eq!(
+        parsed.amounts,
+        vec![AmountRange {
+            min: 84.0,
+            max: 85.0
+        }]
+    );
+    assert_eq!(
+        parsed.tsquery().as_deref(),
+        Some("that:* | thing:* | at:* | costco:*")
+    );
+}
+
+#[test]
+fn test_parse_search_query_amount_formats() {
+    let parsed = parse_search_query("84.50 $1,200 route 66");
+
+    assert_eq!(parsed.terms, vec!["route", "66"]);
+    assert_eq!(parsed.amounts.len(), 2);
+    assert!(parsed.amounts[0].min < 84.5 && 84.5 < parsed.amounts[0].max);
+    assert!(parsed.amounts[0].max < 84.51);
+    assert_eq!(
+        parsed.amounts[1],
+        AmountRange {
+            min: 1200.0,
+            max: 1201.0
+        }
+    );
+}
+
+#[test]
+fn test_parse_search_query_strips_operators() {
+    let parsed = parse_search_query("Trader Joe's & (groceries) | !rent");
+
+    assert_eq!(
+        parsed.terms,
+        vec!["trader", "joe", "s", "groceries", "rent"]
+    );
+    assert!(parsed.amounts.is_empty());
+    assert!(parse_search_query("  ").is_empty());
+    assert!(parse_search_query("$").is_empty());
+}
@@ -0,0 +1,201 @@
+use uuid::Uuid;
+
+use crate::import::{ImportError, ImportLineItem};
+use crate::models::ExpenseCategory;
+use crate::splitwise::{
+    check_balances, find_duplicate_mapping, imported_balances, map_splitwise_category,
+    parse_splitwise_export, splitwise_expense_rows,
+};
+
+const EXPORT: &str = "Date,Description,Category,Cost,Currency,Alice Smith,Bob Jones,Carol White\n\
+                      \n\
+                      2026-08-01,Groceries,Groceries,90.00,USD,60.00,-30.00,-30.00\n\
+                      2026-08-03,Dinner,Dining out,100.00,USD,-25.00,-25.00,50.00\n\
+                      2026-08-05,Cabin,Hotel,300.00,USD,100.00,50.00,-150.00\n\
+                      2026-08-09,Bob Jones paid Alice Smith,Payment,20.00,USD,-20.00,20.00,0.00\n\
+                      \n\
+                      2026-08-31,Total balance, , ,USD,115.00,15.00,-130.00\n";
+
+fn member_ids() -> Vec<Uuid> {
+    vec![Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(3)]
+}
+
+fn line_item(n: u128, amount: f64) -> ImportLineItem {
+    ImportLineItem {
+        debtor_member_id: Uuid::from_u128(n),
+        amount,
+    }
+}
+
+#[test]
+fn test_map_splitwise_category() {
+    assert_eq!(
+        map_splitwise_category("Dining out"),
+        ExpenseCategory::FoodDining
+    );
+    assert_eq!(
+        map_splitwise_category("TV/Phone/Internet"),
+        ExpenseCategory::Utilities
+    );
+    assert_eq!(
+        map_splitwise_category("General"),
+        ExpenseCategory::Miscellaneous
+    );
+}
+
+#[test]
+fn test_parse_splitwise_export() {
+    let export = parse_splitwise_export(EXPORT).unwrap();
+
+    assert_eq!(
+        export.members,
+        vec!["Alice Smith", "Bob Jones", "Carol White"]
+    );
+    assert_eq!(export.expenses.len(), 3);
+    assert!(export.errors.is_empty());
+    assert_eq!(export.totals, Some(vec![115.0, 15.0, -130.0]));
+
+    assert_eq!(export.expenses[0].row, 3);
+    assert_eq!(export.expenses[0].cost, 90.0);
+    assert_eq!(export.expenses[0].balances, vec![60.0, -30.0, -30.0]);
+
+    assert_eq!(export.payments.len(), 1);
+    assert_eq!(export.payments[0].row, 6);
+    assert_eq!(export.payments[0].balances, vec![-20.0, 20.0, 0.0]);
+}
+
+#[test]
+fn test_parse_splitwise_export_reports_bad_rows() {
+    let export = parse_splitwise_export(
+        "Date,Description,Category,Cost,Currency,Alice,Bob\n\
+         2026-08-01,Snacks,General,6.00,USD,3.00,-3.00\n\
+         2026-08-02,Lunch,General,20.00,USD,10.00,-5.00\n\
+         2026-08-03,Taxi,Taxi,15.00,EUR,7.50,-7.50\n\
+         yesterday,Coffee,General,4.00,USD,2.00,-2.00\n",
+    )
+    .unwrap();
+
+    assert_eq!(export.expenses.len(), 1);
+    assert_eq!(
+        export
+            .errors
+            .iter()
+            .map(|error| (error.row, error.column.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![(3, None), (4, Some("Currency")), (5, Some("Date"))]
+    );
+
+    assert!(matches!(
+        parse_splitwise_export("Date,Description,Cost,Alice\n"),
+        Err(ImportError::Csv(_))
+    ));
+}
+
+#[test]
+fn test_splitwise_expense_rows_keep_exact_shares() {
+    let export = parse_splitwise_export(EXPORT).unwrap();
+    let member_ids = member_ids();
+
+    let groceries = splitwise_expense_rows(&export.expenses[0], &member_ids);
+    assert_eq!(groceries.len(), 1);
+    assert_eq!(groceries[0].paid_by_member_id, Uuid::from_u128(1));
+    assert_eq!(groceries[0].amount, 90.0);
+    assert_eq!(
+        groceries[0].category,
+        Some((ExpenseCategory::Groceries, None))
+    );
+    assert_eq!(
+        groceries[0].line_items,
+        vec![line_item(1, 30.0), line_item(2, 30.0), line_item(3, 30.0)]
+    );
+}
+
+#[test]
+fn test_splitwise_expense_rows_split_multiple_payers() {
+    let export = parse_splitwise_export(EXPORT).unwrap();
+
+    let cabin = splitwise_expense_rows(&export.expenses[2], &member_ids());
+
+    assert_eq!(cabin.len(), 2);
+    assert_eq!(cabin[0].paid_by_member_id, Uuid::from_u128(1));
+    assert_eq!(cabin[0].amount, 200.0);
+    assert_eq!(
+        cabin[0].line_items,
+        vec![line_item(1, 100.0), line_item(3, 100.0)]
+    );
+    assert_eq!(cabin[1].paid_by_member_id, Uuid::from_u128(2));
+    assert_eq!(cabin[1].amount, 100.0);
+    assert_eq!(
+        cabin[1].line_items,
+        vec![line_item(2, 50.0), line_item(3, 50.0)]
+    );
+
+    // Thirds that don't round evenly still add up to the cost.
+    let export = parse_splitwise_export(
+        "Date,Description,Category,Cost,Currency,Alice,Bob,Carol,Dan\n\
+         2026-08-01,Boat,General,100.00,USD,10.00,10.00,10.00,-30.00\n",
+    )
+    .unwrap();
+    let ids = vec![
+        Uuid::from_u128(1),
+        Uuid::from_u128(2),
+        Uuid::from_u128(3),
+        Uuid::from_u128(4),
+    ];
+    let boat = splitwise_expense_rows(&export.expenses[0], &ids);
+
+    assert_eq!(
+        boat.iter().map(|row| row.amount).collect::<Vec<_>>(),
+        vec![33.33, 33.33, 33.34]
+    );
+    for row in &boat {
+        let total: f64 = row.line_items.iter().map(|item| item.amount).sum();
+        assert!((total - row.amount).abs() < 1e-9);
+    }
+    assert_eq!(
+        imported_balances(&boat, &ids),
+        vec![10.0, 10.0, 10.0, -30.0]
+    );
+}
+
+#[test]
+fn test_check_balances_against_export_totals() {
+    let export = parse_splitwise_export(EXPORT).unwrap();
+    let member_ids = member_ids();
+
+    let rows: Vec<_> = export
+        .expenses
+        .iter()
+        .flat_map(|expense| splitwise_expense_rows(expense, &member_ids))
+        .collect();
+
+    // The totals less Bob's $20 payment to Alice, which isn't imported.
+    assert_eq!(
+        imported_balances(&rows, &member_ids),
+        vec![135.0, -5.0, -130.0]
+    );
+
+    let checks = check_balances(&export, &rows, &member_ids);
+    assert!(checks.iter().all(|check| check.matches));
+    assert_eq!(checks[0].expected, 135.0);
+    assert_eq!(checks[2].name, "Carol White");
+    assert_eq!(checks[2].expected, -130.0);
+
+    let mismatched = check_balances(&export, &rows[1..], &member_ids);
+    assert!(!mismatched[0].matches);
+}
+
+#[test]
+fn test_find_duplicate_mapping() {
+    let alice = Uuid::from_u128(1);
+    let bob = Uuid::from_u128(2);
+
+    assert_eq!(
+        find_duplicate_mapping([("Alice", alice), ("Bob", bob)]),
+        None
+    );
+    assert_eq!(
+        find_duplicate_mapping([("Alice", alice), ("Bob", bob), ("Ali", alice)]),
+        Some(("Alice", "Ali"))
+    );
+}
@@ -0,0 +1,214 @@
+use crate::models::{
+    CategoryNameCount, Expense, ExpenseCategory, ExpenseCategoryRule, Member, RuleMatchType,
+    SplitMethod,
+};
+use crate::schema::expense;
+use crate::suggestions::{CategorySuggester, propose_rules, tokenize};
+use crate::tests::db;
+use chrono::{NaiveDate, TimeZone, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+fn expense(name: &str, category: ExpenseCategory) -> Expense {
+    let now = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+
+    Expense {
+        id: Uuid::new_v4(),
+        name: name.to_string(),
+        amount: 25.0,
+        is_settled: false,
+        inserted_at: now,
+        updated_at: now,
+        pool_id: Uuid::from_u128(10),
+        paid_by_member_id: Uuid::from_u128(1),
+        description: None,
+        notes: None,
+        category,
+        split_method: SplitMethod::Default,
+        incurred_on: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+        deleted_at: None,
+        custom_category_id: None,
+        categorized_by_rule: false,
+    }
+}
+
+#[test]
+fn test_tokenize_drops_noise() {
+    assert_eq!(tokenize("Trader Joe's #552 - 03/01"), vec!["trader", "joe"]);
+    assert_eq!(tokenize("PG&E"), vec!["pg"]);
+    assert!(tokenize("  ").is_empty());
+}
+
+#[test]
+fn test_suggests_category_from_shared_tokens() {
+    let mut suggester = CategorySuggester::new();
+    suggester.observe("Trader Joe's", &ExpenseCategory::Groceries, 1.0);
+    suggester.observe("Whole Foods Market", &ExpenseCategory::Groceries, 1.0);
+    suggester.observe("Uber to airport", &ExpenseCategory::Transportation, 1.0);
+    suggester.observe("Uber", &ExpenseCategory::Transportation, 1.0);
+
+    let suggestions = suggester.suggest("uber home", 3);
+
+    assert_eq!(suggestions[0].category, ExpenseCategory::Transportation);
+    assert!(suggestions[0].score > 0.5);
+
+    let total: f64 = suggester
+        .suggest("trader joe", 10)
+        .iter()
+        .map(|s| s.score)
+        .sum();
+    assert!((total - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_unknown_names_get_no_suggestions() {
+    let mut suggester = CategorySuggester::new();
+    suggester.observe("Trader Joe's", &ExpenseCategory::Groceries, 1.0);
+
+    assert!(suggester.suggest("Dentist", 3).is_empty());
+    assert!(
+        CategorySuggester::new()
+            .suggest("Trader Joe's", 3)
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_weights_and_exact_names_break_ties() {
+    let mut suggester = CategorySuggester::new();
+    suggester.observe("Costco", &ExpenseCategory::Groceries, 1.0);
+    suggester.observe("Costco", &ExpenseCategory::HomeHouseholdSupplies, 2.0);
+
+    let suggestions = suggester.suggest("costco", 2);
+
+    assert_eq!(
+        suggestions[0].category,
+        ExpenseCategory::HomeHouseholdSupplies
+    );
+    assert_eq!(suggestions.len(), 2);
+}
+
+#[test]
+fn test_proposes_rules_for_consistent_uncovered_names() {
+    let history = vec![
+        expense("PG&E", ExpenseCategory::Utilities),
+        expense("pg&e ", ExpenseCategory::Utilities),
+        expense("PG&E", ExpenseCategory::Utilities),
+        expense("Costco", ExpenseCategory::Groceries),
+        expense("Costco", ExpenseCategory::Groceries),
+        expense("Costco", ExpenseCategory::HomeHouseholdSupplies),
+        expense("Netflix", ExpenseCategory::Subscriptions),
+        expense("Netflix", ExpenseCategory::Subscriptions),
+        expense("Netflix", ExpenseCategory::Subscriptions),
+        expense("Lunch", ExpenseCategory::FoodDining),
+    ];
+
+    let netflix_rule = ExpenseCategoryRule {
+        member_id: Uuid::from_u128(1),
+        rule: "netflix".to_string(),
+        category: ExpenseCategory::Subscriptions,
+        inserted_at: Utc::now(),
+        updated_at: Utc::now(),
+        id: Uuid::new_v4(),
+        match_type: RuleMatchType::Contains,
+        min_amount: None,
+        max_amount: None,
+        pool_id: None,
+        priority: 0,
+        custom_category_id: None,
+    };
+
+    let proposals = propose_rules(&history, &[netflix_rule], 3);
+
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0].rule.rule, "PG&E");
+    assert_eq!(proposals[0].rule.match_type, RuleMatchType::Exact);
+    assert_eq!(proposals[0].rule.category, ExpenseCategory::Utilities);
+    assert_eq!(proposals[0].occurrences, 3);
+}
+
+#[test]
+fn test_training_on_counts_matches_observing_each_expense() {
+    let count = |name: &str, category, is_own, occurrences| CategoryNameCount {
+        name: name.to_string(),
+        category,
+        is_own,
+        occurrences,
+    };
+
+    let trained = CategorySuggester::from_history(&[
+        count("Costco", ExpenseCategory::Groceries, false, 3),
+        count("Costco", ExpenseCategory::HomeHouseholdSupplies, true, 1),
+    ]);
+
+    let mut observed = CategorySuggester::new();
+    for _ in 0..3 {
+        observed.observe("Costco", &ExpenseCategory::Groceries, 1.0);
+    }
+    observed.observe("Costco", &ExpenseCategory::HomeHouseholdSupplies, 2.0);
+
+    assert_eq!(trained.suggest("costco", 2), observed.suggest("costco", 2));
+    assert_eq!(
+        trained.suggest("costco", 1)[0].category,
+        ExpenseCategory::Groceries
+    );
+}
+
+#[test]
+fn test_history_is_counted_by_name_without_rule_categorized_expenses() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let bob = db::member(&mut conn, "Bob");
+    let pool = db::pool(&mut conn, &[&alice, &bob]);
+
+    let mut add = |paid_by: &Member, name: &str, category, categorized_by_rule| {
+        let (created, _) = db::expense(&mut conn, &pool, paid_by, name, None, &[(&alice, 10.0)]);
+        diesel::update(expense::table.filter(expense::id.eq(created.id)))
+            .set((
+                expense::category.eq(category),
+                expense::categorized_by_rule.eq(categorized_by_rule),
+            ))
+            .execute(&mut conn)
+            .unwrap();
+    };
+
+    add(&alice, "Trader Joe's", ExpenseCategory::Groceries, false);
+    add(&alice, "trader  joe's ", ExpenseCategory::Groceries, false);
+    add(&bob, "Trader Joe's", ExpenseCategory::Groceries, false);
+    add(&alice, "Trader Joe's", ExpenseCategory::Shopping, true);
+    add(&alice, "Uber", ExpenseCategory::Transportation, true);
+
+    let mut counts = Expense::count_categories_by_name(&mut conn, alice.id, Some(pool.id), 100)
+        .unwrap()
+        .into_iter()
+        .map(|count| (count.category, count.is_own, count.occurrences))
+        .collect::<Vec<_>>();
+    counts.sort_by_key(|(_, is_own, _)| *is_own);
+
+    assert_eq!(
+        counts,
+        vec![
+            (ExpenseCategory::Groceries, false, 1),
+            (ExpenseCategory::Groceries, true, 2),
+        ]
+    );
+
+    // Without the pool, only what Alice paid for herself counts.
+    assert_eq!(
+        Expense::count_categories_by_name(&mut conn, alice.id, None, 100)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let history: Vec<ExpenseCategory> = Expense::find_category_history(&mut conn, alice.id, 100)
+        .unwrap()
+        .into_iter()
+        .map(|expense| expense.category)
+        .collect();
+
+    assert_eq!(
+        history,
+        vec![ExpenseCategory::Groceries, ExpenseCategory::Groceries]
+    );
+}
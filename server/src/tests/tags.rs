@@ -0,0 +1,79 @@
+use crate::models::Tag;
+use crate::tags::{TagError, normalize_tag, normalize_tags, parse_tag_filter};
+use crate::tests::db;
+
+#[test]
+fn test_normalize_tag_collapses_whitespace() {
+    assert_eq!(
+        normalize_tag("  bachelor \t  party "),
+        Ok("bachelor party".to_string())
+    );
+    assert_eq!(normalize_tag("   "), Err(TagError::EmptyTag));
+    assert_eq!(normalize_tag(&"q".repeat(51)), Err(TagError::TagTooLong));
+}
+
+#[test]
+fn test_normalize_tags_drops_case_insensitive_duplicates() {
+    let tags = vec![
+        "Q3".to_string(),
+        "reimbursable by work".to_string(),
+        "q3".to_string(),
+        " Reimbursable  by work".to_string(),
+    ];
+
+    assert_eq!(
+        normalize_tags(&tags),
+        Ok(vec!["Q3".to_string(), "reimbursable by work".to_string()])
+    );
+
+    let too_many: Vec<String> = (0..21).map(|i| format!("tag {i}")).collect();
+    assert_eq!(normalize_tags(&too_many), Err(TagError::TooManyTags));
+}
+
+#[test]
+fn test_parse_tag_filter() {
+    assert_eq!(
+        parse_tag_filter("q3, bachelor  party,,Q3, "),
+        vec!["q3".to_string(), "bachelor party".to_string()]
+    );
+    assert!(parse_tag_filter(" , ").is_empty());
+}
+
+#[test]
+fn test_find_or_create_reuses_existing_tags_case_insensitively() {
+    let mut conn = db::connection();
+    let alice = db::member(&mut conn, "Alice");
+    let pool = db::pool(&mut conn, &[&alice]);
+    let other_pool = db::pool(&mut conn, &[&alice]);
+
+    let names = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let [q3] = Tag::find_or_create(&mut conn, pool.id, &names(&["Q3"]))
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    // "q3" is the tag that already exists, and "Work" is new. Both come back
+    // in the order they were asked for.
+    let tags = Tag::find_or_create(&mut conn, pool.id, &names(&["Work", "q3"])).unwrap();
+
+    assert_eq!(tags.len(), 2);
+    assert_eq!(tags[0].name, "Work");
+    assert_eq!(tags[1].id, q3.id);
+    assert_eq!(tags[1].name, "Q3");
+
+    // Tags belong to their pool.
+    let elsewhere = Tag::find_or_create(&mut conn, other_pool.id, &names(&["Q3"])).unwrap();
+    assert_ne!(elsewhere[0].id, q3.id);
+
+    assert!(
+        Tag::find_or_create(&mut conn, pool.id, &[])
+            .unwrap()
+            .is_empty()
+    );
+}
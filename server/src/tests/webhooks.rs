@@ -0,0 +1,353 @@
+use axum::Router;
+use std::net::IpAddr;
+
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::routing::post;
+use chrono::{Duration, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::models::WebhookEvent;
+use crate::webhooks::{
+    AllowedHosts, DELIVERY_HEADER, DeliveryOutcome, EVENT_HEADER, MAX_ATTEMPTS, SIGNATURE_HEADER,
+    WebhookError, deliver, generate_secret, is_public_address, retry_delay, sign_payload,
+    validate_events, validate_webhook_url, webhook_client,
+};
+
+/// The test receivers listen on loopback, which deliveries otherwise refuse.
+fn local_hosts() -> AllowedHosts {
+    AllowedHosts::new(["127.0.0.1", "localhost"])
+}
+
+fn client(allowed_hosts: &AllowedHosts) -> reqwest::Client {
+    webhook_client(std::time::Duration::from_secs(5), allowed_hosts).unwrap()
+}
+
+/// Starts a receiver on a free local port that answers every POST with
+/// `status` and passes what it got back through the channel.
+async fn receiver(status: StatusCode) -> (String, mpsc::UnboundedReceiver<(HeaderMap, String)>) {
+    let (sender, received) = mpsc::unbounded_channel();
+
+    let app = Router::new().route(
+        "/hook",
+        post(move |headers: HeaderMap, body: String| {
+            let sender = sender.clone();
+            async move {
+                sender.send((headers, body)).unwrap();
+                (status, "receiver says no")
+            }
+        }),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    (url, received)
+}
+
+fn verify(secret: &str, signature: &str, body: &str) -> bool {
+    let (timestamp, expected) = signature
+        .strip_prefix("t=")
+        .and_then(|rest| rest.split_once(",v1="))
+        .unwrap();
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    mac.verify_slice(&hex::decode(expected).unwrap()).is_ok()
+}
+
+#[test]
+fn test_sign_payload() {
+    let timestamp = Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+    let signature = sign_payload("whsec_test", timestamp, "{\"a\":1}");
+
+    assert!(signature.starts_with(&format!("t={},v1=", timestamp.timestamp())));
+    assert_eq!(signature.len(), "t=1792324800,v1=".len() + 64);
+    assert!(verify("whsec_test", &signature, "{\"a\":1}"));
+    assert!(!verify("whsec_test", &signature, "{\"a\":2}"));
+    assert!(!verify("whsec_other", &signature, "{\"a\":1}"));
+}
+
+#[test]
+fn test_retry_delay_backs_off_exponentially() {
+    assert_eq!(retry_delay(1), Some(Duration::seconds(30)));
+    assert_eq!(retry_delay(2), Some(Duration::seconds(60)));
+    assert_eq!(retry_delay(3), Some(Duration::seconds(120)));
+    assert_eq!(retry_delay(7), Some(Duration::seconds(1920)));
+    assert_eq!(retry_delay(MAX_ATTEMPTS - 1), Some(Duration::hours(1)));
+    assert_eq!(retry_delay(MAX_ATTEMPTS), None);
+}
+
+#[tokio::test]
+async fn test_validate_webhook_url() {
+    let none = AllowedHosts::default();
+
+    assert_eq!(
+        validate_webhook_url(" https://93.184.215.14/hooks ", &none).await,
+        Ok("https://93.184.215.14/hooks".to_string())
+    );
+    assert_eq!(
+        validate_webhook_url("ftp://93.184.215.14", &none).await,
+        Err(WebhookError::InvalidUrl)
+    );
+    assert_eq!(
+        validate_webhook_url("example.com/hooks", &none).await,
+        Err(WebhookError::InvalidUrl)
+    );
+    assert_eq!(
+        validate_webhook_url("http://host.invalid/hooks", &none).await,
+        Err(WebhookError::UnresolvableHost)
+    );
+
+    for url in [
+        "http://192.168.1.10:8123/api/webhook/medici",
+        "http://127.0.0.1/hooks",
+        "http://169.254.169.254/latest/meta-data",
+        "http://[::1]/hooks",
+        "http://[::ffff:10.0.0.1]/hooks",
+        "http://localhost/hooks",
+    ] {
+        assert_eq!(
+            validate_webhook_url(url, &none).await,
+            Err(WebhookError::PrivateAddress),
+            "{}",
+            url
+        );
+    }
+
+    // Operators can let webhooks reach hosts on their own network.
+    let allowed = AllowedHosts::new([" 192.168.1.10", "LOCALHOST", "::1"]);
+    assert!(
+        validate_webhook_url("http://192.168.1.10:8123/api/webhook/medici", &allowed)
+            .await
+            .is_ok()
+    );
+    assert!(
+        validate_webhook_url("http://localhost/hooks", &allowed)
+            .await
+            .is_ok()
+    );
+    assert!(
+        validate_webhook_url("http://[::1]/hooks", &allowed)
+            .await
+            .is_ok()
+    );
+    assert_eq!(
+        validate_webhook_url("http://192.168.1.11/hooks", &allowed).await,
+        Err(WebhookError::PrivateAddress)
+    );
+}
+
+#[test]
+fn test_is_public_address() {
+    let public = |ip: &str| is_public_address(ip.parse::<IpAddr>().unwrap());
+
+    assert!(public("93.184.215.14"));
+    assert!(public("2606:2800:21f:cb07:6820:80da:af6b:8b2c"));
+
+    for ip in [
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.0.1",
+        "127.0.0.1",
+        "169.254.169.254",
+        "100.64.0.1",
+        "0.0.0.0",
+        "255.255.255.255",
+        "224.0.0.1",
+        "::",
+        "::1",
+        "fc00::1",
+        "fe80::1",
+        "::ffff:127.0.0.1",
+    ] {
+        assert!(!public(ip), "{}", ip);
+    }
+}
+
+#[test]
+fn test_validate_events() {
+    assert_eq!(
+        validate_events(&[
+            WebhookEvent::MemberJoined,
+            WebhookEvent::ExpenseCreated,
+            WebhookEvent::MemberJoined,
+        ]),
+        Ok(vec![
+            WebhookEvent::MemberJoined,
+            WebhookEvent::ExpenseCreated
+        ])
+    );
+    assert_eq!(validate_events(&[]), Err(WebhookError::NoEvents));
+}
+
+#[test]
+fn test_generate_secret() {
+    let secret = generate_secret();
+
+    assert!(secret.starts_with("whsec_"));
+    assert_eq!(secret.len(), "whsec_".len() + 64);
+    assert_ne!(secret, generate_secret());
+}
+
+#[tokio::test]
+async fn test_deliver_sends_a_signed_payload() {
+    let (url, mut received) = receiver(StatusCode::NO_CONTENT).await;
+    let delivery_id = Uuid::new_v4();
+    let payload = serde_json::json!({"id": delivery_id, "event": "expense.created"});
+
+    let outcome = deliver(
+        &client(&local_hosts()),
+        &local_hosts(),
+        &url,
+        "whsec_test",
+        delivery_id,
+        WebhookEvent::ExpenseCreated,
+        &payload,
+    )
+    .await;
+
+    assert_eq!(outcome, DeliveryOutcome::Delivered { status: 204 });
+
+    let (headers, body) = received.recv().await.unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+        payload
+    );
+    assert_eq!(headers[EVENT_HEADER], "expense.created");
+    assert_eq!(headers[DELIVERY_HEADER], delivery_id.to_string());
+    assert_eq!(headers["content-type"], "application/json");
+    assert!(verify(
+        "whsec_test",
+        headers[SIGNATURE_HEADER].to_str().unwrap(),
+        &body
+    ));
+}
+
+#[tokio::test]
+async fn test_deliver_reports_failures() {
+    let (url, _received) = receiver(StatusCode::INTERNAL_SERVER_ERROR).await;
+
+    let outcome = deliver(
+        &client(&local_hosts()),
+        &local_hosts(),
+        &url,
+        "whsec_test",
+        Uuid::new_v4(),
+        WebhookEvent::MemberJoined,
+        &serde_json::json!({}),
+    )
+    .await;
+
+    assert_eq!(
+        outcome,
+        DeliveryOutcome::Failed {
+            status: Some(500),
+            error: "Receiver responded with 500 Internal Server Error".to_string(),
+        }
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed = format!("http://{}/hook", listener.local_addr().unwrap());
+    drop(listener);
+
+    let outcome = deliver(
+        &client(&local_hosts()),
+        &local_hosts(),
+        &closed,
+        "whsec_test",
+        Uuid::new_v4(),
+        WebhookEvent::MemberJoined,
+        &serde_json::json!({}),
+    )
+    .await;
+
+    assert!(matches!(
+        outcome,
+        DeliveryOutcome::Failed { status: None, .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_deliver_refuses_private_addresses() {
+    let (url, mut received) = receiver(StatusCode::NO_CONTENT).await;
+    let none = AllowedHosts::default();
+
+    let outcome = deliver(
+        &client(&none),
+        &none,
+        &url,
+        "whsec_test",
+        Uuid::new_v4(),
+        WebhookEvent::MemberJoined,
+        &serde_json::json!({}),
+    )
+    .await;
+
+    assert_eq!(
+        outcome,
+        DeliveryOutcome::Failed {
+            status: None,
+            error: WebhookError::PrivateAddress.to_string(),
+        }
+    );
+
+    // Names are checked when they're resolved, in case they've been pointed
+    // somewhere private since the webhook was added.
+    let by_name = url.replace("127.0.0.1", "localhost");
+    let outcome = deliver(
+        &client(&none),
+        &none,
+        &by_name,
+        "whsec_test",
+        Uuid::new_v4(),
+        WebhookEvent::MemberJoined,
+        &serde_json::json!({}),
+    )
+    .await;
+
+    assert!(matches!(
+        outcome,
+        DeliveryOutcome::Failed { status: None, .. }
+    ));
+    assert!(received.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_deliver_does_not_follow_redirects() {
+    let (target, mut received) = receiver(StatusCode::NO_CONTENT).await;
+
+    let app = Router::new().route(
+        "/hook",
+        post(move || {
+            let target = target.clone();
+            async move { (StatusCode::FOUND, [(header::LOCATION, target)]) }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let outcome = deliver(
+        &client(&local_hosts()),
+        &local_hosts(),
+        &url,
+        "whsec_test",
+        Uuid::new_v4(),
+        WebhookEvent::MemberJoined,
+        &serde_json::json!({}),
+    )
+    .await;
+
+    assert_eq!(
+        outcome,
+        DeliveryOutcome::Failed {
+            status: Some(302),
+            error: "Receiver responded with 302 Found".to_string(),
+        }
+    );
+    assert!(received.try_recv().is_err());
+}
@@ -0,0 +1,308 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect;
+use sha2::Sha256;
+
+use crate::models::WebhookEvent;
+
+/// Deliveries that still fail after this many attempts are given up on.
+pub const MAX_ATTEMPTS: i32 = 10;
+
+/// The wait before the first retry. Each retry after that waits twice as
+/// long as the one before, up to `MAX_RETRY_DELAY_SECONDS`.
+const BASE_RETRY_DELAY_SECONDS: i64 = 30;
+const MAX_RETRY_DELAY_SECONDS: i64 = 60 * 60;
+
+/// Errors are recorded in the delivery log cut down to this many
+/// characters.
+const MAX_ERROR_LENGTH: usize = 500;
+
+pub const SIGNATURE_HEADER: &str = "X-Medici-Signature";
+pub const EVENT_HEADER: &str = "X-Medici-Event";
+pub const DELIVERY_HEADER: &str = "X-Medici-Delivery";
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum WebhookError {
+    #[error("webhook URL must be an absolute http or https URL")]
+    InvalidUrl,
+    #[error("webhook must subscribe to at least one event")]
+    NoEvents,
+    #[error("webhook host could not be resolved")]
+    UnresolvableHost,
+    #[error("webhook URL must not point at a private, loopback or link-local address")]
+    PrivateAddress,
+}
+
+/// Hosts an operator lets webhooks reach even though they're on a private
+/// network, from the comma-separated `WEBHOOK_ALLOWED_HOSTS`. Self-hosters
+/// often point webhooks at other services of their own.
+#[derive(Debug, Clone, Default)]
+pub struct AllowedHosts(Vec<String>);
+
+impl AllowedHosts {
+    pub fn new<S: AsRef<str>>(hosts: impl IntoIterator<Item = S>) -> Self {
+        AllowedHosts(
+            hosts
+                .into_iter()
+                .map(|host| host.as_ref().trim().trim_matches(['[', ']']).to_lowercase())
+                .filter(|host| !host.is_empty())
+                .collect(),
+        )
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("WEBHOOK_ALLOWED_HOSTS")
+                .unwrap_or_default()
+                .split(','),
+        )
+    }
+
+    pub fn contains(&self, host: &str) -> bool {
+        let host = host.trim_matches(['[', ']']);
+        self.0
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    }
+}
+
+/// Whether an address is on the public internet, as opposed to this
+/// machine, a private network or cloud metadata services like
+/// 169.254.169.254.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let is_shared = ip.octets()[0] == 100 && (ip.octets()[1] & 0b1100_0000) == 64;
+
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || is_shared
+                || ip.octets()[0] == 0)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_address(IpAddr::V4(ip)),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+/// The host's IP if the URL names one directly rather than by name.
+fn literal_address(url: &reqwest::Url) -> Option<IpAddr> {
+    url.host_str()?.trim_matches(['[', ']']).parse().ok()
+}
+
+/// Trims the URL and checks it's something we can POST to. Hosts that
+/// resolve to a private, loopback or link-local address are refused unless
+/// they're in `allowed_hosts`. Deliveries check again when they connect, in
+/// case the host's DNS has changed since.
+pub async fn validate_webhook_url(
+    url: &str,
+    allowed_hosts: &AllowedHosts,
+) -> Result<String, WebhookError> {
+    let url = url.trim();
+    let parsed = reqwest::Url::parse(url).map_err(|_| WebhookError::InvalidUrl)?;
+
+    let Some(host) = parsed.host_str() else {
+        return Err(WebhookError::InvalidUrl);
+    };
+
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(WebhookError::InvalidUrl);
+    }
+
+    if allowed_hosts.contains(host) {
+        return Ok(url.to_string());
+    }
+
+    let addresses: Vec<IpAddr> = match literal_address(&parsed) {
+        Some(ip) => vec![ip],
+        None => tokio::net::lookup_host((host, 0))
+            .await
+            .map_err(|_| WebhookError::UnresolvableHost)?
+            .map(|address| address.ip())
+            .collect(),
+    };
+
+    if addresses.is_empty() {
+        return Err(WebhookError::UnresolvableHost);
+    }
+
+    if !addresses.into_iter().all(is_public_address) {
+        return Err(WebhookError::PrivateAddress);
+    }
+
+    Ok(url.to_string())
+}
+
+/// Resolves webhook hosts to their public addresses only, so a host can't be
+/// pointed at a private address after its webhook was validated.
+struct PublicResolver {
+    allowed_hosts: AllowedHosts,
+}
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let is_allowed = self.allowed_hosts.contains(name.as_str());
+
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_allowed || is_public_address(address.ip()))
+                .collect();
+
+            if addresses.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// The client webhooks are delivered with. It doesn't follow redirects,
+/// which could lead anywhere, and only connects to public addresses unless
+/// the host is allowed.
+pub fn webhook_client(
+    timeout: std::time::Duration,
+    allowed_hosts: &AllowedHosts,
+) -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver {
+            allowed_hosts: allowed_hosts.clone(),
+        }))
+        .build()
+}
+
+/// Drops duplicates, keeping the order events were listed in.
+pub fn validate_events(events: &[WebhookEvent]) -> Result<Vec<WebhookEvent>, WebhookError> {
+    let mut unique = Vec::new();
+
+    for event in events {
+        if !unique.contains(event) {
+            unique.push(*event);
+        }
+    }
+
+    if unique.is_empty() {
+        Err(WebhookError::NoEvents)
+    } else {
+        Ok(unique)
+    }
+}
+
+/// A fresh signing secret, shown to the user once when the webhook is made.
+pub fn generate_secret() -> String {
+    format!(
+        "whsec_{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// The `X-Medici-Signature` header for `body` sent at `timestamp`. The
+/// signature is the hex HMAC-SHA256 of `"{timestamp}.{body}"` keyed with the
+/// webhook's secret; receivers should recompute it and reject stale
+/// timestamps to guard against replays.
+pub fn sign_payload(secret: &str, timestamp: DateTime<Utc>, body: &str) -> String {
+    let timestamp = timestamp.timestamp();
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+
+    format!(
+        "t={},v1={}",
+        timestamp,
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+/// How long to wait before the next attempt once `attempts` attempts have
+/// failed, or `None` if the delivery should be given up on.
+pub fn retry_delay(attempts: i32) -> Option<Duration> {
+    if attempts >= MAX_ATTEMPTS {
+        return None;
+    }
+
+    let exponent = attempts.saturating_sub(1).clamp(0, 30) as u32;
+    let seconds = BASE_RETRY_DELAY_SECONDS
+        .saturating_mul(2_i64.saturating_pow(exponent))
+        .min(MAX_RETRY_DELAY_SECONDS);
+
+    Some(Duration::seconds(seconds))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DeliveryOutcome {
+    Delivered { status: u16 },
+    Failed { status: Option<u16>, error: String },
+}
+
+/// POSTs a signed payload to the webhook. Any 2xx response counts as
+/// delivered; everything else, including timeouts and redirects, is a
+/// failure to retry. Only the status is kept from the receiver's response.
+pub async fn deliver(
+    client: &reqwest::Client,
+    allowed_hosts: &AllowedHosts,
+    url: &str,
+    secret: &str,
+    delivery_id: uuid::Uuid,
+    event: WebhookEvent,
+    payload: &serde_json::Value,
+) -> DeliveryOutcome {
+    // Hosts given as an IP aren't resolved, so the client's resolver never
+    // sees them.
+    if let Ok(parsed) = reqwest::Url::parse(url)
+        && let Some(ip) = literal_address(&parsed)
+        && !is_public_address(ip)
+        && !parsed
+            .host_str()
+            .is_some_and(|host| allowed_hosts.contains(host))
+    {
+        return DeliveryOutcome::Failed {
+            status: None,
+            error: WebhookError::PrivateAddress.to_string(),
+        };
+    }
+
+    let body = payload.to_string();
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, sign_payload(secret, Utc::now(), &body))
+        .header(EVENT_HEADER, event.as_str())
+        .header(DELIVERY_HEADER, delivery_id.to_string())
+        .body(body)
+        .send()
+        .await;
+
+    match response {
+        Ok(response) if response.status().is_success() => DeliveryOutcome::Delivered {
+            status: response.status().as_u16(),
+        },
+        Ok(response) => DeliveryOutcome::Failed {
+            status: Some(response.status().as_u16()),
+            error: format!("Receiver responded with {}", response.status()),
+        },
+        Err(e) => DeliveryOutcome::Failed {
+            status: None,
+            error: e.to_string().chars().take(MAX_ERROR_LENGTH).collect(),
+        },
+    }
+}